@@ -0,0 +1,120 @@
+use core::fmt;
+
+use rand::Rng;
+
+use crate::result::Result;
+
+// Win rate and standard deviation per hand, in the same unit as the bankroll
+// (e.g. big blinds), for example the EV of a spot from the equity engine.
+#[derive(Debug, Clone, Copy)]
+pub struct Bankroll {
+    pub ev: f64,
+    pub std_dev: f64,
+    pub bankroll: f64,
+}
+
+pub struct BankrollSimulation {
+    pub trials: u64,
+    pub hands: u64,
+    pub risk_of_ruin: f64,
+    pub mean_final: f64,
+    // Largest drop from a previous high of every trial, sorted ascending.
+    pub downswings: Vec<f64>,
+}
+
+impl fmt::Display for BankrollSimulation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "risk-of-ruin={:2.2} mean-final={:.2} downswing-50={:.2} downswing-90={:.2} downswing-99={:.2}",
+            self.risk_of_ruin * 100.0,
+            self.mean_final,
+            self.downswing_percentile(0.5),
+            self.downswing_percentile(0.9),
+            self.downswing_percentile(0.99),
+        )
+    }
+}
+
+impl BankrollSimulation {
+    // Downswing not exceeded by the given share of trials.
+    pub fn downswing_percentile(&self, p: f64) -> f64 {
+        if self.downswings.is_empty() {
+            return 0.0;
+        }
+        let index = ((self.downswings.len() - 1) as f64 * p).round() as usize;
+        self.downswings[index.min(self.downswings.len() - 1)]
+    }
+}
+
+impl Bankroll {
+    pub fn new(ev: f64, std_dev: f64, bankroll: f64) -> Result<Self> {
+        if !(ev.is_finite() && std_dev.is_finite() && std_dev >= 0.0 && bankroll.is_finite() && bankroll > 0.0) {
+            return Err("bankroll failed: invalid ev, std dev or bankroll".into());
+        }
+        Ok(Self { ev, std_dev, bankroll })
+    }
+
+    // Risk of ruin over an unlimited number of hands,
+    // approximating the results as a random walk with drift.
+    pub fn risk_of_ruin(&self) -> f64 {
+        if self.ev <= 0.0 {
+            1.0
+        } else if self.std_dev == 0.0 {
+            0.0
+        } else {
+            (-2.0 * self.ev * self.bankroll / (self.std_dev * self.std_dev)).exp()
+        }
+    }
+
+    // Bankroll needed for the given risk of ruin.
+    pub fn required_bankroll(&self, risk_of_ruin: f64) -> Option<f64> {
+        if self.ev <= 0.0 || !(risk_of_ruin > 0.0 && risk_of_ruin < 1.0) {
+            return None;
+        }
+        Some(-(self.std_dev * self.std_dev) * risk_of_ruin.ln() / (2.0 * self.ev))
+    }
+
+    // Every trial plays the given number of hands with normally distributed results,
+    // a trial is ruined once the bankroll reaches zero.
+    pub fn simulate(&self, hands: u64, trials: u64, rng: &mut impl Rng) -> Option<BankrollSimulation> {
+        if hands == 0 || trials == 0 {
+            return None;
+        }
+        let mut ruined = 0u64;
+        let mut final_sum = 0.0;
+        let mut downswings = Vec::with_capacity(usize::try_from(trials).ok()?);
+        for _ in 0..trials {
+            let mut bankroll = self.bankroll;
+            let mut high = bankroll;
+            let mut downswing: f64 = 0.0;
+            for _ in 0..hands {
+                bankroll += self.ev + self.std_dev * standard_normal(rng);
+                high = high.max(bankroll);
+                downswing = downswing.max(high - bankroll);
+                if bankroll <= 0.0 {
+                    ruined += 1;
+                    bankroll = 0.0;
+                    break;
+                }
+            }
+            final_sum += bankroll;
+            downswings.push(downswing);
+        }
+        downswings.sort_by(f64::total_cmp);
+        Some(BankrollSimulation {
+            trials,
+            hands,
+            risk_of_ruin: ruined as f64 / trials as f64,
+            mean_final: final_sum / trials as f64,
+            downswings,
+        })
+    }
+}
+
+// Box-Muller transform.
+fn standard_normal(rng: &mut impl Rng) -> f64 {
+    let u1: f64 = 1.0 - rng.r#gen::<f64>();
+    let u2: f64 = rng.r#gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
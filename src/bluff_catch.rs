@@ -0,0 +1,133 @@
+use core::fmt;
+use std::cmp::Ordering;
+
+use crate::cards::Cards;
+use crate::equity::live_hands;
+use crate::hand::Hand;
+use crate::range::HandRange;
+use crate::result::Result;
+
+// Facing a river bet of villain, whose betting range is split into value hands and bluffs.
+pub struct BluffCatch {
+    pub pot: f64,
+    pub bet: f64,
+    pub candidates: Vec<BluffCatcher>,
+}
+
+// A hero hand with the villain combos left after its blockers
+// and the resulting calling EV in chips (folding = 0).
+pub struct BluffCatcher {
+    pub hand: Hand,
+    pub value_combos: u32,
+    pub bluff_combos: u32,
+    pub equity: f64,
+    pub ev: f64,
+}
+
+impl fmt::Display for BluffCatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: ev={:.2} equity={:2.2} value={} bluffs={} bluff-share={:2.2}",
+            self.hand,
+            self.ev,
+            self.equity * 100.0,
+            self.value_combos,
+            self.bluff_combos,
+            self.bluff_share() * 100.0,
+        )
+    }
+}
+
+impl BluffCatcher {
+    pub fn bluff_share(&self) -> f64 {
+        let total = self.value_combos + self.bluff_combos;
+        if total == 0 {
+            0.0
+        } else {
+            f64::from(self.bluff_combos) / f64::from(total)
+        }
+    }
+}
+
+impl BluffCatch {
+    // Candidates are sorted by their calling EV, best first.
+    // Combos in both villain ranges count as value.
+    pub fn new(
+        community_cards: Cards,
+        pot: f64,
+        bet: f64,
+        hero_range: &impl HandRange,
+        value_range: &impl HandRange,
+        bluff_range: &impl HandRange,
+    ) -> Result<Self> {
+        if community_cards.count() != 5 {
+            return Err("bluff catch failed: expected 5 community cards".into());
+        }
+        if !(pot > 0.0 && bet > 0.0 && pot.is_finite() && bet.is_finite()) {
+            return Err("bluff catch failed: pot and bet must be positive".into());
+        }
+        let value_hands = live_hands(community_cards, value_range);
+        let bluff_hands: Vec<_> = live_hands(community_cards, bluff_range).into_iter()
+            .filter(|hand| !value_hands.contains(hand))
+            .collect();
+
+        let mut candidates = Vec::new();
+        for hand in live_hands(community_cards, hero_range) {
+            let hero_cards = hand.to_cards();
+            let hero_score = (community_cards | hero_cards).score_fast();
+            let mut value_combos = 0;
+            let mut bluff_combos = 0;
+            let mut pot_share = 0.0;
+            let villain_hands = value_hands.iter().map(|hand| (*hand, true))
+                .chain(bluff_hands.iter().map(|hand| (*hand, false)));
+            for (villain_hand, value) in villain_hands {
+                if (villain_hand.to_cards() & hero_cards) != Cards::EMPTY {
+                    continue;
+                }
+                if value {
+                    value_combos += 1;
+                } else {
+                    bluff_combos += 1;
+                }
+                let villain_score = (community_cards | villain_hand.to_cards()).score_fast();
+                pot_share += match hero_score.cmp(&villain_score) {
+                    Ordering::Greater => 1.0,
+                    Ordering::Equal => 0.5,
+                    Ordering::Less => 0.0,
+                };
+            }
+            if value_combos + bluff_combos == 0 {
+                continue;
+            }
+            let equity = pot_share / f64::from(value_combos + bluff_combos);
+            candidates.push(BluffCatcher {
+                hand,
+                value_combos,
+                bluff_combos,
+                equity,
+                ev: equity * (pot + 2.0 * bet) - bet,
+            });
+        }
+        if candidates.is_empty() {
+            return Err("bluff catch failed: no valid hero hands".into());
+        }
+        candidates.sort_by(|a, b| b.ev.partial_cmp(&a.ev).unwrap_or(Ordering::Equal));
+        Ok(Self { pot, bet, candidates })
+    }
+
+    // Share of its range hero has to continue with,
+    // so that a pure bluff of villain does not profit immediately.
+    pub fn minimum_defense_frequency(&self) -> f64 {
+        self.pot / (self.pot + self.bet)
+    }
+
+    // Equity hero needs for a call to break even.
+    pub fn required_equity(&self) -> f64 {
+        self.bet / (self.pot + 2.0 * self.bet)
+    }
+
+    pub fn profitable_calls(&self) -> usize {
+        self.candidates.iter().filter(|candidate| candidate.ev > 0.0).count()
+    }
+}
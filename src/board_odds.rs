@@ -0,0 +1,104 @@
+use core::fmt;
+
+use crate::cards::Cards;
+use crate::equity::boards_of_size;
+use crate::error::Error;
+use crate::rank::Rank;
+use crate::result::Result;
+use crate::suite::Suite;
+
+// How the community cards develop by the river, counted over every runout.
+// Suited counts are by Suite::to_usize: three-suited is at least three cards
+// of the suite on the board, four-suited at least four.
+// Paired is any rank at least twice on the board (trips and quads included),
+// double-paired at least two such ranks (e.g. two pair or a full house on the board).
+#[derive(Debug, Clone, Copy)]
+pub struct BoardOdds {
+    pub boards: u64,
+    pub three_suited: [u64; Suite::COUNT],
+    pub four_suited: [u64; Suite::COUNT],
+    pub paired: u64,
+    pub double_paired: u64,
+}
+
+impl fmt::Display for BoardOdds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let suited = [("three-suited", &self.three_suited), ("four-suited", &self.four_suited)];
+        for (name, counts) in suited {
+            write!(f, "{}: {:2.2}", name, self.share(counts.iter().sum()) * 100.0)?;
+            for suite in Suite::SUITES {
+                write!(f, " {}={:2.2}", suite, self.share(counts[suite.to_usize()]) * 100.0)?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "paired: {:2.2}", self.paired() * 100.0)?;
+        write!(f, "double-paired: {:2.2}", self.double_paired() * 100.0)
+    }
+}
+
+impl BoardOdds {
+    fn share(&self, count: u64) -> f64 {
+        count as f64 / self.boards as f64
+    }
+
+    // Probabilities as fractions. Only one suite can have three of the five cards,
+    // so the probabilities for any suite are the sums over the suites.
+    pub fn three_suited(&self, suite: Suite) -> f64 {
+        self.share(self.three_suited[suite.to_usize()])
+    }
+
+    pub fn four_suited(&self, suite: Suite) -> f64 {
+        self.share(self.four_suited[suite.to_usize()])
+    }
+
+    pub fn any_three_suited(&self) -> f64 {
+        self.share(self.three_suited.iter().sum())
+    }
+
+    pub fn any_four_suited(&self) -> f64 {
+        self.share(self.four_suited.iter().sum())
+    }
+
+    pub fn paired(&self) -> f64 {
+        self.share(self.paired)
+    }
+
+    pub fn double_paired(&self) -> f64 {
+        self.share(self.double_paired)
+    }
+}
+
+// Enumerates every river from 0 to 5 community cards, the known cards
+// (e.g. hero's hand) are never dealt. Preflop these are about 2.1 million boards.
+pub fn board_odds(community_cards: Cards, known_cards: Cards) -> Result<BoardOdds> {
+    if community_cards.count() > 5 {
+        return Err(Error::usage("board odds failed: expected at most 5 community cards"));
+    }
+    if let Some(card) = (community_cards & known_cards).first() {
+        return Err(Error::DuplicateCard(card));
+    }
+    let mut odds = BoardOdds {
+        boards: 0,
+        three_suited: [0; Suite::COUNT],
+        four_suited: [0; Suite::COUNT],
+        paired: 0,
+        double_paired: 0,
+    };
+    for board in boards_of_size(community_cards, known_cards, 5) {
+        let mut suites = [0u8; Suite::COUNT];
+        let mut ranks = [0u8; Rank::COUNT];
+        for card in board.iter() {
+            suites[card.suite().to_usize()] += 1;
+            ranks[card.rank().to_usize()] += 1;
+        }
+        for (suite, count) in suites.iter().enumerate() {
+            odds.three_suited[suite] += u64::from(*count >= 3);
+            odds.four_suited[suite] += u64::from(*count >= 4);
+        }
+        let pairs = ranks.iter().filter(|count| **count >= 2).count();
+        odds.paired += u64::from(pairs >= 1);
+        odds.double_paired += u64::from(pairs >= 2);
+        odds.boards += 1;
+    }
+    Ok(odds)
+}
@@ -0,0 +1,145 @@
+use core::fmt;
+use std::cmp::Ordering;
+
+use crate::hand::Hand;
+use crate::preflop::{
+    class_combos, class_hands, class_index, class_name, class_representatives, PreflopTable,
+    CLASS_COUNT, MAX_OPPONENTS,
+};
+use crate::range::RangeTable;
+use crate::result::Result;
+
+const TOTAL_COMBOS: u32 = 1326;
+
+// The field the starting hands are measured against.
+pub enum BucketField {
+    // 1 to 8 random hands.
+    Random(usize),
+    // Heads-up against a range, with card removal between the two hands.
+    Range(RangeTable),
+}
+
+// The 169 starting hand classes partitioned into buckets by preflop equity,
+// bucket 0 is the strongest. Every bucket holds about the same number of combos,
+// a class is never split. Every combo is in the bucket of its class.
+pub struct PreflopBuckets {
+    // Per class, ordered by preflop::class_index.
+    pub equities: Vec<f64>,
+    pub assignments: Vec<usize>,
+    // The lowest equity in every bucket, descending.
+    pub boundaries: Vec<f64>,
+}
+
+impl fmt::Display for PreflopBuckets {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (bucket, boundary) in self.boundaries.iter().enumerate() {
+            let classes: Vec<_> = self.classes(bucket).collect();
+            let combos: u32 = classes.iter().map(|hand| class_combos(*hand)).sum();
+            let names: Vec<_> = classes.iter().map(|hand| class_name(*hand)).collect();
+            writeln!(
+                f,
+                "bucket {}: equity>={:2.2} combos={} {}",
+                bucket + 1,
+                boundary * 100.0,
+                combos,
+                names.join(","),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub fn range_equity(table: &PreflopTable, representative: Hand, range: &RangeTable) -> Option<f64> {
+    let mut villain_hands = Vec::new();
+    range.for_each_hand(|hand| villain_hands.push(hand));
+    let mut total = 0.0;
+    let mut count = 0u32;
+    for hero in class_hands(representative) {
+        for villain in &villain_hands {
+            if (hero.to_cards() & villain.to_cards()).count() == 0 {
+                total += table.heads_up(hero, *villain);
+                count += 1;
+            }
+        }
+    }
+    (count != 0).then(|| total / f64::from(count))
+}
+
+impl PreflopBuckets {
+    pub fn new(field: &BucketField, bucket_count: usize) -> Result<Self> {
+        if !(1..=CLASS_COUNT).contains(&bucket_count) {
+            return Err(format!("buckets failed: expected 1 to {CLASS_COUNT} buckets").into());
+        }
+        let table = PreflopTable::active();
+        let equities = match field {
+            BucketField::Random(opponents) => {
+                if !(1..=MAX_OPPONENTS).contains(opponents) {
+                    return Err(format!("buckets failed: expected 1 to {MAX_OPPONENTS} opponents").into());
+                }
+                class_representatives()
+                    .map(|hand| table.vs_random(hand, *opponents))
+                    .collect::<Vec<_>>()
+            },
+            BucketField::Range(range) => {
+                let mut equities = Vec::with_capacity(CLASS_COUNT);
+                for hand in class_representatives() {
+                    // Classes fully blocked by the range only happen for tiny ranges,
+                    // they can't be ranked against it.
+                    let Some(equity) = range_equity(table, hand, range) else {
+                        return Err(format!(
+                            "buckets failed: {} is blocked by every hand of the range",
+                            class_name(hand),
+                        ).into());
+                    };
+                    equities.push(equity);
+                }
+                equities
+            },
+        };
+
+        let mut order: Vec<Hand> = class_representatives().collect();
+        order.sort_by(|a, b| {
+            equities[class_index(*b)]
+                .partial_cmp(&equities[class_index(*a)])
+                .unwrap_or(Ordering::Equal)
+        });
+        let mut assignments = vec![0; CLASS_COUNT];
+        let mut boundaries = vec![1.0; bucket_count];
+        let mut combos_before = 0;
+        let mut bucket = 0;
+        for (position, hand) in order.into_iter().enumerate() {
+            // A new bucket starts once the previous ones hold their share of the combos,
+            // but every bucket gets at least one class.
+            let share = combos_before as usize * bucket_count / TOTAL_COMBOS as usize;
+            let remaining_classes = CLASS_COUNT - position;
+            if position != 0
+                && bucket + 1 < bucket_count
+                && (share > bucket || remaining_classes < bucket_count - bucket)
+            {
+                bucket += 1;
+            }
+            let equity = equities[class_index(hand)];
+            assignments[class_index(hand)] = bucket;
+            boundaries[bucket] = f64::min(boundaries[bucket], equity);
+            combos_before += class_combos(hand);
+        }
+        Ok(Self { equities, assignments, boundaries })
+    }
+
+    pub fn bucket(&self, hand: Hand) -> usize {
+        self.assignments[class_index(hand)]
+    }
+
+    // The classes of a bucket, strongest first.
+    pub fn classes(&self, bucket: usize) -> impl Iterator<Item = Hand> + '_ {
+        let mut classes: Vec<_> = class_representatives()
+            .filter(|hand| self.bucket(*hand) == bucket)
+            .collect();
+        classes.sort_by(|a, b| {
+            self.equities[class_index(*b)]
+                .partial_cmp(&self.equities[class_index(*a)])
+                .unwrap_or(Ordering::Equal)
+        });
+        classes.into_iter()
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::cards::Cards;
+use crate::equity::{Equity, EquityResult};
+use crate::hand::Hand;
+use crate::range::HandRange;
+use crate::shove::{permute_cards, suit_permutations};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EquityMethod {
+    Enumerate,
+    Simulate { rounds: u64 },
+}
+
+// Inputs of an equity calculation with the suits relabeled to the smallest
+// of all 24 suit permutations, so spots differing only by suits share a key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EquityKey(Vec<u64>);
+
+impl EquityKey {
+    pub fn new(
+        community_cards: Cards,
+        hero_hand: Hand,
+        villain_ranges: &[impl HandRange],
+        method: EquityMethod,
+    ) -> Self {
+        let ranges: Vec<Vec<Cards>> = villain_ranges.iter()
+            .map(|range| {
+                let mut hands = Vec::new();
+                range.for_each_hand(|hand| hands.push(hand.to_cards()));
+                hands
+            })
+            .collect();
+        let key = suit_permutations().iter()
+            .map(|permutation| {
+                let mut key = match method {
+                    EquityMethod::Enumerate => vec![0, 0],
+                    EquityMethod::Simulate { rounds } => vec![1, rounds],
+                };
+                key.push(permute_cards(community_cards, permutation).to_u64());
+                key.push(permute_cards(hero_hand.to_cards(), permutation).to_u64());
+                for hands in &ranges {
+                    let mut permuted: Vec<_> = hands.iter()
+                        .map(|cards| permute_cards(*cards, permutation).to_u64())
+                        .collect();
+                    permuted.sort_unstable();
+                    key.push(permuted.len() as u64);
+                    key.extend(permuted);
+                }
+                key
+            })
+            .min()
+            .unwrap();
+        Self(key)
+    }
+}
+
+// Least recently used cache of equity results within a process.
+pub struct EquityCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<EquityKey, (u64, EquityResult)>,
+    recent: BTreeMap<u64, EquityKey>,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl EquityCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            recent: BTreeMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Approximate heap memory in bytes, every key is held twice (entries and recent).
+    pub fn memory(&self) -> usize {
+        let entry = size_of::<(EquityKey, (u64, EquityResult))>() + 1;
+        let recent = size_of::<(u64, EquityKey)>();
+        self.entries.iter()
+            .map(|(key, (_, result))| {
+                entry
+                    + recent
+                    + 2 * key.0.capacity() * size_of::<u64>()
+                    + result.equities.capacity() * size_of::<Equity>()
+            })
+            .sum()
+    }
+
+    pub fn get(&mut self, key: &EquityKey) -> Option<EquityResult> {
+        self.tick += 1;
+        let Some((tick, result)) = self.entries.get_mut(key) else {
+            self.misses += 1;
+            return None;
+        };
+        self.hits += 1;
+        self.recent.remove(tick);
+        *tick = self.tick;
+        self.recent.insert(self.tick, key.clone());
+        Some(result.clone())
+    }
+
+    pub fn insert(&mut self, key: EquityKey, result: EquityResult) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some((tick, _)) = self.entries.get(&key) {
+            self.recent.remove(tick);
+        } else if self.entries.len() >= self.capacity {
+            if let Some((_, oldest)) = self.recent.pop_first() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.recent.insert(self.tick, key.clone());
+        self.entries.insert(key, (self.tick, result));
+    }
+}
@@ -0,0 +1,85 @@
+use core::fmt;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::card::Card;
+use crate::cards::Cards;
+use crate::shove::{permute_cards, suit_permutations};
+
+pub const CANONICAL_FLOP_COUNT: usize = 1755;
+
+static FLOPS: OnceLock<Vec<CanonicalBoard>> = OnceLock::new();
+
+// Heap memory in bytes of the canonical flops, if they are loaded.
+pub fn loaded_memory() -> usize {
+    FLOPS.get().map_or(0, |flops| flops.capacity() * size_of::<CanonicalBoard>())
+}
+
+// A strategically distinct board: the representative of all boards
+// equal up to relabeling the suits, weight is the number of boards it stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CanonicalBoard {
+    pub cards: Cards,
+    pub weight: u32,
+}
+
+impl fmt::Display for CanonicalBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut cards: Vec<_> = self.cards.iter().collect();
+        cards.sort_by_key(|card| core::cmp::Reverse(card.rank()));
+        for card in cards {
+            write!(f, "{}", card)?;
+        }
+        write!(f, " weight={}", self.weight)
+    }
+}
+
+// The representative of cards under all 24 suit relabelings
+// (the one with the smallest bit pattern).
+pub fn canonical_cards(cards: Cards) -> Cards {
+    suit_permutations().iter()
+        .map(|permutation| permute_cards(cards, permutation))
+        .min()
+        .unwrap()
+}
+
+// Groups the boards by their canonical cards, the first board of a group represents it.
+fn group(boards: impl Iterator<Item = Cards>) -> Vec<(Cards, CanonicalBoard)> {
+    let mut groups: HashMap<Cards, CanonicalBoard> = HashMap::new();
+    for board in boards {
+        groups.entry(canonical_cards(board))
+            .or_insert(CanonicalBoard { cards: board, weight: 0 })
+            .weight += 1;
+    }
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    groups.sort_by_key(|(_, board)| board.cards);
+    groups
+}
+
+// All 1755 strategically distinct flops, the weights sum up to the 22100 flops.
+pub fn canonical_flops() -> impl Iterator<Item = CanonicalBoard> {
+    FLOPS.get_or_init(|| {
+        let cards: Vec<_> = Card::all().collect();
+        let flops = (0..cards.len()).flat_map(|a| {
+            let cards = &cards;
+            (a+1..cards.len()).flat_map(move |b| {
+                (b+1..cards.len()).map(move |c| Cards::EMPTY.with(cards[a]).with(cards[b]).with(cards[c]))
+            })
+        });
+        let mut flops: Vec<_> = group(flops).into_iter()
+            .map(|(cards, board)| CanonicalBoard { cards, weight: board.weight })
+            .collect();
+        flops.sort_by_key(|board| board.cards);
+        assert_eq!(flops.len(), CANONICAL_FLOP_COUNT);
+        flops
+    }).iter().copied()
+}
+
+// The strategically distinct boards with one more card, e.g. the turns of a flop
+// or the rivers of a turn. Every extension is represented by the board itself
+// with one of the cards (not relabeled), the weights sum up to the number of remaining cards.
+pub fn canonical_extensions(board: Cards) -> Vec<CanonicalBoard> {
+    group((!board).iter().map(|card| board.with(card))).into_iter()
+        .map(|(_, board)| board)
+        .collect()
+}
@@ -2,9 +2,11 @@ use std::{cmp::Ordering, fmt};
 
 use rand::{distributions::{Distribution, Standard}, Rng};
 
-use crate::{cards::Cards, rank::Rank, result::Result, suite::Suite};
+use crate::{cards::Cards, parse_error::{normalize_cards, ParseError}, rank::Rank, result::Result, suite::Suite};
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+// Ordered by suit (diamonds, spades, hearts, clubs), then by rank,
+// the order of Card::all and of the dense index. Card::cmp_by_rank orders by rank first.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Card(i8);
 
 impl Distribution<Card> for Standard {
@@ -25,36 +27,80 @@ impl fmt::Display for Card {
     }
 }
 
+impl std::str::FromStr for Card {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
 impl Card {
     pub const MIN: Self = Self(0);
 
     pub const COUNT: usize = Suite::COUNT * Rank::COUNT;
 
+    pub const COUNT_INDEX: usize = 64;
+
     pub fn of(rank: Rank, suite: Suite) -> Self {
         Self(suite.to_index() + rank.to_i8())
     }
 
     pub fn from_index(index: i8) -> Option<Self> {
-        if index < 0 || index > 63 {
-            None
-        } else if Cards::MASK_FULL&(1u64 << u64::try_from(index).unwrap()) == 0 {
+        if !(0..=63).contains(&index)
+            || Cards::MASK_FULL&(1u64 << u64::try_from(index).unwrap()) == 0 {
             None
         } else {
             Some(Self(index))
-        }      
+        }
     }
 
-    pub fn from_str(s: &str) -> Result<Self> {
-        match s.as_bytes() {
-            [rank_raw, suite_raw] => {
-                let rank = Rank::from_ascii(*rank_raw)?;
-                let suite = Suite::from_ascii(*suite_raw)?;
-                Ok(Self::of(rank, suite))
+    // The rank ("10" also for a ten) and the suite, both in either case,
+    // the suite also as its symbol, e.g. "Ah", "aH", "10h" or "A♥".
+    pub fn parse(s: &str) -> std::result::Result<Self, ParseError> {
+        let rank_len = Self::rank_len(s);
+        let (rank_raw, suite_raw) = s.split_at(rank_len);
+        let mut suite_chars = suite_raw.chars();
+        let result = match (suite_chars.next(), suite_chars.next()) {
+            (Some(suite_raw), None) => match Self::parse_rank(rank_raw) {
+                Err(err) => Err(ParseError::new("card", s, 0, rank_raw, err.to_string())),
+                Ok(rank) => match Suite::try_from(suite_raw.to_ascii_lowercase()) {
+                    Err(err) => Err(ParseError::new("card", s, rank_len, &s[rank_len..], err.to_string())),
+                    Ok(suite) => Ok(Self::of(rank, suite)),
+                },
             },
-            _ => Err(format!("invalid card '{s}': bad length").into()),
+            (None, _) if rank_len != 0 => Err(ParseError::new("card", s, 0, s, "missing suite")),
+            _ => Err(ParseError::new("card", s, 0, s, "bad length")),
+        };
+        result.map_err(|err| err.suggest(normalize_cards(s), |suggestion| {
+            Self::parse(suggestion).is_ok()
+        }))
+    }
+
+    fn parse_rank(s: &str) -> Result<Rank> {
+        let mut chars = s.chars();
+        match (s, chars.next(), chars.next()) {
+            ("10", _, _) => Ok(Rank::Ten),
+            (_, Some(ch), None) => Rank::try_from(ch.to_ascii_uppercase()),
+            _ => Err(format!("invalid rank '{}'", s).into()),
+        }
+    }
+
+    fn rank_len(s: &str) -> usize {
+        if s.starts_with("10") {
+            2
+        } else {
+            s.chars().next().map_or(0, char::len_utf8)
         }
     }
 
+    // The length of the card at the start of s as read by parse,
+    // the rank and one more char for the suite.
+    pub fn len_at_start(s: &str) -> usize {
+        let rank_len = Self::rank_len(s);
+        rank_len + s[rank_len..].chars().next().map_or(0, char::len_utf8)
+    }
+
     pub fn all() -> impl Iterator<Item = Self> {
         Suite::SUITES.iter()
             .flat_map(|suite| Rank::RANKS.iter().map(|rank| Self::of(*rank, *suite)))
@@ -80,6 +126,19 @@ impl Card {
         self.0 as usize
     }
 
+    // Unlike to_index without holes, 0 to Card::COUNT - 1, e.g. for arrays of Card::COUNT.
+    pub fn to_dense_index(self) -> usize {
+        self.suite().to_usize() * Rank::COUNT + self.rank().to_usize()
+    }
+
+    pub fn from_dense_index(index: usize) -> Option<Self> {
+        if index >= Self::COUNT {
+            None
+        } else {
+            Some(Self::of(Rank::RANKS[index % Rank::COUNT], Suite::SUITES[index / Rank::COUNT]))
+        }
+    }
+
     pub fn cmp_by_rank(self, other: Self) -> Ordering {
         self.rank().cmp(&other.rank())
             .then_with(|| self.suite().to_usize().cmp(&other.suite().to_usize()))
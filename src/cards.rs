@@ -1,6 +1,6 @@
-use std::{cmp::Ordering, collections::HashMap, fmt, ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not, Shl}, ptr::addr_of_mut};
+use std::{cmp::Ordering, collections::HashMap, fmt, ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not, Shl}, sync::OnceLock};
 
-use crate::{card::Card, hand::Hand, rank::Rank, result::Result, suite::Suite};
+use crate::{card::Card, hand::Hand, parse_error::{normalize_cards, ParseError}, rank::Rank, result::Result, suite::Suite};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Score(u32);
@@ -13,7 +13,13 @@ impl Score {
         debug_assert_eq!(hand_ranking&0xfff, hand_ranking);
         let mut n = u32::from(hand_ranking) << 20;
         assert!(cards.count() <= 5);
-        for (i, rank) in cards.by_rank().iter().enumerate() {
+        let mut ranks = cards.by_rank();
+        let is_straight = matches!(ranking, HandRanking::Straight | HandRanking::StraightFlush);
+        if is_straight && ranks == CardsByRank::WHEEL {
+            // The ace plays low, the wheel is the lowest straight.
+            ranks.remove(Rank::Ace);
+        }
+        for (i, rank) in ranks.iter().enumerate() {
             n |= rank.to_u32() << (16 - i*4);
         }
         Score(n)
@@ -29,10 +35,7 @@ impl Score {
                 suite = Suite::try_from(i8::try_from(next_suite_index).unwrap()).unwrap();
             }
         }
-        assert!({
-            let count = cards.count();
-            count >= 5 && count <= 7
-        });
+        assert!((5..=7).contains(&cards.count()));
         assert!(cards.flush().is_none());
         let top5 = cards.top5();
         assert!(matches!(
@@ -52,7 +55,7 @@ impl Score {
         Score(self.0 + rhs.0)
     }
 
-    fn to_hand_ranking(self) -> HandRanking {
+    pub fn to_hand_ranking(self) -> HandRanking {
         let n = u16::try_from((self.0>>20) & 0xfff).unwrap();
         HandRanking::from_u16(n).unwrap()
     }
@@ -74,6 +77,26 @@ pub enum HandRanking {
 }
 
 impl HandRanking {
+    pub const CATEGORY_COUNT: usize = 10;
+
+    pub const CATEGORY_NAMES: [&'static str; Self::CATEGORY_COUNT] = [
+        "high-card",
+        "pair",
+        "two-pair",
+        "three-of-a-kind",
+        "straight",
+        "flush",
+        "full-house",
+        "four-of-a-kind",
+        "straight-flush",
+        "royal-flush",
+    ];
+
+    // HighCard = 0, ..., RoyalFlush = 9
+    pub fn category(self) -> usize {
+        usize::from(self.to_u16() >> 8)
+    }
+
     fn to_u16(self) -> u16 {
         match self {
             HandRanking::HighCard => 0,
@@ -155,29 +178,34 @@ impl Top5 {
     }
 
     pub fn compare(self, villain: Top5) -> Ordering {
-        match self.ranking.cmp(&villain.ranking) {
-            Ordering::Equal => {
-                let hero_rankings = self.cards.by_rank();
-                let villain_rankings = villain.cards.by_rank();
-                let iter = hero_rankings.iter().zip(villain_rankings.iter());
-                for (hero_rank, villain_rank) in iter {
-                    match hero_rank.cmp(&villain_rank) {
-                        Ordering::Equal => continue,
-                        o => return o,
-                    }
-                }
-                Ordering::Equal
-            },
-            o => o,
-        }
+        self.to_score().cmp(&villain.to_score())
     }
 
     pub fn to_score(self) -> Score {
         Score::from_ranking_cards(self.ranking, self.cards)
     }
+
+    pub fn ranking(self) -> HandRanking {
+        self.ranking
+    }
+
+    pub fn cards(self) -> Cards {
+        self.cards
+    }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+// The cards that make the hand (five, or all with fewer cards)
+// and the cards that don't play.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BestFive {
+    pub ranking: HandRanking,
+    pub cards: Cards,
+    pub unused: Cards,
+}
+
+// Ordered by the bit pattern (Cards::to_u64), not by rank,
+// a stable order e.g. for sorting or as a BTreeMap key.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Cards(u64);
 
 impl fmt::Display for Cards {
@@ -201,6 +229,14 @@ impl fmt::Debug for Cards {
     }
 }
 
+impl std::str::FromStr for Cards {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
 impl BitAnd<Cards> for Cards {
     type Output = Cards;
 
@@ -231,6 +267,33 @@ impl Not for Cards {
     }
 }
 
+impl IntoIterator for Cards {
+    type Item = Card;
+    type IntoIter = CardsIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// Cards are a set, a card collected or extended more than once is added once
+// (Cards::from_slice rejects duplicates instead).
+impl FromIterator<Card> for Cards {
+    fn from_iter<I: IntoIterator<Item = Card>>(iter: I) -> Self {
+        let mut cards = Self::EMPTY;
+        cards.extend(iter);
+        cards
+    }
+}
+
+impl Extend<Card> for Cards {
+    fn extend<I: IntoIterator<Item = Card>>(&mut self, iter: I) {
+        for card in iter {
+            self.try_add(card);
+        }
+    }
+}
+
 fn interleave_first_32_bits_with_zeros(mut n: u64) -> u64 {
     n = (n ^ (n << 16)) & 0x0000ffff0000ffff;
     n = (n ^ (n << 8)) & 0x00ff00ff00ff00ff;
@@ -240,11 +303,47 @@ fn interleave_first_32_bits_with_zeros(mut n: u64) -> u64 {
     n
 }
 
-static mut CARDS_SCORE_MAP: Option<&'static HashMap<u64, Score>> = None;
+// How Cards::score_fast evaluates 5 to 7 cards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoreBackend {
+    // Lookup tables by rank counts and by flush ranks, see Cards::score_tables_memory.
+    Tables,
+    // No tables, every score is computed from the cards (about 2.5x slower).
+    Direct,
+}
+
+impl ScoreBackend {
+    // The tables if they fit within the limit in bytes, otherwise direct.
+    pub fn within(limit: Option<usize>) -> Self {
+        match limit {
+            Some(limit) if limit < Cards::score_tables_memory() => Self::Direct,
+            _ => Self::Tables,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Tables => "tables",
+            Self::Direct => "direct",
+        }
+    }
+}
+
+struct ScoreTables {
+    score_map: HashMap<u64, Score>,
+    flush_map: Box<[Score]>,
+}
+
+// Chosen with Cards::set_score_backend before the first score, tables by default.
+static SCORE_BACKEND: OnceLock<ScoreBackend> = OnceLock::new();
+
+// Built on the first score, None for ScoreBackend::Direct.
+static SCORE_TABLES: OnceLock<Option<ScoreTables>> = OnceLock::new();
 
 const FLUSH_MAP_SIZE: usize = (Cards::MASK_SINGLE + 1) as usize;
 
-static mut CARDS_FLUSH_MAP: [Score; FLUSH_MAP_SIZE] = [Score::ZERO; FLUSH_MAP_SIZE];
+// Number of rank counts (at most 4 of every rank) of 5 to 7 cards.
+const SCORE_MAP_LEN: usize = 73775;
 
 impl Cards {
     pub const EMPTY: Self = Cards(0);
@@ -256,24 +355,34 @@ impl Cards {
         | Cards::MASK_SINGLE << 16
         | Cards::MASK_SINGLE;
 
-    pub fn from_str(s: &str) -> Result<Self> {
+    // The cards as read by Card::parse, optionally separated by whitespace or commas,
+    // e.g. "AsKd7h", "As Kd 7h" or "as,10d,7♥". "none" are no cards.
+    pub fn parse(s: &str) -> std::result::Result<Self, ParseError> {
         if s == "none" {
             return Ok(Cards::EMPTY);
         }
+        Self::parse_cards(s).map_err(|err| err.suggest(normalize_cards(s), |suggestion| {
+            Self::parse_cards(suggestion).is_ok()
+        }))
+    }
 
-        if s.len()%2 != 0 {
-            return Err(format!("invalid cards '{s}': bad length").into());
-        }
-        if !s.is_ascii() {
-            return Err(format!("invalid cards '{s}': not ascii").into());
-        }
+    fn parse_cards(s: &str) -> std::result::Result<Self, ParseError> {
+        let is_separator = |ch: char| ch.is_whitespace() || ch == ',';
         let mut cards = Self::EMPTY;
-        for i in (0..s.len()).step_by(2) {
-            let card_raw = &s[i..i+2];
-            let card = Card::from_str(card_raw)?;
+        let mut offset = 0;
+        loop {
+            let rest = &s[offset..];
+            let card_start = rest.trim_start_matches(is_separator);
+            if card_start.is_empty() {
+                break;
+            }
+            offset += rest.len() - card_start.len();
+            let card_raw = &card_start[..Card::len_at_start(card_start)];
+            let card = Card::parse(card_raw).map_err(|err| err.within("cards", s, offset))?;
             if !cards.try_add(card) {
-                return Err(format!("invalid cards '{s}': duplicate card {card}").into());
+                return Err(ParseError::duplicate("cards", s, offset, card_raw, card));
             };
+            offset += card_raw.len();
         }
         Ok(cards)
     }
@@ -299,19 +408,10 @@ impl Cards {
     }
 
     pub fn to_hand(self) -> Option<Hand> {
-        let mut iter = self.iter();
-        let (a, b) = match (iter.next(), iter.next()) {
-            (Some(a), Some(b)) => (a, b),
-            _ => return None,
-        };
-        if iter.next().is_some() {
-            None
-        } else {
-            Some(Hand::of_two_cards(a, b))
-        }
+        Hand::try_of_cards(self).ok()
     }
 
-    fn to_u64(self) -> u64 {
+    pub fn to_u64(self) -> u64 {
         self.0
     }
 
@@ -373,21 +473,35 @@ impl Cards {
             .map(move |suite| (suite, CardsByRank::from_cards_suite(self, suite)))
     }
 
-    fn score_map() -> &'static HashMap<u64, Score> {
-        unsafe { CARDS_SCORE_MAP.unwrap() }
+    fn score_tables() -> Option<&'static ScoreTables> {
+        SCORE_TABLES.get_or_init(|| {
+            match SCORE_BACKEND.get_or_init(|| ScoreBackend::Tables) {
+                ScoreBackend::Tables => {
+                    let mut flush_map = vec![Score::ZERO; FLUSH_MAP_SIZE].into_boxed_slice();
+                    Self::init_flush_map(&mut flush_map);
+                    Some(ScoreTables { score_map: Self::build_score_map(), flush_map })
+                },
+                ScoreBackend::Direct => None,
+            }
+        }).as_ref()
     }
 
-    fn flush_map_get(cards: CardsByRank) -> Score {
-        unsafe { CARDS_FLUSH_MAP[cards.to_usize()] }
+    // Any number of cards, for boards that are not 5 cards.
+    pub fn score(self) -> Score {
+        if (5..=7).contains(&self.count()) {
+            self.score_fast()
+        } else {
+            self.top5().to_score()
+        }
     }
 
     pub fn score_fast(self) -> Score {
-        assert!({
-            let count = self.count();
-            count >= 5 && count <= 7
-        });
+        assert!((5..=7).contains(&self.count()));
+        let Some(tables) = Self::score_tables() else {
+            return self.top5().to_score();
+        };
         let counts_n = self.counts_n_fast();
-        let score = Self::score_map()[&counts_n];
+        let score = tables.score_map[&counts_n];
         if !self.is_flush() {
             return score;
         }
@@ -395,30 +509,92 @@ impl Cards {
         let mut score = Score::ZERO;
         for suite in Suite::SUITES {
             let cards = CardsByRank::from_cards_suite(self, suite);
-            let suite_score = Self::flush_map_get(cards);
+            let suite_score = tables.flush_map[cards.to_usize()];
             score = score.add_unchecked(suite_score);
         }
         debug_assert_eq!(self.top5().to_score(), score);
         score
     }
 
-    pub unsafe fn init() {
-        unsafe {
-            assert_eq!(CARDS_FLUSH_MAP[0b11111], Score::ZERO);
-            let flush_map = &mut (*addr_of_mut!(CARDS_FLUSH_MAP));
-            Self::init_flush_map(flush_map);
+    // Exactly five cards, classified directly by the ranks of every suit,
+    // without the lookup tables and without picking the best five.
+    // The same score as score_fast, e.g. for Omaha, draw or the rows of OFC.
+    pub fn score_five(self) -> Score {
+        assert_eq!(self.count(), 5);
+        let [a, b, c, d] = Suite::SUITES.map(|suite| CardsByRank::from_cards_suite(self, suite));
+        let ranks = a | b | c | d;
+        let pairs = (a & b) | (a & c) | (a & d) | (b & c) | (b & d) | (c & d);
+        let trips = (a & b & c) | (a & b & d) | (a & c & d) | (b & c & d);
+        let ranking = if let Some(quads) = (a & b & c & d).highest_rank() {
+            HandRanking::FourOfAKind(quads)
+        } else if let Some(trips) = trips.highest_rank() {
+            match pairs.without(trips).highest_rank() {
+                Some(pair) => HandRanking::FullHouse { trips, pair },
+                None => HandRanking::ThreeOfAKind(trips),
+            }
+        } else if let Some(first) = pairs.highest_rank() {
+            match pairs.without(first).highest_rank() {
+                Some(second) => HandRanking::TwoPair { first, second },
+                None => HandRanking::OnePair(first),
+            }
+        } else {
+            let is_flush = [a, b, c, d].iter().any(|suite| suite.count() == 5);
+            match (ranks.straight().is_some(), is_flush) {
+                (true, true) if ranks.has(Rank::King) && ranks.has(Rank::Ace) => HandRanking::RoyalFlush,
+                (true, true) => HandRanking::StraightFlush,
+                (false, true) => HandRanking::Flush,
+                (true, false) => HandRanking::Straight,
+                (false, false) => HandRanking::HighCard,
+            }
         };
-        let score_map = Self::build_score_map();
-        unsafe {
-            assert!(CARDS_SCORE_MAP.is_none());
-            CARDS_SCORE_MAP = Some(Box::leak(Box::new(score_map)));
+        // Like Score::from_ranking_cards, all five cards play.
+        let mut rank_bits = ranks.to_u64() as u32;
+        let is_straight = matches!(ranking, HandRanking::Straight | HandRanking::StraightFlush);
+        if is_straight && ranks == CardsByRank::WHEEL {
+            rank_bits &= !(1 << Rank::Ace.to_u32());
+        }
+        let mut n = u32::from(ranking.to_u16()) << 20;
+        let mut shift = 16;
+        while rank_bits != 0 {
+            let rank = 31 - rank_bits.leading_zeros();
+            n |= rank << shift;
+            rank_bits &= !(1 << rank);
+            shift -= 4;
+        }
+        Score(n)
+    }
+
+    // Fails once the backend is chosen, by an earlier call or by the first score.
+    pub fn set_score_backend(backend: ScoreBackend) -> Result<()> {
+        SCORE_BACKEND.set(backend)
+            .map_err(|_| "score backend failed: already chosen".into())
+    }
+
+    // Builds the tables now instead of on the first score, e.g. before measuring time.
+    pub fn warm_up() {
+        Self::score_tables();
+    }
+
+    pub fn score_backend() -> ScoreBackend {
+        match Self::score_tables() {
+            Some(_) => ScoreBackend::Tables,
+            None => ScoreBackend::Direct,
         }
     }
 
-    fn init_flush_map(map: &mut [Score; FLUSH_MAP_SIZE]) {
-        for n in 0..FLUSH_MAP_SIZE {
+    // Heap memory of the tables of ScoreBackend::Tables in bytes, whether they are loaded or not.
+    pub fn score_tables_memory() -> usize {
+        // Like the HashMap implementation: a power of two of buckets at most 7/8 full,
+        // one control byte per bucket and a group of trailing control bytes.
+        let buckets = (SCORE_MAP_LEN * 8).div_ceil(7).next_power_of_two();
+        let score_map = buckets * (size_of::<(u64, Score)>() + 1) + 16;
+        score_map + FLUSH_MAP_SIZE * size_of::<Score>()
+    }
+
+    fn init_flush_map(map: &mut [Score]) {
+        for (n, score) in map.iter_mut().enumerate() {
             if n.count_ones() < 5 {
-                map[n] = Score::ZERO;
+                *score = Score::ZERO;
             } else {
                 let cards = CardsByRank::from_raw(i16::try_from(n).unwrap())
                     .to_cards_suite(Suite::Diamonds);
@@ -426,19 +602,20 @@ impl Cards {
                 assert!(matches!(top5.ranking, HandRanking::Flush
                     | HandRanking::StraightFlush
                     | HandRanking::RoyalFlush));
-                map[n] = top5.to_score();
+                *score = top5.to_score();
             }
         }
     }
 
     fn build_score_map() -> HashMap<u64, Score> {
-        let mut map = HashMap::new();
+        let mut map = HashMap::with_capacity(SCORE_MAP_LEN);
         Self::score_map_recursive(
             &mut map,
             0,
             &mut [0u8; Rank::COUNT],
             Rank::COUNT,
         );
+        assert_eq!(map.len(), SCORE_MAP_LEN);
         map
     }
 
@@ -453,7 +630,7 @@ impl Cards {
             counts[index] = n;
             let next_count = old_count + n;
             if remainder == 1 {
-                if next_count < 5 || next_count > 7 {
+                if !(5..=7).contains(&next_count) {
                     continue;
                 }
                 let counts_n = Self::counts_n(counts);
@@ -492,7 +669,8 @@ impl Cards {
     pub fn top5(self) -> Top5 {
         let counts = self.counts();
         if let Some(cards) = self.straight_flush() {
-            if cards.first().unwrap().rank() == Rank::Ace {
+            // The ace is also the highest card of the wheel.
+            if cards.by_rank().has(Rank::King) && cards.by_rank().has(Rank::Ace) {
                 Top5::of(HandRanking::RoyalFlush, cards)
             } else {
                 Top5::of(HandRanking::StraightFlush, cards)
@@ -514,6 +692,17 @@ impl Cards {
         }
     }
 
+    // Of several equal choices (e.g. a straight with a paired rank)
+    // always the same cards are returned.
+    pub fn best_five(self) -> BestFive {
+        let top5 = self.top5();
+        BestFive {
+            ranking: top5.ranking,
+            cards: top5.cards,
+            unused: self & !top5.cards,
+        }
+    }
+
     fn kickers(self, count: u8) -> Self {
         let mut kickers = Self::EMPTY;
         let mut remaining = count;
@@ -529,9 +718,7 @@ impl Cards {
     }
 
     fn pair(self, counts: [u8; Rank::COUNT]) -> Option<Top5> {
-        let Some(first_pair_rank) = Self::best_n(counts, 2) else {
-            return None;
-        };
+        let first_pair_rank = Self::best_n(counts, 2)?;
         let first_pair = (self & Cards::of_rank(first_pair_rank)).take_n(2);
 
         let second_pair_rank = {
@@ -570,9 +757,7 @@ impl Cards {
     }
 
     fn straight(self) -> Option<Self> {
-        let Some(straight) = self.by_rank().straight() else {
-            return None;
-        };
+        let straight = self.by_rank().straight()?;
         let mut out = Self::EMPTY;
         for rank in straight.iter() {
             out |= (self & Self::of_rank(rank)).take_n(1);
@@ -601,16 +786,11 @@ impl Cards {
     }
 
     fn full_house(self, counts: [u8; Rank::COUNT]) -> Option<(Rank, Rank, Self)> {
-        let Some(trips_rank) = Self::best_n(counts, 3) else {
-            return None;
-        };
+        let trips_rank = Self::best_n(counts, 3)?;
         let pair_rank = {
             let mut counts_without_trips = counts;
             counts_without_trips[trips_rank.to_usize()] = 0;
-            match Self::best_n(counts_without_trips, 2) {
-                Some(rank) => rank,
-                None => return None,
-            }
+            Self::best_n(counts_without_trips, 2)?
         };
         let trips = (self & Cards::of_rank(trips_rank)).take_n(3);
         let pair = (self & Cards::of_rank(pair_rank)).take_n(2);
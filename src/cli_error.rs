@@ -0,0 +1,55 @@
+use crate::daemon::json_string;
+use crate::error::Error;
+
+// Why the CLI failed, every kind exits with its own code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    // Unknown command, wrong arguments or input that doesn't make a valid calculation.
+    Usage,
+    // Malformed cards, hands, ranges or numbers.
+    Parse,
+    // Valid input, but too large to enumerate.
+    Infeasible,
+    // Everything else, e.g. IO.
+    Internal,
+}
+
+impl ErrorKind {
+    pub fn of(err: &Error) -> Self {
+        match err {
+            Error::Usage(_) | Error::DuplicateCard(_) | Error::NoLiveCombos(_) => ErrorKind::Usage,
+            Error::Parse(_) | Error::Int(_) | Error::Float(_) => ErrorKind::Parse,
+            Error::TooLarge(_) => ErrorKind::Infeasible,
+            Error::Io(_) | Error::Cancelled | Error::Other(_) => ErrorKind::Internal,
+        }
+    }
+
+    pub fn exit_code(self) -> u8 {
+        match self {
+            ErrorKind::Internal => 1,
+            ErrorKind::Usage => 2,
+            ErrorKind::Parse => 3,
+            ErrorKind::Infeasible => 4,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorKind::Usage => "usage",
+            ErrorKind::Parse => "parse",
+            ErrorKind::Infeasible => "infeasible",
+            ErrorKind::Internal => "internal",
+        }
+    }
+}
+
+// {"error": {"kind": "parse", "exit_code": 3, "message": "..."}}
+pub fn error_json(err: &Error) -> String {
+    let kind = ErrorKind::of(err);
+    format!(
+        "{{\"error\":{{\"kind\":\"{}\",\"exit_code\":{},\"message\":{}}}}}",
+        kind.name(),
+        kind.exit_code(),
+        json_string(&err.to_string()),
+    )
+}
@@ -0,0 +1,200 @@
+use core::fmt;
+use std::cmp::Ordering;
+
+use crate::cards::{Cards, HandRanking, Score};
+use crate::equity::{live_hands, remaining_boards, score_hands};
+use crate::hand::Hand;
+use crate::range::HandRange;
+use crate::result::Result;
+
+const CATEGORY_COUNT: usize = HandRanking::CATEGORY_COUNT;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cooler {
+    SetOverSet,
+    StraightOverStraight,
+    FlushOverFlush,
+    FullHouseOverFlush,
+    FullHouseOverFullHouse,
+    QuadsOverFullHouse,
+}
+
+impl fmt::Display for Cooler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Cooler::SetOverSet => "set-over-set",
+            Cooler::StraightOverStraight => "straight-over-straight",
+            Cooler::FlushOverFlush => "flush-over-flush",
+            Cooler::FullHouseOverFlush => "full-house-over-flush",
+            Cooler::FullHouseOverFullHouse => "full-house-over-full-house",
+            Cooler::QuadsOverFullHouse => "quads-over-full-house",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Cooler {
+    pub const COUNT: usize = 6;
+
+    pub const COOLERS: [Cooler; Self::COUNT] = [
+        Cooler::SetOverSet,
+        Cooler::StraightOverStraight,
+        Cooler::FlushOverFlush,
+        Cooler::FullHouseOverFlush,
+        Cooler::FullHouseOverFullHouse,
+        Cooler::QuadsOverFullHouse,
+    ];
+
+    fn classify(winner: Strength, loser: Strength) -> Option<Self> {
+        use Strength::*;
+        match (winner, loser) {
+            (Set, Set) => Some(Cooler::SetOverSet),
+            (Straight, Straight) => Some(Cooler::StraightOverStraight),
+            (Flush, Flush) => Some(Cooler::FlushOverFlush),
+            (FullHouse, Flush) => Some(Cooler::FullHouseOverFlush),
+            (FullHouse, FullHouse) => Some(Cooler::FullHouseOverFullHouse),
+            (Quads, FullHouse) => Some(Cooler::QuadsOverFullHouse),
+            _ => None,
+        }
+    }
+}
+
+// The strong made hands relevant for coolers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Strength {
+    Other,
+    // Three of a kind made with a pocket pair.
+    Set,
+    Straight,
+    Flush,
+    FullHouse,
+    Quads,
+}
+
+impl Strength {
+    fn of(hand: Hand, score: Score) -> Self {
+        match score.to_hand_ranking() {
+            HandRanking::ThreeOfAKind(trips)
+                if hand.high().rank() == trips && hand.low().rank() == trips => Strength::Set,
+            HandRanking::Straight => Strength::Straight,
+            HandRanking::Flush => Strength::Flush,
+            HandRanking::FullHouse { .. } => Strength::FullHouse,
+            HandRanking::FourOfAKind(_) => Strength::Quads,
+            _ => Strength::Other,
+        }
+    }
+}
+
+struct Scored {
+    cards: Cards,
+    score: Score,
+    category: usize,
+    strength: Strength,
+}
+
+// How often the hands of range A and range B meet in a cooler,
+// over all runouts of the community cards and all non-overlapping combos.
+pub struct CoolerStats {
+    pub showdowns: u64,
+    pub a_over_b: [u64; Cooler::COUNT],
+    pub b_over_a: [u64; Cooler::COUNT],
+    pub a_categories: [u64; CATEGORY_COUNT],
+    pub b_categories: [u64; CATEGORY_COUNT],
+}
+
+impl fmt::Display for CoolerStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "showdowns: {}", self.showdowns)?;
+        for cooler in Cooler::COOLERS {
+            let a_over_b = self.a_over_b[cooler as usize];
+            let b_over_a = self.b_over_a[cooler as usize];
+            writeln!(
+                f,
+                "{}: {:.2}% (a over b={:.2}% b over a={:.2}%)",
+                cooler,
+                self.percent(a_over_b + b_over_a),
+                self.percent(a_over_b),
+                self.percent(b_over_a),
+            )?;
+        }
+        for (index, name) in HandRanking::CATEGORY_NAMES.iter().enumerate() {
+            writeln!(
+                f,
+                "{}: a={:.2}% b={:.2}%",
+                name,
+                self.percent(self.a_categories[index]),
+                self.percent(self.b_categories[index]),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl CoolerStats {
+    pub fn enumerate(
+        community_cards: Cards,
+        range_a: &impl HandRange,
+        range_b: &impl HandRange,
+    ) -> Result<Self> {
+        if !(3..=5).contains(&community_cards.count()) {
+            return Err("cooler analysis failed: expected 3 to 5 community cards".into());
+        }
+        let hands_a = live_hands(community_cards, range_a);
+        let hands_b = live_hands(community_cards, range_b);
+
+        let mut stats = CoolerStats {
+            showdowns: 0,
+            a_over_b: [0; Cooler::COUNT],
+            b_over_a: [0; Cooler::COUNT],
+            a_categories: [0; CATEGORY_COUNT],
+            b_categories: [0; CATEGORY_COUNT],
+        };
+        for board in remaining_boards(community_cards, Cards::EMPTY) {
+            let scored_a = scored(board, &hands_a);
+            let scored_b = scored(board, &hands_b);
+            for a in &scored_a {
+                for b in &scored_b {
+                    if (a.cards & b.cards) != Cards::EMPTY {
+                        continue;
+                    }
+                    stats.showdowns += 1;
+                    stats.a_categories[a.category] += 1;
+                    stats.b_categories[b.category] += 1;
+                    match a.score.cmp(&b.score) {
+                        Ordering::Greater => if let Some(cooler) = Cooler::classify(a.strength, b.strength) {
+                            stats.a_over_b[cooler as usize] += 1;
+                        },
+                        Ordering::Less => if let Some(cooler) = Cooler::classify(b.strength, a.strength) {
+                            stats.b_over_a[cooler as usize] += 1;
+                        },
+                        Ordering::Equal => (),
+                    }
+                }
+            }
+        }
+        if stats.showdowns == 0 {
+            return Err("cooler analysis failed: no valid matchups".into());
+        }
+        Ok(stats)
+    }
+
+    fn percent(&self, count: u64) -> f64 {
+        count as f64 / self.showdowns as f64 * 100.0
+    }
+
+    pub fn frequency(&self, cooler: Cooler) -> f64 {
+        let count = self.a_over_b[cooler as usize] + self.b_over_a[cooler as usize];
+        count as f64 / self.showdowns as f64
+    }
+}
+
+fn scored(board: Cards, hands: &[Hand]) -> Vec<Scored> {
+    score_hands(board, hands)
+        .map(|(hand, score)| Scored {
+            cards: hand.to_cards(),
+            score,
+            category: score.to_hand_ranking().category(),
+            strength: Strength::of(hand, score),
+        })
+        .collect()
+}
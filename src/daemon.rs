@@ -0,0 +1,545 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use rand::{rngs::SmallRng, SeedableRng};
+
+use crate::cache::{EquityCache, EquityKey, EquityMethod};
+use crate::cards::Cards;
+use crate::cli_error::ErrorKind;
+use crate::equity::{check_live_combos, enumeration_error, Convergence, Equity, EquityResult};
+use crate::error::Error;
+use crate::hand::Hand;
+use crate::json::JsonValue;
+use crate::labels::PlayerLabels;
+use crate::memory::MemoryReport;
+use crate::preflop::{PreflopTable, MAX_OPPONENTS};
+use crate::range::RangeTable;
+use crate::range_registry::WatchedRegistry;
+use crate::result::Result;
+
+// A long running process answering JSON-RPC 2.0 requests over TCP, so the evaluation tables
+// and the preflop table are only built once and equity results are cached.
+// Every request is one line of JSON, the params are the arguments like on the command line, e.g.
+// {"jsonrpc":"2.0","method":"enumerate","params":["AsTd3h","AhTh","QQ+","--players","hero,BB"],"id":1},
+// every response one line of JSON with either the result or the error and the id of the request.
+// Requests without an id are notifications and get no response.
+// Methods: enumerate, simulate, preflop, ranges, memory, ping and shutdown.
+// Villain ranges of enumerate can be names of the range registry,
+// which is reloaded when the file changes (checked every second).
+pub struct Daemon {
+    max_concurrent: usize,
+    active: AtomicUsize,
+    shutdown: AtomicBool,
+    // A handle of every open connection, to disconnect the clients on shutdown.
+    connections: Mutex<HashMap<usize, TcpStream>>,
+    cache: Mutex<EquityCache>,
+    ranges: WatchedRegistry,
+}
+
+const CACHE_CAPACITY: usize = 1024;
+
+const RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+impl Daemon {
+    pub fn new(max_concurrent: usize, ranges_path: PathBuf) -> Result<Self> {
+        if max_concurrent == 0 {
+            return Err("daemon failed: expected at least one concurrent request".into());
+        }
+        Ok(Self {
+            max_concurrent,
+            active: AtomicUsize::new(0),
+            shutdown: AtomicBool::new(false),
+            connections: Mutex::new(HashMap::new()),
+            cache: Mutex::new(EquityCache::new(CACHE_CAPACITY)),
+            ranges: WatchedRegistry::new(ranges_path)?,
+        })
+    }
+
+    // Serves until a shutdown request.
+    pub fn serve(self, address: &str) -> Result<()> {
+        // Warm up before accepting the first request.
+        Cards::warm_up();
+        PreflopTable::active();
+        let listener = TcpListener::bind(address)?;
+        eprintln!("listening on {}", listener.local_addr()?);
+        eprintln!("ranges: {} ({})", self.ranges.path().display(), self.ranges.current().len());
+        self.serve_listener(listener)
+    }
+
+    // On shutdown the listener is closed and the open connections are shut down for reading:
+    // requests in progress are still answered, idle clients are disconnected.
+    fn serve_listener(self, listener: TcpListener) -> Result<()> {
+        let local_address = listener.local_addr()?;
+        let daemon = Arc::new(self);
+        let watcher = {
+            let daemon = Arc::clone(&daemon);
+            thread::spawn(move || daemon.watch_ranges())
+        };
+        let mut handlers = Vec::new();
+        for (id, stream) in listener.incoming().enumerate() {
+            if daemon.shutdown.load(Ordering::SeqCst) {
+                break;
+            }
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let Ok(handle) = stream.try_clone() else {
+                continue;
+            };
+            daemon.connections.lock().unwrap().insert(id, handle);
+            let daemon = Arc::clone(&daemon);
+            handlers.push(thread::spawn(move || {
+                let shutdown = daemon.connection(stream);
+                daemon.connections.lock().unwrap().remove(&id);
+                if shutdown {
+                    daemon.shutdown.store(true, Ordering::SeqCst);
+                    // Wake up the accept loop.
+                    let _ = TcpStream::connect(local_address);
+                }
+            }));
+            handlers.retain(|handler| !handler.is_finished());
+        }
+        drop(listener);
+        for (_, connection) in daemon.connections.lock().unwrap().drain() {
+            // Unblocks the read of the next request, a response in progress is still written.
+            let _ = connection.shutdown(Shutdown::Read);
+        }
+        for handler in handlers {
+            let _ = handler.join();
+        }
+        let _ = watcher.join();
+        Ok(())
+    }
+
+    fn watch_ranges(&self) {
+        let mut reported_error = None;
+        while !self.shutdown.load(Ordering::SeqCst) {
+            thread::sleep(RELOAD_INTERVAL);
+            if self.ranges.reload_if_changed() {
+                eprintln!("ranges: reloaded ({})", self.ranges.current().len());
+            }
+            let error = self.ranges.last_error();
+            if let Some(message) = error.as_ref().filter(|_| error != reported_error) {
+                eprintln!("ranges: keeping the previous ranges, {}", message);
+            }
+            reported_error = error;
+        }
+    }
+
+    // Returns true if a shutdown was requested.
+    fn connection(&self, stream: TcpStream) -> bool {
+        let Ok(mut writer) = stream.try_clone() else {
+            return false;
+        };
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else {
+                return false;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (id, result, shutdown) = match RpcRequest::parse(&line) {
+                Ok(request) if request.method == "shutdown" => {
+                    (request.id, Ok("\"shutdown\"".to_string()), true)
+                },
+                Ok(request) => {
+                    let result = self.request(&request);
+                    (request.id, result, false)
+                },
+                // The id of an invalid request is unknown.
+                Err(err) => (Some(JsonValue::Null), Err(err), false),
+            };
+            if let Some(id) = id {
+                if writeln!(writer, "{}", response_json(&id, result)).is_err() {
+                    return false;
+                }
+            }
+            if shutdown {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn request(&self, request: &RpcRequest) -> std::result::Result<String, RpcError> {
+        if !METHODS.contains(&request.method.as_str()) {
+            return Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("method not found: '{}'", request.method),
+            ));
+        }
+        let params = request.string_params()?;
+        if self.active.fetch_add(1, Ordering::SeqCst) >= self.max_concurrent {
+            self.active.fetch_sub(1, Ordering::SeqCst);
+            return Err(Error::from("busy: too many concurrent requests").into());
+        }
+        let result = self.handle_request(&request.method, params);
+        self.active.fetch_sub(1, Ordering::SeqCst);
+        Ok(result?)
+    }
+
+    // The cache is not locked during a calculation,
+    // concurrent requests for the same spot may both calculate it.
+    fn cached(
+        &self,
+        key: EquityKey,
+        calculate: impl FnOnce() -> Option<EquityResult>,
+    ) -> Option<EquityResult> {
+        if let Some(result) = self.cache.lock().unwrap().get(&key) {
+            return Some(result);
+        }
+        let result = calculate()?;
+        self.cache.lock().unwrap().insert(key, result.clone());
+        Some(result)
+    }
+
+    fn handle_request(&self, method: &str, mut params: Vec<String>) -> Result<String> {
+        let labels = PlayerLabels::from_args(&mut params)?;
+        match (method, params.as_slice()) {
+            ("ping", []) => Ok("\"pong\"".to_string()),
+            ("enumerate", [community_cards_raw, hero_hand_raw, ranges @ ..]) => {
+                let community_cards = community_cards_raw.parse::<Cards>()?;
+                let hero_hand = hero_hand_raw.parse::<Hand>()?;
+                let registry = self.ranges.current();
+                let villain_ranges = ranges.iter()
+                    .map(|raw_range| registry.resolve(raw_range))
+                    .collect::<Result<Vec<_>>>()?;
+                check_live_combos(community_cards, hero_hand, &villain_ranges)?;
+                let method = EquityMethod::Enumerate;
+                let key = EquityKey::new(community_cards, hero_hand, &villain_ranges, method);
+                let result = self.cached(key, || {
+                    Equity::enumerate(community_cards, hero_hand, &villain_ranges)
+                });
+                let Some(result) = result else {
+                    return Err(enumeration_error("enumerate", community_cards, hero_hand, &villain_ranges));
+                };
+                result_json(&result, &labels)
+            },
+            ("simulate", [community_cards_raw, hero_hand_raw, villain_count_raw, rounds_raw]) => {
+                let community_cards = community_cards_raw.parse::<Cards>()?;
+                let hero_hand = hero_hand_raw.parse::<Hand>()?;
+                let villain_count: usize = villain_count_raw.parse()?;
+                let rounds: u64 = rounds_raw.parse()?;
+                // Random villains are the same as villains with every hand.
+                let villain_ranges = vec![RangeTable::full(); villain_count];
+                let method = EquityMethod::Simulate { rounds };
+                let key = EquityKey::new(community_cards, hero_hand, &villain_ranges, method);
+                let result = self.cached(key, || {
+                    Equity::simulate(community_cards, hero_hand, villain_count, rounds, &mut SmallRng::from_entropy())
+                });
+                let Some(result) = result else {
+                    return Err("simulate failed: invalid input".into());
+                };
+                result_json(&result, &labels)
+            },
+            ("preflop", [hero_hand_raw, villain_raw]) => {
+                let hero_hand = hero_hand_raw.parse::<Hand>()?;
+                let table = PreflopTable::active();
+                let equity = match villain_raw.parse::<usize>() {
+                    Ok(villain_count) if (1..=MAX_OPPONENTS).contains(&villain_count) => {
+                        table.vs_random(hero_hand, villain_count)
+                    },
+                    Ok(_) => return Err(format!("preflop failed: expected 1 to {MAX_OPPONENTS} villains").into()),
+                    Err(_) => table.heads_up(hero_hand, villain_raw.parse::<Hand>()?),
+                };
+                Ok(format!("{{\"equity\":{:.4}}}", equity * 100.0))
+            },
+            ("memory", []) => {
+                let cache_memory = self.cache.lock().unwrap().memory();
+                Ok(memory_json(&MemoryReport::loaded().with("equity-cache", cache_memory)))
+            },
+            ("ranges", []) => {
+                self.ranges.reload_if_changed();
+                Ok(ranges_json(&self.ranges))
+            },
+            _ => Err(Error::usage(format!("invalid params of '{}'", method))),
+        }
+    }
+}
+
+const METHODS: [&str; 7] = ["enumerate", "simulate", "preflop", "ranges", "memory", "ping", "shutdown"];
+
+// Error codes of the JSON-RPC 2.0 specification.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+// The first of the codes left to the implementation for server errors.
+const SERVER_ERROR: i64 = -32000;
+
+struct RpcRequest {
+    method: String,
+    params: Option<JsonValue>,
+    // None for a notification.
+    id: Option<JsonValue>,
+}
+
+impl RpcRequest {
+    fn parse(line: &str) -> std::result::Result<Self, RpcError> {
+        let request = JsonValue::parse(line)
+            .map_err(|err| RpcError::new(PARSE_ERROR, err.to_string()))?;
+        let invalid = |message: &str| {
+            RpcError::new(INVALID_REQUEST, format!("invalid request: {}", message))
+        };
+        if !matches!(request.get("jsonrpc"), Ok(JsonValue::String(version)) if version == "2.0") {
+            return Err(invalid("expected \"jsonrpc\":\"2.0\""));
+        }
+        let method = request.get("method")
+            .and_then(JsonValue::as_str)
+            .map_err(|_| invalid("expected a method"))?
+            .to_string();
+        let id = match request.get("id") {
+            Ok(id @ (JsonValue::String(_) | JsonValue::Number(_) | JsonValue::Null)) => Some(id.clone()),
+            Ok(_) => return Err(invalid("expected a string, number or null id")),
+            Err(_) => None,
+        };
+        Ok(Self { method, params: request.get("params").ok().cloned(), id })
+    }
+
+    // Only params by position are supported, every param is a command line argument.
+    fn string_params(&self) -> std::result::Result<Vec<String>, RpcError> {
+        let Some(params) = &self.params else {
+            return Ok(Vec::new());
+        };
+        params.as_array()
+            .and_then(|params| params.iter()
+                .map(|param| param.as_str().map(str::to_string))
+                .collect::<Result<Vec<_>>>())
+            .map_err(|_| RpcError::new(INVALID_PARAMS, "invalid params: expected an array of strings"))
+    }
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+    // The kind and exit code of the error, like the errors of the CLI.
+    kind: Option<ErrorKind>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into(), kind: None }
+    }
+
+    fn json(&self) -> String {
+        let data = match self.kind {
+            Some(kind) => format!(
+                ",\"data\":{{\"kind\":\"{}\",\"exit_code\":{}}}",
+                kind.name(),
+                kind.exit_code(),
+            ),
+            None => String::new(),
+        };
+        format!("{{\"code\":{},\"message\":{}{}}}", self.code, json_string(&self.message), data)
+    }
+}
+
+// Usage and parse errors are invalid params, everything else a server error.
+impl From<Error> for RpcError {
+    fn from(err: Error) -> Self {
+        let kind = ErrorKind::of(&err);
+        let code = match kind {
+            ErrorKind::Usage | ErrorKind::Parse => INVALID_PARAMS,
+            ErrorKind::Infeasible | ErrorKind::Internal => SERVER_ERROR,
+        };
+        Self { code, message: err.to_string(), kind: Some(kind) }
+    }
+}
+
+// {"jsonrpc":"2.0","result":...,"id":...} or {"jsonrpc":"2.0","error":{"code":..,"message":..},"id":...}.
+fn response_json(id: &JsonValue, result: std::result::Result<String, RpcError>) -> String {
+    let id = match id {
+        JsonValue::String(id) => json_string(id),
+        JsonValue::Number(id) => id.to_string(),
+        _ => "null".to_string(),
+    };
+    match result {
+        Ok(result) => format!("{{\"jsonrpc\":\"2.0\",\"result\":{},\"id\":{}}}", result, id),
+        Err(err) => format!("{{\"jsonrpc\":\"2.0\",\"error\":{},\"id\":{}}}", err.json(), id),
+    }
+}
+
+// Version of the result schema, the "schema_version" of every result.
+// Adding a field keeps the version, readers ignore fields they don't know.
+// Removing or renaming a field or changing its meaning or unit increments it.
+// Results from before the version was written have none and read as version 0,
+// which has the same fields as version 1.
+pub const SCHEMA_VERSION: u64 = 1;
+
+// The schema version of a result, an error if it is newer than this program can read.
+pub fn check_schema_version(result: &JsonValue) -> Result<u64> {
+    let version = match result.get("schema_version") {
+        Ok(version) => version.as_f64()? as u64,
+        Err(_) => 0,
+    };
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "unsupported schema version {}, expected at most {}",
+            version,
+            SCHEMA_VERSION,
+        ).into());
+    }
+    Ok(version)
+}
+
+// Equities in the report order of the labels.
+pub fn result_json(result: &EquityResult, labels: &PlayerLabels) -> Result<String> {
+    Ok(format!("{{{}}}", result_json_fields(result, labels)?))
+}
+
+// The result with the convergence of a simulation:
+// "convergence":{"samples":..,"std_dev":..,"std_error":..,"trace":[{"rounds":..,"equity":..,"std_error":..},..]},
+// the trace is empty without a trace interval. All values except counts are percent.
+pub fn simulation_json(result: &EquityResult, convergence: &Convergence, labels: &PlayerLabels) -> Result<String> {
+    let trace = convergence.trace.iter()
+        .map(|point| format!(
+            "{{\"rounds\":{},\"equity\":{:.4},\"std_error\":{:.4}}}",
+            point.rounds,
+            point.equity * 100.0,
+            point.std_error * 100.0,
+        ))
+        .collect::<Vec<_>>()
+        .join(",");
+    Ok(format!(
+        "{{{},\"convergence\":{{\"samples\":{},\"std_dev\":{:.4},\"std_error\":{:.4},\"trace\":[{}]}}}}",
+        result_json_fields(result, labels)?,
+        convergence.samples,
+        convergence.variance().sqrt() * 100.0,
+        convergence.std_error() * 100.0,
+        trace,
+    ))
+}
+
+fn result_json_fields(result: &EquityResult, labels: &PlayerLabels) -> Result<String> {
+    let equities = labels.labeled(&result.equities)?
+        .into_iter()
+        .map(|(label, equity)| format!(
+            "{{\"player\":{},\"equity\":{:.4},\"win\":{:.4},\"tie\":{:.4}}}",
+            json_string(&label),
+            equity.equity_percent() * 100.0,
+            equity.win_percent() * 100.0,
+            equity.tie_percent() * 100.0,
+        ))
+        .collect::<Vec<_>>()
+        .join(",");
+    Ok(format!(
+        "\"schema_version\":{},\"equities\":[{}],\"boards\":{},\"showdowns\":{},\"time_ms\":{:.1}",
+        SCHEMA_VERSION,
+        equities,
+        result.work.boards,
+        result.work.showdowns,
+        result.work.elapsed.as_secs_f64() * 1000.0,
+    ))
+}
+
+// Bytes of every part of the report.
+fn memory_json(report: &MemoryReport) -> String {
+    let parts = report.parts.iter()
+        .map(|(name, bytes)| format!("{}:{}", json_string(name), bytes))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!(
+        "{{\"score_backend\":{},\"parts\":{{{}}},\"total\":{}}}",
+        json_string(report.backend.name()),
+        parts,
+        report.total(),
+    )
+}
+
+// The registered ranges and the error of the last reload, if the file is invalid.
+fn ranges_json(ranges: &WatchedRegistry) -> String {
+    let registry = ranges.current();
+    let entries = registry.iter()
+        .map(|(name, raw_range, range)| format!(
+            "{{\"name\":{},\"range\":{},\"combos\":{}}}",
+            json_string(name),
+            json_string(raw_range),
+            range.count_cards() / 2,
+        ))
+        .collect::<Vec<_>>()
+        .join(",");
+    let error = match ranges.last_error() {
+        Some(error) => json_string(&error),
+        None => "null".to_string(),
+    };
+    format!(
+        "{{\"path\":{},\"ranges\":[{}],\"error\":{}}}",
+        json_string(&ranges.path().display().to_string()),
+        entries,
+        error,
+    )
+}
+
+pub fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            ch if ch.is_control() => out.push_str(&format!("\\u{:04x}", u32::from(ch))),
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    use super::Daemon;
+
+    fn call(reader: &mut BufReader<TcpStream>, request: &str) -> String {
+        writeln!(reader.get_mut(), "{}", request).unwrap();
+        let mut response = String::new();
+        reader.read_line(&mut response).unwrap();
+        response.trim_end().to_string()
+    }
+
+    #[test]
+    fn shutdown_disconnects_idle_clients() {
+        let daemon = Daemon::new(1, "tests/fixtures/missing-ranges.txt".into()).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let (done_sender, done) = mpsc::channel();
+        thread::spawn(move || done_sender.send(daemon.serve_listener(listener).is_ok()));
+
+        let mut idle = BufReader::new(TcpStream::connect(address).unwrap());
+        let mut client = BufReader::new(TcpStream::connect(address).unwrap());
+        assert_eq!(
+            call(&mut client, r#"{"jsonrpc":"2.0","method":"ping","id":1}"#),
+            r#"{"jsonrpc":"2.0","result":"pong","id":1}"#,
+        );
+        // A notification gets no response, the next response is for "a".
+        writeln!(client.get_mut(), r#"{{"jsonrpc":"2.0","method":"ping"}}"#).unwrap();
+        assert_eq!(
+            call(&mut client, r#"{"jsonrpc":"2.0","method":"fold","id":"a"}"#),
+            r#"{"jsonrpc":"2.0","error":{"code":-32601,"message":"method not found: 'fold'"},"id":"a"}"#,
+        );
+        assert!(call(&mut client, r#"{"jsonrpc":"2.0","method":"preflop","params":["AhAd"],"id":2}"#)
+            .starts_with(r#"{"jsonrpc":"2.0","error":{"code":-32602,"#));
+        assert!(call(&mut client, r#"{"jsonrpc":"2.0","method":"ping""#)
+            .starts_with(r#"{"jsonrpc":"2.0","error":{"code":-32700,"#));
+        assert!(call(&mut client, r#"{"method":"ping","id":3}"#)
+            .starts_with(r#"{"jsonrpc":"2.0","error":{"code":-32600,"#));
+        assert_eq!(
+            call(&mut client, r#"{"jsonrpc":"2.0","method":"shutdown","id":4}"#),
+            r#"{"jsonrpc":"2.0","result":"shutdown","id":4}"#,
+        );
+
+        assert_eq!(done.recv_timeout(Duration::from_secs(10)), Ok(true));
+        let mut line = String::new();
+        assert_eq!(idle.read_line(&mut line).unwrap(), 0);
+    }
+}
@@ -0,0 +1,446 @@
+use core::fmt;
+
+use rand::Rng;
+
+use crate::card::Card;
+use crate::cards::Cards;
+use crate::equity::{Deck, ShowdownResult};
+use crate::error::Error;
+use crate::hand::Hand;
+use crate::range::RangeTable;
+use crate::result::Result;
+
+pub const MAX_SEATS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+impl fmt::Display for Street {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Street::Preflop => "preflop",
+            Street::Flop => "flop",
+            Street::Turn => "turn",
+            Street::River => "river",
+        };
+        f.write_str(name)
+    }
+}
+
+impl Street {
+    pub const STREETS: [Street; 4] = [Street::Preflop, Street::Flop, Street::Turn, Street::River];
+
+    pub fn board_size(self) -> u8 {
+        match self {
+            Street::Preflop => 0,
+            Street::Flop => 3,
+            Street::Turn => 4,
+            Street::River => 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    // Folding without a bet to call is a check.
+    Fold,
+    // A check without a bet to call, all-in if the stack is too short.
+    Call,
+    // Bet or raise to a total of this many chips on the street,
+    // less than a min raise is a min raise and more than the stack is all-in.
+    RaiseTo(u64),
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Fold => write!(f, "fold"),
+            Action::Call => write!(f, "call"),
+            Action::RaiseTo(to) => write!(f, "raise-to {}", to),
+        }
+    }
+}
+
+// What a player knows when it's their turn.
+#[derive(Debug, Clone, Copy)]
+pub struct Decision<'a> {
+    pub seat: usize,
+    pub button: usize,
+    pub street: Street,
+    pub hand: Hand,
+    pub board: Cards,
+    // Every chip put in this hand, including the current street.
+    pub pot: u64,
+    pub to_call: u64,
+    pub min_raise_to: u64,
+    // Chips behind and put in on the current street, by seat.
+    pub stacks: &'a [u64],
+    pub bets: &'a [u64],
+}
+
+// Decides the actions of a seat, implemented for closures taking a &Decision.
+pub trait Player {
+    fn act(&mut self, decision: &Decision) -> Action;
+}
+
+impl <F: FnMut(&Decision) -> Action> Player for F {
+    fn act(&mut self, decision: &Decision) -> Action {
+        self(decision)
+    }
+}
+
+// Plays the actions in order over all hands, afterwards it only calls.
+#[derive(Debug, Clone)]
+pub struct ScriptedPlayer {
+    actions: Vec<Action>,
+    next: usize,
+}
+
+impl ScriptedPlayer {
+    pub fn new(actions: Vec<Action>) -> Self {
+        Self { actions, next: 0 }
+    }
+}
+
+impl Player for ScriptedPlayer {
+    fn act(&mut self, _decision: &Decision) -> Action {
+        let action = self.actions.get(self.next).copied().unwrap_or(Action::Call);
+        self.next += 1;
+        action
+    }
+}
+
+// Goes all-in with the hands of its range and folds the others,
+// checks if there is nothing to call. E.g. push/fold ranges.
+#[derive(Clone)]
+pub struct RangePlayer {
+    range: RangeTable,
+}
+
+impl RangePlayer {
+    pub fn new(range: RangeTable) -> Self {
+        Self { range }
+    }
+}
+
+impl Player for RangePlayer {
+    fn act(&mut self, decision: &Decision) -> Action {
+        if self.range.contains(decision.hand) {
+            Action::RaiseTo(u64::MAX)
+        } else {
+            Action::Fold
+        }
+    }
+}
+
+// The cards of a hand, dealt before it is played, so the same cards
+// can be played more than once, e.g. with the players swapped (see duplicate).
+#[derive(Debug, Clone)]
+pub struct Deal {
+    // By seat, seats sitting out don't get theirs.
+    pub hands: Vec<Hand>,
+    // In the order of the streets.
+    pub board: [Card; 5],
+}
+
+impl Deal {
+    pub fn random(seats: usize, rng: &mut impl Rng) -> Self {
+        let mut deck = Deck::from_cards(rng, Cards::EMPTY);
+        let hands = (0..seats).map(|_| deck.hand(rng).unwrap()).collect();
+        let board = std::array::from_fn(|_| deck.draw(rng).unwrap());
+        Self { hands, board }
+    }
+}
+
+// Everything that happened in a hand, by seat. Actions are recorded as played,
+// e.g. a fold without a bet as a call, a raise above the stack as the all-in amount.
+#[derive(Debug, Clone)]
+pub struct HandRecord {
+    pub button: usize,
+    pub small_blind: usize,
+    pub big_blind: usize,
+    // None for seats without chips, they sit the hand out.
+    pub hands: Vec<Option<Hand>>,
+    // Only the streets that were reached.
+    pub board: Cards,
+    pub actions: Vec<(Street, usize, Action)>,
+    pub contributed: Vec<u64>,
+    pub won: Vec<u64>,
+    pub showdown: bool,
+}
+
+impl HandRecord {
+    pub fn net(&self, seat: usize) -> i64 {
+        i64::try_from(self.won[seat]).unwrap() - i64::try_from(self.contributed[seat]).unwrap()
+    }
+}
+
+// A no-limit hold'em table: the seats with their stacks, the blinds and the button.
+// Every hand is played out with the players' actions, the pots (with side pots)
+// are awarded with ShowdownResult and the stacks and the button are updated.
+// Seats without chips sit out until they get a stack again, see set_stack.
+#[derive(Debug, Clone)]
+pub struct Table {
+    stacks: Vec<u64>,
+    small_blind: u64,
+    big_blind: u64,
+    button: usize,
+}
+
+impl Table {
+    // The button starts at the first seat.
+    pub fn new(stacks: Vec<u64>, small_blind: u64, big_blind: u64) -> Result<Self> {
+        if !(2..=MAX_SEATS).contains(&stacks.len()) {
+            return Err(Error::usage(format!("invalid table: {} seats, expected 2 to {}", stacks.len(), MAX_SEATS)));
+        }
+        if small_blind == 0 || small_blind > big_blind {
+            return Err(Error::usage(format!(
+                "invalid table: blinds {}/{}, expected 0 < small blind <= big blind",
+                small_blind,
+                big_blind,
+            )));
+        }
+        Ok(Self { stacks, small_blind, big_blind, button: 0 })
+    }
+
+    pub fn stacks(&self) -> &[u64] {
+        &self.stacks
+    }
+
+    pub fn set_stack(&mut self, seat: usize, stack: u64) {
+        self.stacks[seat] = stack;
+    }
+
+    pub fn button(&self) -> usize {
+        self.button
+    }
+
+    // Plays one hand with a player per seat and moves the button,
+    // None if fewer than two seats have chips.
+    pub fn play_hand(&mut self, players: &mut [&mut dyn Player], rng: &mut impl Rng) -> Option<HandRecord> {
+        let deal = Deal::random(self.stacks.len(), rng);
+        self.play_deal(players, &deal)
+    }
+
+    // Like play_hand, with the given cards.
+    pub fn play_deal(&mut self, players: &mut [&mut dyn Player], deal: &Deal) -> Option<HandRecord> {
+        assert_eq!(players.len(), self.stacks.len());
+        assert_eq!(deal.hands.len(), self.stacks.len());
+        let seated: Vec<_> = self.stacks.iter().map(|stack| *stack > 0).collect();
+        if seated.iter().filter(|seated| **seated).count() < 2 {
+            return None;
+        }
+        if !seated[self.button] {
+            self.button = next_seat(&seated, self.button);
+        }
+        let chips_before: u64 = self.stacks.iter().sum();
+
+        let hands: Vec<_> = seated.iter()
+            .zip(&deal.hands)
+            .map(|(seated, hand)| seated.then_some(*hand))
+            .collect();
+        let mut hand = HandState {
+            stacks: &mut self.stacks,
+            button: self.button,
+            hands: &hands,
+            in_hand: seated.clone(),
+            board: Cards::EMPTY,
+            bets: vec![0; seated.len()],
+            contributed: vec![0; seated.len()],
+            actions: Vec::new(),
+        };
+
+        // Heads up the button posts the small blind and acts first before the flop.
+        let heads_up = seated.iter().filter(|seated| **seated).count() == 2;
+        let small_blind = if heads_up { self.button } else { next_seat(&seated, self.button) };
+        let big_blind = next_seat(&seated, small_blind);
+        hand.put(small_blind, self.small_blind);
+        hand.put(big_blind, self.big_blind);
+
+        for street in Street::STREETS {
+            while hand.board.count() < street.board_size() {
+                hand.board.add(deal.board[usize::from(hand.board.count())]);
+            }
+            if street != Street::Preflop {
+                hand.bets.iter_mut().for_each(|bet| *bet = 0);
+            }
+            let first = if street == Street::Preflop {
+                next_seat(&seated, big_blind)
+            } else {
+                next_seat(&seated, self.button)
+            };
+            hand.betting_round(players, street, first, self.big_blind);
+            if hand.players_in_hand() == 1 {
+                break;
+            }
+        }
+
+        let won = hand.award();
+        let showdown = hand.players_in_hand() > 1;
+        let (board, actions, contributed) = (hand.board, hand.actions, hand.contributed);
+        let record = HandRecord {
+            button: self.button,
+            small_blind,
+            big_blind,
+            hands,
+            board,
+            actions,
+            contributed,
+            won,
+            showdown,
+        };
+        for (stack, won) in self.stacks.iter_mut().zip(&record.won) {
+            *stack += won;
+        }
+        debug_assert_eq!(self.stacks.iter().sum::<u64>(), chips_before);
+        let seated: Vec<_> = self.stacks.iter().map(|stack| *stack > 0).collect();
+        if seated.iter().any(|seated| *seated) {
+            self.button = next_seat(&seated, self.button);
+        }
+        Some(record)
+    }
+}
+
+// The next seat after seat (wrapping around) that is seated,
+// seat itself if it is the only one.
+fn next_seat(seated: &[bool], seat: usize) -> usize {
+    (1..=seated.len())
+        .map(|offset| (seat + offset) % seated.len())
+        .find(|seat| seated[*seat])
+        .unwrap()
+}
+
+struct HandState<'a> {
+    // Chips behind.
+    stacks: &'a mut [u64],
+    button: usize,
+    hands: &'a [Option<Hand>],
+    // Dealt in and not folded.
+    in_hand: Vec<bool>,
+    board: Cards,
+    bets: Vec<u64>,
+    contributed: Vec<u64>,
+    actions: Vec<(Street, usize, Action)>,
+}
+
+impl HandState<'_> {
+    // Puts up to amount chips in, less if the stack is shorter.
+    fn put(&mut self, seat: usize, amount: u64) {
+        let amount = amount.min(self.stacks[seat]);
+        self.stacks[seat] -= amount;
+        self.bets[seat] += amount;
+        self.contributed[seat] += amount;
+    }
+
+    fn players_in_hand(&self) -> usize {
+        self.in_hand.iter().filter(|in_hand| **in_hand).count()
+    }
+
+    fn can_act(&self, seat: usize) -> bool {
+        self.in_hand[seat] && self.stacks[seat] > 0
+    }
+
+    fn betting_round(
+        &mut self,
+        players: &mut [&mut dyn Player],
+        street: Street,
+        first: usize,
+        big_blind: u64,
+    ) {
+        let seat_count = self.stacks.len();
+        let mut current_bet = self.bets.iter().copied().max().unwrap();
+        let mut min_raise = big_blind;
+        let mut pending: Vec<_> = (0..seat_count).map(|seat| self.can_act(seat)).collect();
+        let mut seat = first;
+        while pending.iter().any(|pending| *pending) && self.players_in_hand() > 1 {
+            if pending[seat] {
+                pending[seat] = false;
+                let to_call = current_bet - self.bets[seat];
+                let others_can_act = (0..seat_count).any(|other| other != seat && self.can_act(other));
+                if to_call == 0 && !others_can_act {
+                    seat = (seat + 1) % seat_count;
+                    continue;
+                }
+                let decision = Decision {
+                    seat,
+                    button: self.button,
+                    street,
+                    hand: self.hands[seat].unwrap(),
+                    board: self.board,
+                    pot: self.contributed.iter().sum(),
+                    to_call,
+                    min_raise_to: current_bet + min_raise,
+                    stacks: self.stacks,
+                    bets: &self.bets,
+                };
+                let max_to = self.bets[seat] + self.stacks[seat];
+                let action = match players[seat].act(&decision) {
+                    Action::Fold if to_call != 0 => Action::Fold,
+                    Action::RaiseTo(to) if max_to > current_bet && others_can_act => {
+                        Action::RaiseTo(to.max(current_bet + min_raise).min(max_to))
+                    },
+                    _ => Action::Call,
+                };
+                match action {
+                    Action::Fold => self.in_hand[seat] = false,
+                    Action::Call => self.put(seat, to_call),
+                    Action::RaiseTo(to) => {
+                        // A short all-in raise is not a full raise, but it still reopens the betting.
+                        min_raise = min_raise.max(to - current_bet);
+                        current_bet = to;
+                        self.put(seat, to - self.bets[seat]);
+                        for (other, pending) in pending.iter_mut().enumerate() {
+                            *pending = other != seat && self.can_act(other);
+                        }
+                    },
+                }
+                self.actions.push((street, seat, action));
+            }
+            seat = (seat + 1) % seat_count;
+        }
+    }
+
+    // The chips won by seat: everything to the last player, otherwise every pot
+    // (the main pot and a side pot for every all-in level) to its best eligible hands.
+    // Odd chips go to the first winners left of the button.
+    fn award(&self) -> Vec<u64> {
+        let seat_count = self.stacks.len();
+        let mut won = vec![0; seat_count];
+        if self.players_in_hand() == 1 {
+            let winner = self.in_hand.iter().position(|in_hand| *in_hand).unwrap();
+            won[winner] = self.contributed.iter().sum();
+            return won;
+        }
+        let order: Vec<_> = (1..=seat_count).map(|offset| (self.button + offset) % seat_count).collect();
+        let mut levels: Vec<_> = (0..seat_count)
+            .filter(|seat| self.in_hand[*seat])
+            .map(|seat| self.contributed[seat])
+            .collect();
+        levels.sort_unstable();
+        levels.dedup();
+        let mut previous_level = 0;
+        for level in levels {
+            let amount: u64 = self.contributed.iter()
+                .map(|contributed| (*contributed).min(level) - (*contributed).min(previous_level))
+                .sum();
+            previous_level = level;
+            let eligible: Vec<_> = order.iter()
+                .copied()
+                .filter(|seat| self.in_hand[*seat] && self.contributed[*seat] >= level)
+                .collect();
+            let scores: Vec<_> = eligible.iter()
+                .map(|seat| (self.hands[*seat].unwrap().to_cards() | self.board).score())
+                .collect();
+            let awards = ShowdownResult::new(&scores).unwrap().award(amount, eligible.len());
+            for (seat, award) in eligible.iter().zip(awards) {
+                won[*seat] += award;
+            }
+        }
+        won
+    }
+}
@@ -0,0 +1,74 @@
+use std::env;
+use std::path::PathBuf;
+
+use crate::result::Result;
+
+const APP_NAME: &str = "poker-equity";
+
+// Overrides the data and config directories, both are then this directory.
+pub const HOME_ENV: &str = "POKER_EQUITY_HOME";
+
+fn home_override() -> Option<PathBuf> {
+    env::var_os(HOME_ENV).filter(|home| !home.is_empty()).map(PathBuf::from)
+}
+
+// Generated data like the preflop table:
+// $XDG_DATA_HOME/poker-equity or ~/.local/share/poker-equity on Linux,
+// ~/Library/Application Support/poker-equity on macOS,
+// %LOCALAPPDATA%\poker-equity on Windows.
+pub fn data_dir() -> Result<PathBuf> {
+    if let Some(home) = home_override() {
+        return Ok(home);
+    }
+    let base = if cfg!(windows) {
+        env_path("LOCALAPPDATA").ok_or("data directory failed: LOCALAPPDATA is not set")?
+    } else if cfg!(target_os = "macos") {
+        home_dir()?.join("Library").join("Application Support")
+    } else {
+        match env_path("XDG_DATA_HOME") {
+            Some(data_home) => data_home,
+            None => home_dir()?.join(".local").join("share"),
+        }
+    };
+    Ok(base.join(APP_NAME))
+}
+
+// User configuration:
+// $XDG_CONFIG_HOME/poker-equity or ~/.config/poker-equity on Linux,
+// ~/Library/Application Support/poker-equity on macOS,
+// %APPDATA%\poker-equity on Windows.
+pub fn config_dir() -> Result<PathBuf> {
+    if let Some(home) = home_override() {
+        return Ok(home);
+    }
+    let base = if cfg!(windows) {
+        env_path("APPDATA").ok_or("config directory failed: APPDATA is not set")?
+    } else if cfg!(target_os = "macos") {
+        home_dir()?.join("Library").join("Application Support")
+    } else {
+        match env_path("XDG_CONFIG_HOME") {
+            Some(config_home) => config_home,
+            None => home_dir()?.join(".config"),
+        }
+    };
+    Ok(base.join(APP_NAME))
+}
+
+// A file in the data directory, creating the directory if necessary.
+pub fn data_file(name: &str) -> Result<PathBuf> {
+    let dir = data_dir()?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|err| format!("data directory failed: {}: {}", dir.display(), err))?;
+    Ok(dir.join(name))
+}
+
+fn home_dir() -> Result<PathBuf> {
+    let var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    env_path(var).ok_or_else(|| format!("home directory failed: {} is not set", var).into())
+}
+
+// Unset, empty and relative paths are ignored, like the XDG base directory spec says.
+fn env_path(var: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(env::var_os(var)?);
+    path.is_absolute().then_some(path)
+}
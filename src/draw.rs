@@ -0,0 +1,176 @@
+use core::fmt;
+use std::time::Instant;
+
+use rand::Rng;
+
+use crate::card::Card;
+use crate::cards::{Cards, HandRanking, Score};
+use crate::equity::{showdown, Deck, Equity, EquityResult, Work};
+use crate::rank::Rank;
+use crate::result::Result;
+use crate::suite::Suite;
+
+const HAND_SIZE: u8 = 5;
+
+const MAX_PLAYERS: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscardStrategy {
+    // Keeps all cards.
+    Stand,
+    // Keeps exactly the given cards.
+    Keep(Cards),
+    // Stands with a straight or better, otherwise keeps
+    // four to a flush, the paired cards, four to an open-ended straight
+    // or the two highest cards, in that order.
+    Simple,
+}
+
+impl DiscardStrategy {
+    pub fn keep(self, hand: Cards) -> Cards {
+        match self {
+            DiscardStrategy::Stand => hand,
+            DiscardStrategy::Keep(cards) => cards,
+            DiscardStrategy::Simple => simple_keep(hand),
+        }
+    }
+}
+
+fn simple_keep(hand: Cards) -> Cards {
+    if hand.score_five().to_hand_ranking() >= HandRanking::Straight {
+        return hand;
+    }
+    for suite in Suite::SUITES {
+        let suited: Vec<_> = hand.iter().filter(|card| card.suite() == suite).collect();
+        if suited.len() == 4 {
+            return Cards::from_slice(&suited).unwrap();
+        }
+    }
+    let paired: Vec<_> = hand.iter()
+        .filter(|card| hand.iter().filter(|other| other.rank() == card.rank()).count() >= 2)
+        .collect();
+    if !paired.is_empty() {
+        return Cards::from_slice(&paired).unwrap();
+    }
+    // Four consecutive ranks below the ace are open-ended.
+    for window in Rank::RANKS[..Rank::COUNT-1].windows(4) {
+        if window.iter().all(|rank| hand.iter().any(|card| card.rank() == *rank)) {
+            let cards: Vec<_> = hand.iter().filter(|card| window.contains(&card.rank())).collect();
+            return Cards::from_slice(&cards).unwrap();
+        }
+    }
+    let mut cards: Vec<_> = hand.iter().collect();
+    cards.sort_by_key(|card| std::cmp::Reverse(card.rank()));
+    Cards::from_slice(&cards[..2]).unwrap()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DrawPlayer {
+    pub hand: Cards,
+    pub strategy: DiscardStrategy,
+}
+
+impl fmt::Display for DrawPlayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for card in self.hand.iter() {
+            write!(f, "{}", card)?;
+        }
+        write!(f, " keep=")?;
+        for card in self.kept().iter() {
+            write!(f, "{}", card)?;
+        }
+        Ok(())
+    }
+}
+
+impl DrawPlayer {
+    // Five cards with an optional discard strategy:
+    // "AsKsQsJs2d" (simple), "AsKsQsJs2d:stand" or "AsKsQsJs2d:AsKsQsJs" (kept cards).
+    pub fn parse(s: &str) -> Result<Self> {
+        let (raw_hand, raw_strategy) = s.split_once(':').unwrap_or((s, "simple"));
+        let hand = raw_hand.parse::<Cards>()?;
+        if hand.count() != HAND_SIZE {
+            return Err(format!("invalid draw player '{}': expected 5 cards", s).into());
+        }
+        let strategy = match raw_strategy {
+            "simple" => DiscardStrategy::Simple,
+            "stand" => DiscardStrategy::Stand,
+            raw_keep => {
+                let keep = if raw_keep == "none" { Cards::EMPTY } else { raw_keep.parse::<Cards>()? };
+                if (keep & hand) != keep {
+                    return Err(format!("invalid draw player '{}': kept cards not in hand", s).into());
+                }
+                DiscardStrategy::Keep(keep)
+            },
+        };
+        Ok(Self { hand, strategy })
+    }
+
+    pub fn kept(self) -> Cards {
+        self.strategy.keep(self.hand)
+    }
+}
+
+pub struct DrawEquity {
+    // Showdown with the dealt five cards.
+    pub pre_draw: Vec<Equity>,
+    pub post_draw: EquityResult,
+}
+
+// Every player discards by its strategy and draws from the deck without
+// the dealt cards, the discarded cards are out of play.
+pub fn draw_simulate(
+    players: &[DrawPlayer],
+    rounds: u64,
+    rng: &mut impl Rng,
+) -> Option<DrawEquity> {
+    if !(2..=MAX_PLAYERS).contains(&players.len()) || rounds == 0 {
+        return None;
+    }
+    let mut dealt_cards = Cards::EMPTY;
+    for player in players {
+        if (dealt_cards & player.hand) != Cards::EMPTY {
+            return None;
+        }
+        dealt_cards |= player.hand;
+    }
+    let draws: u32 = players.iter().map(|player| u32::from(HAND_SIZE - player.kept().count())).sum();
+    if draws > Card::COUNT as u32 - u32::from(dealt_cards.count()) {
+        return None;
+    }
+
+    let scores: Vec<_> = players.iter().map(|player| player.hand.score_five()).collect();
+    let mut wins = vec![0; players.len()];
+    let mut ties = vec![0; players.len()];
+    showdown(&scores, &mut wins, &mut ties);
+    let pre_draw = Equity::from_total_wins_ties(1, &wins, &ties);
+
+    let start = Instant::now();
+    let kept: Vec<_> = players.iter().map(|player| player.kept()).collect();
+    let mut deck = Deck::from_cards(rng, dealt_cards);
+    let mut scores = vec![Score::ZERO; players.len()];
+    let mut wins = vec![0; players.len()];
+    let mut ties = vec![0; players.len()];
+    for _ in 0..rounds {
+        deck.reset();
+        for (kept, score) in kept.iter().zip(scores.iter_mut()) {
+            let mut cards = *kept;
+            while cards.count() < HAND_SIZE {
+                cards.add(deck.draw(rng)?);
+            }
+            *score = cards.score_five();
+        }
+        showdown(&scores, &mut wins, &mut ties);
+    }
+    let work = Work {
+        boards: rounds,
+        showdowns: rounds,
+        elapsed: start.elapsed(),
+        ..Work::default()
+    };
+    let post_draw = EquityResult {
+        equities: Equity::from_total_wins_ties(rounds, &wins, &ties),
+        work,
+    };
+    Some(DrawEquity { pre_draw, post_draw })
+}
@@ -0,0 +1,96 @@
+use core::fmt;
+
+use rand::Rng;
+
+use crate::dealer::{Deal, Player, Table};
+use crate::error::Error;
+use crate::result::Result;
+
+// Duplicate poker heads-up: every deal is played twice with the same cards,
+// the second time with the players in swapped seats, so player a plays both hands
+// from both positions. The luck of the cards largely cancels out in the pair of hands,
+// what remains is the difference between the two strategies.
+#[derive(Debug, Clone, Copy)]
+pub struct DuplicateResult {
+    pub deals: u64,
+    // Net of player a in big blinds, the sum and the squares per pair of hands and per single hand.
+    sum: f64,
+    pair_squares: f64,
+    hand_squares: f64,
+}
+
+impl fmt::Display for DuplicateResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "deals={} a={:+.3} bb/hand std-error={:.3} unpaired-std-error={:.3}",
+            self.deals,
+            self.mean(),
+            self.std_error(),
+            self.unpaired_std_error(),
+        )
+    }
+}
+
+impl DuplicateResult {
+    // Net of player a per hand in big blinds, player b's is the negative.
+    pub fn mean(&self) -> f64 {
+        self.sum / (2 * self.deals) as f64
+    }
+
+    // Of the mean, with the two hands of a deal as one sample.
+    pub fn std_error(&self) -> f64 {
+        std_error(self.sum / 2.0, self.pair_squares / 4.0, self.deals)
+    }
+
+    // Of the mean as if every hand was dealt independently,
+    // to compare with std_error how much variance the duplicate deals cancel.
+    pub fn unpaired_std_error(&self) -> f64 {
+        std_error(self.sum, self.hand_squares, 2 * self.deals)
+    }
+}
+
+fn std_error(sum: f64, squares: f64, samples: u64) -> f64 {
+    if samples < 2 {
+        return 0.0;
+    }
+    let n = samples as f64;
+    let mean = sum / n;
+    ((squares / n - mean * mean).max(0.0) / (n - 1.0)).sqrt()
+}
+
+// Plays deals random deals heads-up between a and b, every deal twice with swapped seats.
+// Both players start every hand with stack chips and the first seat on the button.
+pub fn duplicate(
+    a: &mut dyn Player,
+    b: &mut dyn Player,
+    stack: u64,
+    small_blind: u64,
+    big_blind: u64,
+    deals: u64,
+    rng: &mut impl Rng,
+) -> Result<DuplicateResult> {
+    if deals == 0 {
+        return Err(Error::usage("duplicate failed: expected at least one deal"));
+    }
+    if stack == 0 {
+        return Err(Error::usage("duplicate failed: expected a stack above zero"));
+    }
+    let table = Table::new(vec![stack; 2], small_blind, big_blind)?;
+    let mut result = DuplicateResult {
+        deals,
+        sum: 0.0,
+        pair_squares: 0.0,
+        hand_squares: 0.0,
+    };
+    for _ in 0..deals {
+        let deal = Deal::random(2, rng);
+        let first = table.clone().play_deal(&mut [&mut *a, &mut *b], &deal).unwrap().net(0);
+        let second = table.clone().play_deal(&mut [&mut *b, &mut *a], &deal).unwrap().net(1);
+        let (first, second) = (first as f64 / big_blind as f64, second as f64 / big_blind as f64);
+        result.sum += first + second;
+        result.pair_squares += (first + second).powi(2);
+        result.hand_squares += first.powi(2) + second.powi(2);
+    }
+    Ok(result)
+}
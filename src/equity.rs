@@ -1,9 +1,24 @@
 use core::fmt;
 use std::cmp::min;
+use std::io::Write;
+use std::time::{Duration, Instant};
 
-use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+use rand::{seq::SliceRandom, Rng};
 
-use crate::{card::Card, cards::{Cards, Score}, hand::Hand, range::RangeTable};
+use crate::{card::Card, cards::{Cards, HandRanking, Score}, hand::Hand, range::HandRange, result::Result};
+use crate::error::Error;
+use crate::game_rules::{GameRules, Holdem};
+use crate::range_simulator::{RangeSimulator, SamplingStrategy};
+use crate::weighted_range::WeightedRange;
+
+// Rounds between two progress reports of a simulation.
+pub const PROGRESS_INTERVAL: u64 = 1 << 14;
+
+// Community cards dealt by default, games with shorter or longer boards
+// are supported from MIN_BOARD_SIZE to MAX_BOARD_SIZE.
+pub const BOARD_SIZE: u8 = 5;
+pub const MIN_BOARD_SIZE: u8 = 3;
+pub const MAX_BOARD_SIZE: u8 = 6;
 
 fn try_u64_to_f64(n: u64) -> Option<f64> {
     const F64_MAX_SAFE_INT: u64 = 2 << 53;
@@ -14,10 +29,32 @@ fn try_u64_to_f64(n: u64) -> Option<f64> {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+// Ties are counted exactly, in units of 1/TIE_UNITS of a pot:
+// a pot split between up to 23 players (every player of a hold'em showdown),
+// and also in half for hi/lo and again in half for two boards, is a whole number of units.
+pub const TIE_UNITS: u128 = 4 * 5_354_228_880; // 4 * lcm(1..=23)
+
+// The units of a pot split between the given number of players.
+pub fn tie_units(players: usize) -> u128 {
+    let players = u128::try_from(players).unwrap();
+    assert_eq!(TIE_UNITS % players, 0);
+    TIE_UNITS / players
+}
+
+// Tie units as pots, the only rounding of the exact counts.
+pub fn tie_pots(units: u128) -> f64 {
+    units as f64 / TIE_UNITS as f64
+}
+
+// Pots as tie units, rounded to the nearest unit, e.g. for ties stored as pot shares.
+pub fn tie_units_of_pots(pots: f64) -> u128 {
+    (pots * TIE_UNITS as f64).round() as u128
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Equity {
     wins: u64,
-    ties: f64,
+    ties: u128,
     total: u64,
 }
 
@@ -33,205 +70,2264 @@ impl fmt::Display for Equity {
     }
 }
 
-fn valid_input(
-    community_cards: Cards,
-    hero_cards: Cards,
-    villain_ranges: &[impl AsRef<RangeTable>],
-) -> bool {
-    valid_input_without_ranges(community_cards, hero_cards, villain_ranges.len())
-        && villain_ranges.iter().all(|range| !range.as_ref().is_empty())
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Work {
+    pub boards: u64,
+    pub villain_combos: u64,
+    pub showdowns: u64,
+    // Showdowns on boards where hero beats every villain combo (lock)
+    // or where some villain range beats hero with every combo (drawing dead).
+    pub locks: u64,
+    pub drawing_dead: u64,
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for Work {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "boards={} villain-combos={} showdowns={} locks={} drawing-dead={} time={:.1}ms",
+            self.boards,
+            self.villain_combos,
+            self.showdowns,
+            self.locks,
+            self.drawing_dead,
+            self.elapsed.as_secs_f64() * 1000.0,
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EquityResult {
+    pub equities: Vec<Equity>,
+    pub work: Work,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct EquityEstimate {
+    equity: f64,
+    win: f64,
+    tie: f64,
+    std_error: f64,
+}
+
+impl fmt::Display for EquityEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "equity={:2.2}±{:2.2} win={:2.2} tie={:2.2}",
+            self.equity * 100.0,
+            self.std_error * 100.0,
+            self.win * 100.0,
+            self.tie * 100.0,
+        )
+    }
+}
+
+impl EquityEstimate {
+    pub fn equity_percent(self) -> f64 {
+        self.equity
+    }
+
+    pub fn win_percent(self) -> f64 {
+        self.win
+    }
+
+    pub fn tie_percent(self) -> f64 {
+        self.tie
+    }
+
+    pub fn std_error(self) -> f64 {
+        self.std_error
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct EquitySplit {
+    total: u64,
+    ahead: Equity,
+    behind: Equity,
+}
+
+impl fmt::Display for EquitySplit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ahead={:2.2} (equity={:2.2} win-when-ahead={:2.2}) \
+                behind={:2.2} (equity={:2.2} win-when-behind={:2.2})",
+            self.ahead_percent() * 100.0,
+            self.ahead_equity_percent() * 100.0,
+            self.win_when_ahead_percent() * 100.0,
+            self.behind_percent() * 100.0,
+            self.behind_equity_percent() * 100.0,
+            self.win_when_behind_percent() * 100.0,
+        )
+    }
+}
+
+// Chopped pots of an enumeration by the hand category they occurred with.
+// Board plays counts the chops where the best hand is the board itself.
+#[derive(Debug, Clone, Copy)]
+pub struct EquityChops {
+    pub total: u64,
+    pub chops: u64,
+    pub board_plays: u64,
+    pub by_category: [u64; HandRanking::CATEGORY_COUNT],
+}
+
+impl fmt::Display for EquityChops {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "chops={:2.2} board-plays={:2.2}",
+            self.percent(self.chops),
+            self.percent(self.board_plays),
+        )?;
+        for (name, count) in HandRanking::CATEGORY_NAMES.iter().zip(self.by_category) {
+            if count != 0 {
+                write!(f, " {}={:2.2}", name, self.percent(count))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl EquityChops {
+    // Share of all showdowns in percent.
+    pub fn percent(&self, count: u64) -> f64 {
+        EquitySplit::ratio(count, self.total) * 100.0
+    }
+}
+
+struct ChopTracker {
+    board_score: Score,
+    chops: EquityChops,
+}
+
+impl ChopTracker {
+    fn showdown(&mut self, scores: &[Score]) {
+        self.chops.total += 1;
+        let max_score = scores.iter().copied().max().unwrap();
+        if scores.iter().filter(|score| **score == max_score).count() < 2 {
+            return;
+        }
+        self.chops.chops += 1;
+        if max_score == self.board_score {
+            self.chops.board_plays += 1;
+        }
+        self.chops.by_category[max_score.to_hand_ranking().category()] += 1;
+    }
+}
+
+// Hero share per independent sample of a simulation, a sample is a round
+// or with antithetic sampling a pair of rounds (an odd last round is a sample on its own).
+// With a trace interval the estimate is also recorded every interval rounds and after the last round.
+#[derive(Debug, Clone, Default)]
+pub struct Convergence {
+    pub samples: u64,
+    pub share_sum: f64,
+    pub share_squares: f64,
+    pub trace_interval: u64,
+    pub trace: Vec<ConvergencePoint>,
+}
+
+// The hero equity and its standard error after a number of rounds.
+#[derive(Debug, Clone, Copy)]
+pub struct ConvergencePoint {
+    pub rounds: u64,
+    pub equity: f64,
+    pub std_error: f64,
+}
+
+impl fmt::Display for ConvergencePoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "rounds={} equity={:2.2} std-error={:.3}",
+            self.rounds,
+            self.equity * 100.0,
+            self.std_error * 100.0,
+        )
+    }
+}
+
+impl fmt::Display for Convergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "samples={} sample-std-dev={:2.2} std-error={:.3}",
+            self.samples,
+            self.variance().sqrt() * 100.0,
+            self.std_error() * 100.0,
+        )
+    }
+}
+
+impl Convergence {
+    // Records the estimate every interval rounds, 0 disables the trace.
+    pub fn with_trace(trace_interval: u64) -> Self {
+        Self { trace_interval, ..Self::default() }
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.samples == 0 {
+            0.0
+        } else {
+            self.share_sum / try_u64_to_f64(self.samples).unwrap()
+        }
+    }
+
+    pub fn variance(&self) -> f64 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+        let mean = self.mean();
+        (self.share_squares / try_u64_to_f64(self.samples).unwrap() - mean * mean).max(0.0)
+    }
+
+    // Standard error of the hero equity.
+    pub fn std_error(&self) -> f64 {
+        if self.samples < 2 {
+            return 0.0;
+        }
+        (self.variance() / try_u64_to_f64(self.samples - 1).unwrap()).sqrt()
+    }
+
+    fn sample(&mut self, share: f64) {
+        self.samples += 1;
+        self.share_sum += share;
+        self.share_squares += share * share;
+    }
+
+    fn round_done(&mut self, rounds_done: u64, rounds: u64) {
+        if self.trace_interval != 0 && (rounds_done.is_multiple_of(self.trace_interval) || rounds_done == rounds) {
+            self.trace.push(ConvergencePoint {
+                rounds: rounds_done,
+                equity: self.mean(),
+                std_error: self.std_error(),
+            });
+        }
+    }
+}
+
+// Spread of the pot share of hero (1 win, 1/n n-way chop, 0 loss) per showdown
+// and per runout, where the share of a runout is the mean over all villain combos on it.
+// In a simulation every round is a runout with a single showdown.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EquityRisk {
+    pub showdowns: u64,
+    pub scoops: u64,
+    pub chops: u64,
+    pub losses: u64,
+    pub share_sum: f64,
+    pub share_squares: f64,
+    pub runouts: u64,
+    pub runout_share_squares: f64,
+}
+
+impl fmt::Display for EquityRisk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "std-dev={:2.2} runout-std-dev={:2.2} lose-all={:2.2} chop={:2.2} scoop={:2.2}",
+            self.std_dev() * 100.0,
+            self.runout_std_dev() * 100.0,
+            EquitySplit::ratio(self.losses, self.showdowns) * 100.0,
+            EquitySplit::ratio(self.chops, self.showdowns) * 100.0,
+            EquitySplit::ratio(self.scoops, self.showdowns) * 100.0,
+        )
+    }
+}
+
+impl EquityRisk {
+    pub fn mean(&self) -> f64 {
+        if self.showdowns == 0 {
+            0.0
+        } else {
+            self.share_sum / try_u64_to_f64(self.showdowns).unwrap()
+        }
+    }
+
+    pub fn variance(&self) -> f64 {
+        if self.showdowns == 0 {
+            return 0.0;
+        }
+        let mean = self.mean();
+        (self.share_squares / try_u64_to_f64(self.showdowns).unwrap() - mean * mean).max(0.0)
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    // Runouts are weighted by their number of showdowns.
+    pub fn runout_variance(&self) -> f64 {
+        if self.showdowns == 0 {
+            return 0.0;
+        }
+        let mean = self.mean();
+        (self.runout_share_squares / try_u64_to_f64(self.showdowns).unwrap() - mean * mean).max(0.0)
+    }
+
+    pub fn runout_std_dev(&self) -> f64 {
+        self.runout_variance().sqrt()
+    }
+
+    fn showdown(&mut self, share: f64) {
+        self.showdowns += 1;
+        self.share_sum += share;
+        self.share_squares += share * share;
+        if share == 1.0 {
+            self.scoops += 1;
+        } else if share == 0.0 {
+            self.losses += 1;
+        } else {
+            self.chops += 1;
+        }
+    }
+}
+
+#[derive(Default)]
+struct RiskTracker {
+    risk: EquityRisk,
+    board_showdowns: u64,
+    board_share: f64,
+}
+
+impl RiskTracker {
+    fn showdown(&mut self, share: f64) {
+        self.risk.showdown(share);
+        self.board_showdowns += 1;
+        self.board_share += share;
+    }
+
+    fn finish_board(&mut self) {
+        if self.board_showdowns != 0 {
+            let showdowns = try_u64_to_f64(self.board_showdowns).unwrap();
+            let mean = self.board_share / showdowns;
+            self.risk.runouts += 1;
+            self.risk.runout_share_squares += showdowns * mean * mean;
+        }
+        self.board_showdowns = 0;
+        self.board_share = 0.0;
+    }
+}
+
+// Pot share won by a player, split by whether the player already had the best hand
+// with the community cards known at the start of the enumeration (ahead, ties included)
+// or had to catch up on a later street (behind).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EquityImprove {
+    pub showdowns: u64,
+    pub ahead: u64,
+    pub wins_ahead: f64,
+    pub wins_behind: f64,
+}
+
+impl fmt::Display for EquityImprove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ahead={:2.2} wins-ahead={:2.2} wins-behind={:2.2} needs-to-improve={:2.2}",
+            EquitySplit::ratio(self.ahead, self.showdowns) * 100.0,
+            self.percent(self.wins_ahead),
+            self.percent(self.wins_behind),
+            self.needs_to_improve_percent() * 100.0,
+        )
+    }
+}
+
+impl EquityImprove {
+    // Share of all showdowns in percent.
+    pub fn percent(&self, wins: f64) -> f64 {
+        if self.showdowns == 0 {
+            0.0
+        } else {
+            wins / try_u64_to_f64(self.showdowns).unwrap() * 100.0
+        }
+    }
+
+    // Share of the wins that required improving.
+    pub fn needs_to_improve_percent(&self) -> f64 {
+        let wins = self.wins_ahead + self.wins_behind;
+        if wins == 0.0 {
+            0.0
+        } else {
+            self.wins_behind / wins
+        }
+    }
+}
+
+struct ImproveTracker {
+    villain_scores: Vec<Score>,
+    current_scores: Vec<Score>,
+    improve: Vec<EquityImprove>,
+}
+
+impl ImproveTracker {
+    fn new(
+        community_cards: Cards,
+        hero_cards: Cards,
+        villain_ranges: &[impl HandRange],
+    ) -> Self {
+        // Same current street scores as for the ahead/behind split.
+        let split = SplitTracker::new(community_cards, hero_cards, villain_ranges);
+        ImproveTracker {
+            villain_scores: split.villain_scores,
+            current_scores: vec![split.hero_score; villain_ranges.len() + 1],
+            improve: vec![EquityImprove::default(); villain_ranges.len() + 1],
+        }
+    }
+
+    fn set_villain(&mut self, villain_index: usize, hand: Hand) {
+        self.current_scores[villain_index + 1] = self.villain_scores[SplitTracker::hand_index(hand)];
+    }
+
+    fn showdown(&mut self, scores: &[Score]) {
+        let best_current = self.current_scores.iter().copied().max().unwrap();
+        let best_final = scores.iter().copied().max().unwrap();
+        let winners = scores.iter().filter(|score| **score == best_final).count();
+        let share = 1.0 / winners as f64;
+        for ((improve, current), score) in self.improve.iter_mut().zip(&self.current_scores).zip(scores) {
+            improve.showdowns += 1;
+            let ahead = *current == best_current;
+            if ahead {
+                improve.ahead += 1;
+            }
+            if *score != best_final {
+                continue;
+            }
+            if ahead {
+                improve.wins_ahead += share;
+            } else {
+                improve.wins_behind += share;
+            }
+        }
+    }
+}
+
+// A main or side pot of an all-in with unequal stacks.
+// Shares are the expected pot shares per player (hero first), 0 if not eligible.
+#[derive(Debug, Clone)]
+pub struct SidePot {
+    pub amount: u64,
+    pub eligible: Vec<bool>,
+    pub shares: Vec<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EquitySidePots {
+    pub stacks: Vec<u64>,
+    // Main pot first, an uncalled rest of the largest stack is not a pot.
+    pub pots: Vec<SidePot>,
+}
+
+impl fmt::Display for EquitySidePots {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, pot) in self.pots.iter().enumerate() {
+            if index == 0 {
+                write!(f, "main pot={}:", pot.amount)?;
+            } else {
+                write!(f, "side pot {}={}:", index, pot.amount)?;
+            }
+            for (player, share) in pot.shares.iter().enumerate() {
+                if pot.eligible[player] {
+                    write!(f, " {}={:2.2}", player_label(player), share * 100.0)?;
+                }
+            }
+            writeln!(f)?;
+        }
+        write!(f, "expected chips:")?;
+        for (player, chips) in self.expected_chips().iter().enumerate() {
+            write!(f, " {}={:.2}", player_label(player), chips)?;
+        }
+        Ok(())
+    }
+}
+
+fn player_label(player: usize) -> String {
+    if player == 0 {
+        "hero".to_string()
+    } else {
+        format!("villain-{}", player)
+    }
+}
+
+impl EquitySidePots {
+    // Pots from the all-in stacks, hero first.
+    pub fn new(stacks: &[u64]) -> Self {
+        let mut levels = stacks.to_vec();
+        levels.sort_unstable();
+        levels.dedup();
+        let mut pots = Vec::new();
+        let mut previous_level = 0;
+        for level in levels {
+            let eligible: Vec<_> = stacks.iter().map(|stack| *stack >= level).collect();
+            let players = eligible.iter().filter(|eligible| **eligible).count();
+            if players >= 2 && level > previous_level {
+                pots.push(SidePot {
+                    amount: (level - previous_level) * players as u64,
+                    eligible,
+                    shares: vec![0.0; stacks.len()],
+                });
+            }
+            previous_level = level;
+        }
+        Self { stacks: stacks.to_vec(), pots }
+    }
+
+    // Expected chips won from all pots per player.
+    pub fn expected_chips(&self) -> Vec<f64> {
+        let mut chips = vec![0.0; self.stacks.len()];
+        for pot in &self.pots {
+            for (chips, share) in chips.iter_mut().zip(&pot.shares) {
+                *chips += share * pot.amount as f64;
+            }
+        }
+        chips
+    }
+}
+
+// Sums the pot shares over all showdowns, normalized in to_side_pots.
+struct SidePotTracker {
+    total: u64,
+    side_pots: EquitySidePots,
+}
+
+impl SidePotTracker {
+    fn showdown(&mut self, scores: &[Score]) {
+        self.total += 1;
+        for pot in &mut self.side_pots.pots {
+            let eligible_scores = || scores.iter()
+                .zip(&pot.eligible)
+                .filter(|(_, eligible)| **eligible)
+                .map(|(score, _)| *score);
+            let best = eligible_scores().max().unwrap();
+            let winners = eligible_scores().filter(|score| *score == best).count();
+            for ((share, score), eligible) in pot.shares.iter_mut().zip(scores).zip(&pot.eligible) {
+                if *eligible && *score == best {
+                    *share += 1.0 / winners as f64;
+                }
+            }
+        }
+    }
+
+    fn to_side_pots(&self) -> EquitySidePots {
+        let mut side_pots = self.side_pots.clone();
+        let total = try_u64_to_f64(self.total).unwrap();
+        for pot in &mut side_pots.pots {
+            for share in &mut pot.shares {
+                *share /= total;
+            }
+        }
+        side_pots
+    }
+}
+
+impl EquitySplit {
+    fn ratio(n: u64, total: u64) -> f64 {
+        if total == 0 {
+            0.0
+        } else {
+            try_u64_to_f64(n).unwrap() / try_u64_to_f64(total).unwrap()
+        }
+    }
+
+    fn pot_share(equity: Equity) -> f64 {
+        try_u64_to_f64(equity.wins).unwrap() + tie_pots(equity.ties)
+    }
+
+    pub fn ahead_percent(self) -> f64 {
+        Self::ratio(self.ahead.total, self.total)
+    }
+
+    pub fn behind_percent(self) -> f64 {
+        Self::ratio(self.behind.total, self.total)
+    }
+
+    pub fn ahead_equity_percent(self) -> f64 {
+        Self::pot_share(self.ahead) / try_u64_to_f64(self.total).unwrap()
+    }
+
+    pub fn behind_equity_percent(self) -> f64 {
+        Self::pot_share(self.behind) / try_u64_to_f64(self.total).unwrap()
+    }
+
+    pub fn win_when_ahead_percent(self) -> f64 {
+        if self.ahead.total == 0 {
+            0.0
+        } else {
+            self.ahead.equity_percent()
+        }
+    }
+
+    pub fn win_when_behind_percent(self) -> f64 {
+        if self.behind.total == 0 {
+            0.0
+        } else {
+            self.behind.equity_percent()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HybridEquity {
+    pub equities: Vec<EquityEstimate>,
+    pub total_boards: usize,
+    pub exact_boards: usize,
+    pub sampled_boards: usize,
+    pub work: Work,
+}
+
+// A villain range where every combo shares a card with the community cards or the hero hand,
+// villain is the index into the villain ranges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoLiveCombos {
+    pub villain: usize,
 }
 
-fn valid_input_without_ranges(
-    community_cards: Cards,
-    hero_cards: Cards,
-    villain_count: usize,
-) -> bool {
-    let known_cards = community_cards | hero_cards;
-    hero_cards.count() == 2
-        && community_cards.count() <= 5
-        && known_cards.count() == community_cards.count()+hero_cards.count()
-        && villain_count >= 1 && villain_count <= 8
-}
+impl fmt::Display for NoLiveCombos {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "villain {} has no live combos: every combo of the range is blocked by the community cards or the hero hand",
+            self.villain + 1,
+        )
+    }
+}
+
+impl std::error::Error for NoLiveCombos {}
+
+// Combos of the range not blocked by the known cards.
+pub fn live_combos(known_cards: Cards, range: &impl HandRange) -> usize {
+    let mut live = 0;
+    range.for_each_hand(|hand| {
+        if (hand.to_cards() & known_cards) == Cards::EMPTY {
+            live += 1;
+        }
+    });
+    live
+}
+
+// The combos of the range not blocked by the known cards.
+pub fn live_hands(known_cards: Cards, range: &impl HandRange) -> Vec<Hand> {
+    let mut hands = Vec::new();
+    range.for_each_hand(|hand| if (hand.to_cards() & known_cards) == Cards::EMPTY {
+        hands.push(hand);
+    });
+    hands
+}
+
+// The hands not blocked by the board, each with its score on the board.
+pub fn score_hands(board: Cards, hands: &[Hand]) -> impl Iterator<Item = (Hand, Score)> + '_ {
+    hands.iter()
+        .copied()
+        .filter(move |hand| (hand.to_cards() & board) == Cards::EMPTY)
+        .map(move |hand| (hand, (board | hand.to_cards()).score_fast()))
+}
+
+// The first villain range without live combos, if any.
+pub fn check_live_combos(
+    community_cards: Cards,
+    hero_hand: Hand,
+    villain_ranges: &[impl HandRange],
+) -> std::result::Result<(), NoLiveCombos> {
+    let known_cards = community_cards | hero_hand.to_cards();
+    for (villain, range) in villain_ranges.iter().enumerate() {
+        let mut live = false;
+        range.for_each_hand(|hand| live |= (hand.to_cards() & known_cards) == Cards::EMPTY);
+        if !live {
+            return Err(NoLiveCombos { villain });
+        }
+    }
+    Ok(())
+}
+
+// Why an enumeration of Equity failed: a hero card on the board (Error::DuplicateCard),
+// a villain without live combos (Error::NoLiveCombos), too large (Error::TooLarge)
+// or any other invalid input (Error::Usage).
+pub fn enumeration_error(
+    command: &str,
+    community_cards: Cards,
+    hero_hand: Hand,
+    villain_ranges: &[impl HandRange],
+) -> Error {
+    board_size_enumeration_error(command, community_cards, hero_hand, villain_ranges, BOARD_SIZE)
+}
+
+// Same as enumeration_error for boards of board_size cards.
+pub fn board_size_enumeration_error(
+    command: &str,
+    community_cards: Cards,
+    hero_hand: Hand,
+    villain_ranges: &[impl HandRange],
+    board_size: u8,
+) -> Error {
+    let hero_cards = hero_hand.to_cards();
+    if let Some(card) = (community_cards & hero_cards).first() {
+        return Error::DuplicateCard(card);
+    }
+    if let Err(err) = check_live_combos(community_cards, hero_hand, villain_ranges) {
+        return err.into();
+    }
+    let too_large = valid_input(community_cards, hero_cards, villain_ranges, board_size)
+        && u64::try_from(total_combos_upper_bound(community_cards, villain_ranges, board_size)).is_err();
+    if too_large {
+        Error::too_large(format!("{} failed: expected sample to large", command))
+    } else {
+        Error::usage(format!("{} failed: invalid input", command))
+    }
+}
+
+fn valid_input(
+    community_cards: Cards,
+    hero_cards: Cards,
+    villain_ranges: &[impl HandRange],
+    board_size: u8,
+) -> bool {
+    valid_input_without_ranges(community_cards, hero_cards, villain_ranges.len(), board_size)
+        && villain_ranges.iter().all(|range| !range.is_empty())
+}
+
+fn valid_input_without_ranges(
+    community_cards: Cards,
+    hero_cards: Cards,
+    villain_count: usize,
+    board_size: u8,
+) -> bool {
+    let known_cards = community_cards | hero_cards;
+    hero_cards.count() == 2
+        && (MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&board_size)
+        && community_cards.count() <= board_size
+        && known_cards.count() == community_cards.count()+hero_cards.count()
+        && (1..=8).contains(&villain_count)
+}
+
+// The engine deals two hole cards to every player and the board from the deck of the rules.
+fn valid_rules(rules: &impl GameRules, known_cards: Cards, villain_count: usize, board_size: u8) -> bool {
+    let dealt = usize::from(board_size) + 2*villain_count + 2;
+    rules.hole_cards() == (2..=2)
+        && (known_cards & !rules.deck()) == Cards::EMPTY
+        && usize::from(rules.deck().count()) >= dealt
+}
+
+pub fn total_combos_upper_bound(
+    community_cards: Cards,
+    villain_ranges: &[impl HandRange],
+    board_size: u8,
+) -> u128 {
+    assert!(villain_ranges.len() <= 8);
+    assert!(villain_ranges.iter().all(|range| !range.is_empty()));
+    let community_cards_count = community_cards.count();
+    assert!(community_cards_count <= board_size);
+    let mut remaining_cards = {
+        let remaining_cards = Card::COUNT - usize::from(community_cards_count) - 2;
+        u128::try_from(remaining_cards).unwrap()
+    };
+    let mut count = 1u128;
+
+    for _ in community_cards_count..board_size {
+        count *= remaining_cards;
+        remaining_cards -= 1;
+    }
+
+    let mut max_count = count;
+    for _ in 0..villain_ranges.len()*2 {
+        max_count *= remaining_cards;
+        remaining_cards -= 1;
+    }
+
+    for range in villain_ranges {
+        let next_count = count.checked_mul(u128::from(range.count_cards()));
+        match next_count {
+            Some(n) => count = n,
+            None => return u128::MAX,
+        };
+    }
+
+    min(count, max_count)
+}
+
+pub struct VillainPlan {
+    pub combos: u64,
+    // Combos not blocked by the community cards or the hero hand.
+    pub live_combos: u64,
+}
+
+// Breakdown of the enumeration size: boards to deal times the live combos of every villain.
+// The product ignores the blocking between villains and the dealt board cards,
+// so it's an upper bound of the showdowns.
+pub struct EnumerationPlan {
+    pub boards: u64,
+    pub villains: Vec<VillainPlan>,
+    pub showdowns_upper_bound: u128,
+    pub feasible: bool,
+}
+
+impl fmt::Display for EnumerationPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "boards: {}", self.boards)?;
+        for (index, villain) in self.villains.iter().enumerate() {
+            writeln!(
+                f,
+                "villain {}: combos={} live={}",
+                index + 1,
+                villain.combos,
+                villain.live_combos,
+            )?;
+        }
+        write!(
+            f,
+            "showdowns: <={} feasible={}",
+            self.showdowns_upper_bound,
+            self.feasible,
+        )
+    }
+}
+
+impl EnumerationPlan {
+    pub fn new(
+        community_cards: Cards,
+        hero_hand: Hand,
+        villain_ranges: &[impl HandRange],
+    ) -> Option<Self> {
+        let hero_cards = hero_hand.to_cards();
+        if !valid_input(community_cards, hero_cards, villain_ranges, BOARD_SIZE) {
+            return None;
+        }
+        let known_cards = community_cards | hero_cards;
+        let villains: Vec<_> = villain_ranges.iter().map(|range| {
+            let mut combos = 0;
+            let mut live_combos = 0;
+            range.for_each_hand(|hand| {
+                combos += 1;
+                if (hand.to_cards() & known_cards) == Cards::EMPTY {
+                    live_combos += 1;
+                }
+            });
+            VillainPlan { combos, live_combos }
+        }).collect();
+
+        let available = Card::COUNT as u64 - u64::from(known_cards.count());
+        let remaining = u64::from(BOARD_SIZE - community_cards.count());
+        let boards = (0..remaining)
+            .fold(1, |boards, i| boards * (available - i) / (i + 1));
+        let showdowns_upper_bound = villains.iter()
+            .fold(u128::from(boards), |count, villain| {
+                count.saturating_mul(u128::from(villain.live_combos))
+            });
+        let feasible = u64::try_from(
+            total_combos_upper_bound(community_cards, villain_ranges, BOARD_SIZE),
+        ).is_ok();
+        Some(Self { boards, villains, showdowns_upper_bound, feasible })
+    }
+}
+
+impl Equity {
+    // Ties in units of TIE_UNITS, see tie_units.
+    pub fn from_counts(wins: u64, ties: u128, total: u64) -> Self {
+        Equity { wins, ties, total }
+    }
+
+    // Ties in units of TIE_UNITS, e.g. as accumulated by showdown.
+    pub fn from_total_wins_ties(total: u64, wins: &[u64], ties: &[u128]) -> Vec<Self> {
+        assert_ne!(total, 0);
+        assert_eq!(wins.len(), ties.len());
+        let mut equities = Vec::with_capacity(wins.len());
+        for (wins, ties) in wins.iter().copied().zip(ties.iter().copied()) {
+            equities.push(Equity { wins, ties, total });
+        }
+        equities
+    }
+
+    // Sums two partial results of the same player, e.g. of parallel workers,
+    // shards of the boards or batches of rounds, as if they were calculated at once.
+    pub fn merge(&self, other: &Equity) -> Equity {
+        Equity {
+            wins: self.wins + other.wins,
+            ties: self.ties + other.ties,
+            total: self.total + other.total,
+        }
+    }
+
+    // Like merge, for the equities of every player (in the same order).
+    // None if the number of players differs.
+    pub fn merge_all(a: &[Equity], b: &[Equity]) -> Option<Vec<Equity>> {
+        if a.len() != b.len() {
+            return None;
+        }
+        Some(a.iter().zip(b).map(|(a, b)| a.merge(b)).collect())
+    }
+
+    pub fn enumerate(
+        community_cards: Cards,
+        hero_hand: Hand,
+        villain_ranges: &[impl HandRange],
+    ) -> Option<EquityResult> {
+        EquityCalculator::new(
+            community_cards,
+            hero_hand.to_cards(),
+            villain_ranges,
+        )?.enumerate().map(|calculator| calculator.result())
+    }
+
+    // Like enumerate, but a villain range without live combos is a NoLiveCombos error.
+    pub fn try_enumerate(
+        community_cards: Cards,
+        hero_hand: Hand,
+        villain_ranges: &[impl HandRange],
+    ) -> Result<EquityResult> {
+        check_live_combos(community_cards, hero_hand, villain_ranges)?;
+        Self::enumerate(community_cards, hero_hand, villain_ranges)
+            .ok_or_else(|| enumeration_error("enumerate", community_cards, hero_hand, villain_ranges))
+    }
+
+    // Like enumerate, calling the hook for every showdown. Returns the hook with the result.
+    pub fn enumerate_with_hook<H: ShowdownHook>(
+        community_cards: Cards,
+        hero_hand: Hand,
+        villain_ranges: &[impl HandRange],
+        hook: H,
+    ) -> Option<(EquityResult, H)> {
+        let calculator = EquityCalculator::new(community_cards, hero_hand.to_cards(), villain_ranges)?
+            .with_hook(hook)
+            .enumerate()?;
+        let result = calculator.result();
+        Some((result, calculator.hook))
+    }
+
+    // Like enumerate, with the rules of a variant with two hole cards,
+    // e.g. Holdem::with_evaluator(NaiveEvaluator) to score the hands with another evaluator.
+    pub fn enumerate_with_rules(
+        community_cards: Cards,
+        hero_hand: Hand,
+        villain_ranges: &[impl HandRange],
+        rules: impl GameRules,
+    ) -> Option<EquityResult> {
+        EquityCalculator::with_rules(community_cards, hero_hand.to_cards(), villain_ranges, rules)?
+            .enumerate()
+            .map(|calculator| calculator.result())
+    }
+
+    // Like enumerate, the dead cards are neither dealt to the board nor held by a villain.
+    pub fn enumerate_with_dead_cards(
+        community_cards: Cards,
+        hero_hand: Hand,
+        dead_cards: Cards,
+        villain_ranges: &[impl HandRange],
+    ) -> Option<EquityResult> {
+        EquityCalculator::new(community_cards, hero_hand.to_cards(), villain_ranges)?
+            .with_dead_cards(dead_cards)?
+            .enumerate()
+            .map(|calculator| calculator.result())
+    }
+
+    // Like enumerate, for games that deal board_size community cards
+    // (MIN_BOARD_SIZE to MAX_BOARD_SIZE) instead of 5.
+    pub fn enumerate_board_size(
+        community_cards: Cards,
+        hero_hand: Hand,
+        villain_ranges: &[impl HandRange],
+        board_size: u8,
+    ) -> Option<EquityResult> {
+        EquityCalculator::new(
+            community_cards,
+            hero_hand.to_cards(),
+            villain_ranges,
+        )?.with_board_size(board_size)?.enumerate().map(|calculator| calculator.result())
+    }
+
+    /// Enumerates the boards exactly for the first half of the time limit,
+    /// the remaining boards are sampled (without replacement) until the limit is reached.
+    /// Every visited board is evaluated against all villain combos.
+    pub fn hybrid(
+        community_cards: Cards,
+        hero_hand: Hand,
+        villain_ranges: &[impl HandRange],
+        time_limit: Duration,
+        rng: &mut impl Rng,
+    ) -> Option<HybridEquity> {
+        const MIN_SAMPLES: usize = 2;
+
+        let start = Instant::now();
+        let exact_deadline = start + time_limit / 2;
+        let deadline = start + time_limit;
+
+        let hero_cards = hero_hand.to_cards();
+        let mut calculator = EquityCalculator::new(
+            community_cards,
+            hero_cards,
+            villain_ranges,
+        )?;
+        let mut boards = remaining_boards(community_cards, hero_cards);
+        let total_boards = boards.len();
+
+        let mut exact_boards = 0;
+        while exact_boards < total_boards && Instant::now() < exact_deadline {
+            calculator.board(boards[exact_boards]);
+            exact_boards += 1;
+        }
+
+        let player_count = villain_ranges.len() + 1;
+        let exact_total = calculator.total;
+        let exact_wins = calculator.wins.clone();
+        let exact_ties = calculator.ties.clone();
+
+        let remaining = &mut boards[exact_boards..];
+        let mut samples = Vec::new();
+        for i in 0..remaining.len() {
+            if i >= MIN_SAMPLES && Instant::now() >= deadline {
+                break;
+            }
+            let j = rng.gen_range(i..remaining.len());
+            remaining.swap(i, j);
+
+            let total_before = calculator.total;
+            let wins_before = calculator.wins.clone();
+            let ties_before = calculator.ties.clone();
+            calculator.board(remaining[i]);
+            let sample = BoardSample {
+                total: calculator.total - total_before,
+                wins: calculator.wins.iter()
+                    .zip(wins_before)
+                    .map(|(after, before)| after - before)
+                    .collect(),
+                ties: calculator.ties.iter()
+                    .zip(ties_before)
+                    .map(|(after, before)| after - before)
+                    .collect(),
+            };
+            samples.push(sample);
+        }
+
+        let remaining_boards = remaining.len();
+        let sampled_boards = samples.len();
+        let mut equities = Vec::with_capacity(player_count);
+        for player in 0..player_count {
+            let estimate = EquityEstimate::from_exact_and_samples(
+                exact_total,
+                exact_wins[player],
+                tie_pots(exact_ties[player]),
+                remaining_boards,
+                samples.iter().map(|sample| {
+                    (sample.total, sample.wins[player], tie_pots(sample.ties[player]))
+                }),
+            )?;
+            equities.push(estimate);
+        }
+
+        let mut work = calculator.work;
+        work.elapsed = start.elapsed();
+        Some(HybridEquity { equities, total_boards, exact_boards, sampled_boards, work })
+    }
+
+    pub fn enumerate_split(
+        community_cards: Cards,
+        hero_hand: Hand,
+        villain_ranges: &[impl HandRange],
+    ) -> Option<(EquityResult, EquitySplit)> {
+        let mut calculator = EquityCalculator::new(
+            community_cards,
+            hero_hand.to_cards(),
+            villain_ranges,
+        )?;
+        calculator.split = Some(SplitTracker::new(
+            community_cards,
+            hero_hand.to_cards(),
+            villain_ranges,
+        ));
+        let calculator = calculator.enumerate()?;
+        let split = calculator.split.as_ref().unwrap().to_split();
+        Some((calculator.result(), split))
+    }
+
+    pub fn enumerate_chops(
+        community_cards: Cards,
+        hero_hand: Hand,
+        villain_ranges: &[impl HandRange],
+    ) -> Option<(EquityResult, EquityChops)> {
+        let mut calculator = EquityCalculator::new(
+            community_cards,
+            hero_hand.to_cards(),
+            villain_ranges,
+        )?;
+        calculator.chops = Some(ChopTracker {
+            board_score: Score::ZERO,
+            chops: EquityChops {
+                total: 0,
+                chops: 0,
+                board_plays: 0,
+                by_category: [0; HandRanking::CATEGORY_COUNT],
+            },
+        });
+        let calculator = calculator.enumerate()?;
+        let chops = calculator.chops.as_ref().unwrap().chops;
+        Some((calculator.result(), chops))
+    }
+
+    // Stacks of all players, hero first.
+    pub fn enumerate_side_pots(
+        community_cards: Cards,
+        hero_hand: Hand,
+        villain_ranges: &[impl HandRange],
+        stacks: &[u64],
+    ) -> Option<(EquityResult, EquitySidePots)> {
+        if stacks.len() != villain_ranges.len() + 1 || stacks.contains(&0) {
+            return None;
+        }
+        let mut calculator = EquityCalculator::new(
+            community_cards,
+            hero_hand.to_cards(),
+            villain_ranges,
+        )?;
+        calculator.side_pots = Some(SidePotTracker {
+            total: 0,
+            side_pots: EquitySidePots::new(stacks),
+        });
+        let calculator = calculator.enumerate()?;
+        let side_pots = calculator.side_pots.as_ref().unwrap().to_side_pots();
+        Some((calculator.result(), side_pots))
+    }
+
+    // Per player, hero first.
+    pub fn enumerate_improve(
+        community_cards: Cards,
+        hero_hand: Hand,
+        villain_ranges: &[impl HandRange],
+    ) -> Option<(EquityResult, Vec<EquityImprove>)> {
+        let mut calculator = EquityCalculator::new(
+            community_cards,
+            hero_hand.to_cards(),
+            villain_ranges,
+        )?;
+        calculator.improve = Some(ImproveTracker::new(
+            community_cards,
+            hero_hand.to_cards(),
+            villain_ranges,
+        ));
+        let calculator = calculator.enumerate()?;
+        let improve = calculator.improve.as_ref().unwrap().improve.clone();
+        Some((calculator.result(), improve))
+    }
+
+    pub fn enumerate_risk(
+        community_cards: Cards,
+        hero_hand: Hand,
+        villain_ranges: &[impl HandRange],
+    ) -> Option<(EquityResult, EquityRisk)> {
+        let mut calculator = EquityCalculator::new(
+            community_cards,
+            hero_hand.to_cards(),
+            villain_ranges,
+        )?;
+        calculator.risk = Some(RiskTracker::default());
+        let calculator = calculator.enumerate()?;
+        let risk = calculator.risk.as_ref().unwrap().risk;
+        Some((calculator.result(), risk))
+    }
+
+    // Same as enumerate, additionally writes every showdown as CSV to out (see OutcomeWriter).
+    pub fn enumerate_outcomes(
+        community_cards: Cards,
+        hero_hand: Hand,
+        villain_ranges: &[impl HandRange],
+        out: &mut dyn Write,
+    ) -> Result<EquityResult> {
+        let Some(mut calculator) = EquityCalculator::new(
+            community_cards,
+            hero_hand.to_cards(),
+            villain_ranges,
+        ) else {
+            return Err(enumeration_error("enumerate", community_cards, hero_hand, villain_ranges));
+        };
+        calculator.outcomes = Some(OutcomeWriter::new(out, hero_hand, villain_ranges.len()));
+        let Some(mut calculator) = calculator.enumerate() else {
+            return Err(Error::too_large("enumerate failed: expected sample to large"));
+        };
+        calculator.outcomes.take().unwrap().finish()?;
+        Ok(calculator.result())
+    }
+
+    // The rng is only used for dealing, a seeded rng (e.g. SmallRng::seed_from_u64)
+    // makes the simulation reproducible.
+    pub fn simulate(
+        start_community_cards: Cards,
+        hero_hand: Hand,
+        villain_count: usize,
+        rounds: u64,
+        rng: &mut impl Rng,
+    ) -> Option<EquityResult> {
+        let mut options = SimulationOptions::default();
+        Self::simulate_tracked(start_community_cards, hero_hand, villain_count, rounds, rng, &mut options)
+    }
+
+    // Like simulate, for games that deal board_size community cards.
+    pub fn simulate_board_size(
+        start_community_cards: Cards,
+        hero_hand: Hand,
+        villain_count: usize,
+        rounds: u64,
+        board_size: u8,
+        rng: &mut impl Rng,
+    ) -> Option<EquityResult> {
+        let mut options = SimulationOptions { board_size, ..SimulationOptions::default() };
+        Self::simulate_tracked(start_community_cards, hero_hand, villain_count, rounds, rng, &mut options)
+    }
+
+    // Calls progress with the finished rounds every PROGRESS_INTERVAL rounds,
+    // the simulation is cancelled (None) as soon as progress returns false.
+    pub fn simulate_with_progress(
+        start_community_cards: Cards,
+        hero_hand: Hand,
+        villain_count: usize,
+        rounds: u64,
+        rng: &mut impl Rng,
+        mut progress: impl FnMut(u64) -> bool,
+    ) -> Option<EquityResult> {
+        let mut options = SimulationOptions { progress: Some(&mut progress), ..SimulationOptions::default() };
+        Self::simulate_tracked(start_community_cards, hero_hand, villain_count, rounds, rng, &mut options)
+    }
+
+    pub fn simulate_risk(
+        start_community_cards: Cards,
+        hero_hand: Hand,
+        villain_count: usize,
+        rounds: u64,
+        rng: &mut impl Rng,
+    ) -> Option<(EquityResult, EquityRisk)> {
+        let mut options = SimulationOptions { risk: Some(EquityRisk::default()), ..SimulationOptions::default() };
+        let result = Self::simulate_tracked(start_community_cards, hero_hand, villain_count, rounds, rng, &mut options)?;
+        Some((result, options.risk?))
+    }
+
+    // With antithetic sampling every second round deals the partner of the round before:
+    // every dealt card is replaced by its mirror in the available cards ordered by rank,
+    // e.g. an ace by a deuce. The partner is an equally likely deal, so the result stays unbiased,
+    // whether the variance drops depends on the spot, compare the std-error of the convergence.
+    pub fn simulate_convergence(
+        start_community_cards: Cards,
+        hero_hand: Hand,
+        villain_count: usize,
+        rounds: u64,
+        antithetic: bool,
+        trace_interval: u64,
+        rng: &mut impl Rng,
+    ) -> Option<(EquityResult, Convergence)> {
+        let mut options = SimulationOptions {
+            antithetic,
+            convergence: Some(Convergence::with_trace(trace_interval)),
+            ..SimulationOptions::default()
+        };
+        let result = Self::simulate_tracked(start_community_cards, hero_hand, villain_count, rounds, rng, &mut options)?;
+        Some((result, options.convergence?))
+    }
+
+    // Like simulate, calling the hook for every showdown. Returns the hook with the result.
+    pub fn simulate_with_hook<H: ShowdownHook>(
+        start_community_cards: Cards,
+        hero_hand: Hand,
+        villain_count: usize,
+        rounds: u64,
+        rng: &mut impl Rng,
+        hook: H,
+    ) -> Option<(EquityResult, H)> {
+        let mut options = SimulationOptions::default().with_parts(hook, Holdem::new());
+        let result = Self::simulate_tracked(start_community_cards, hero_hand, villain_count, rounds, rng, &mut options)?;
+        Some((result, options.hook))
+    }
+
+    // Like simulate, with the rules of a variant with two hole cards, see enumerate_with_rules.
+    pub fn simulate_with_rules(
+        start_community_cards: Cards,
+        hero_hand: Hand,
+        villain_count: usize,
+        rounds: u64,
+        rng: &mut impl Rng,
+        rules: impl GameRules,
+    ) -> Option<EquityResult> {
+        let mut options = SimulationOptions::default().with_parts((), rules);
+        Self::simulate_tracked(start_community_cards, hero_hand, villain_count, rounds, rng, &mut options)
+    }
+
+    fn simulate_tracked<H: ShowdownHook, R: GameRules>(
+        start_community_cards: Cards,
+        hero_hand: Hand,
+        villain_count: usize,
+        rounds: u64,
+        rng: &mut impl Rng,
+        options: &mut SimulationOptions<H, R>,
+    ) -> Option<EquityResult> {
+        let hero_cards = hero_hand.to_cards();
+        let known_cards = start_community_cards | hero_cards;
+        if !valid_input_without_ranges(start_community_cards, hero_cards, villain_count, options.board_size)
+            || !valid_rules(&options.rules, known_cards, villain_count, options.board_size) {
+            return None;
+        }
+        if rounds == 0 {
+            return None;
+        }
+
+        let start = Instant::now();
+        let remaining_community_cards = options.board_size - start_community_cards.count();
+        let player_count = villain_count + 1;
+
+        let mut scores = vec![Score::ZERO; player_count];
+        let mut wins = vec![0u64; player_count];
+        let mut ties = vec![0; player_count];
+        let mut deck = Deck::from_rules(rng, &options.rules, known_cards);
+        let mirrors = mirrored_cards(known_cards | !options.rules.deck());
+        // Community cards first, then two cards per villain.
+        let deal_count = usize::from(remaining_community_cards) + 2*villain_count;
+        let mut dealt: Vec<Card> = Vec::with_capacity(deal_count);
+        let mut hands = vec![hero_hand; if H::ACTIVE { player_count } else { 0 }];
+
+        for round in 0..rounds {
+            if options.cancelled(round) {
+                return None;
+            }
+            let is_partner = options.antithetic && round % 2 == 1;
+            if is_partner {
+                for card in &mut dealt {
+                    *card = mirrors[card.to_usize()];
+                }
+            } else {
+                deck.reset();
+                dealt.clear();
+                for _ in 0..deal_count {
+                    dealt.push(deck.draw(rng).unwrap());
+                }
+            }
+
+            let (community_dealt, hole_cards) = dealt.split_at(usize::from(remaining_community_cards));
+            let community_cards = community_dealt.iter()
+                .fold(start_community_cards, |community_cards, card| community_cards.with(*card));
+
+            scores[0] = options.rules.score(hero_cards, community_cards);
+            for (score, hand) in scores[1..].iter_mut().zip(hole_cards.chunks_exact(2)) {
+                *score = options.rules.score(Cards::EMPTY.with(hand[0]).with(hand[1]), community_cards);
+            }
+            if H::ACTIVE {
+                for (hand, cards) in hands[1..].iter_mut().zip(hole_cards.chunks_exact(2)) {
+                    *hand = Hand::of_two_cards(cards[0], cards[1]);
+                }
+                options.hook.showdown(&Showdown { board: community_cards, hands: &hands, scores: &scores });
+            }
+
+            let (hero_wins, hero_ties) = (wins[0], ties[0]);
+            showdown(&scores, &mut wins, &mut ties);
+            let share = try_u64_to_f64(wins[0] - hero_wins).unwrap() + tie_pots(ties[0] - hero_ties);
+            options.round_done(round, rounds, share, is_partner);
+        }
+
+        Some(Self::simulation_result(rounds, villain_count, &wins, &ties, start))
+    }
+
+    fn simulation_result(
+        rounds: u64,
+        villain_count: usize,
+        wins: &[u64],
+        ties: &[u128],
+        start: Instant,
+    ) -> EquityResult {
+        let work = Work {
+            boards: rounds,
+            villain_combos: rounds * u64::try_from(villain_count).unwrap(),
+            showdowns: rounds,
+            elapsed: start.elapsed(),
+            ..Work::default()
+        };
+        EquityResult { equities: Self::from_total_wins_ties(rounds, wins, ties), work }
+    }
+
+    // Villain hands are drawn proportionally to their weights,
+    // see SamplingStrategy::ExactRejection.
+    pub fn simulate_weighted(
+        start_community_cards: Cards,
+        hero_hand: Hand,
+        villain_ranges: &[WeightedRange],
+        rounds: u64,
+        rng: &mut impl Rng,
+    ) -> Option<EquityResult> {
+        let simulator = RangeSimulator::new(villain_ranges, SamplingStrategy::ExactRejection)?;
+        Self::simulate_ranges(start_community_cards, hero_hand, Cards::EMPTY, simulator, rounds, rng)
+    }
+
+    // The dead cards are neither dealt to the board nor to a villain.
+    pub fn simulate_ranges(
+        start_community_cards: Cards,
+        hero_hand: Hand,
+        dead_cards: Cards,
+        simulator: RangeSimulator,
+        rounds: u64,
+        rng: &mut impl Rng,
+    ) -> Option<EquityResult> {
+        let mut options = SimulationOptions::default();
+        Self::simulate_ranges_tracked(start_community_cards, hero_hand, dead_cards, simulator, rounds, rng, &mut options)
+    }
+
+    // Like simulate_ranges, calling the hook for every showdown. Returns the hook with the result.
+    pub fn simulate_ranges_with_hook<H: ShowdownHook>(
+        start_community_cards: Cards,
+        hero_hand: Hand,
+        dead_cards: Cards,
+        simulator: RangeSimulator,
+        rounds: u64,
+        rng: &mut impl Rng,
+        hook: H,
+    ) -> Option<(EquityResult, H)> {
+        let mut options = SimulationOptions::default().with_parts(hook, Holdem::new());
+        let result = Self::simulate_ranges_tracked(
+            start_community_cards,
+            hero_hand,
+            dead_cards,
+            simulator,
+            rounds,
+            rng,
+            &mut options,
+        )?;
+        Some((result, options.hook))
+    }
+
+    // Like simulate_ranges, with the rules of a variant with two hole cards, see enumerate_with_rules.
+    pub fn simulate_ranges_with_rules(
+        start_community_cards: Cards,
+        hero_hand: Hand,
+        dead_cards: Cards,
+        simulator: RangeSimulator,
+        rounds: u64,
+        rng: &mut impl Rng,
+        rules: impl GameRules,
+    ) -> Option<EquityResult> {
+        let mut options = SimulationOptions::default().with_parts((), rules);
+        Self::simulate_ranges_tracked(start_community_cards, hero_hand, dead_cards, simulator, rounds, rng, &mut options)
+    }
+
+    // The villain hands are dealt from their ranges, so there are no antithetic partners.
+    fn simulate_ranges_tracked<H: ShowdownHook, R: GameRules>(
+        start_community_cards: Cards,
+        hero_hand: Hand,
+        dead_cards: Cards,
+        mut simulator: RangeSimulator,
+        rounds: u64,
+        rng: &mut impl Rng,
+        options: &mut SimulationOptions<H, R>,
+    ) -> Option<EquityResult> {
+        let villain_count = simulator.player_count();
+        let hero_cards = hero_hand.to_cards();
+        let known_cards = start_community_cards | hero_cards | dead_cards;
+        if !valid_input_without_ranges(start_community_cards, hero_cards, villain_count, options.board_size)
+            || !valid_rules(&options.rules, known_cards, villain_count, options.board_size) {
+            return None;
+        }
+        if rounds == 0 || options.antithetic {
+            return None;
+        }
+
+        let start = Instant::now();
+        // Cards outside the deck are never dealt, like the dead cards.
+        let known_cards = known_cards | !options.rules.deck();
+        let remaining_community_cards = options.board_size - start_community_cards.count();
+        let player_count = villain_count + 1;
+
+        let mut hands = vec![Hand::MIN; villain_count];
+        // Hero first, as passed to the hook.
+        let mut all_hands = vec![hero_hand; if H::ACTIVE { player_count } else { 0 }];
+        let mut scores = vec![Score::ZERO; player_count];
+        let mut wins = vec![0u64; player_count];
+        let mut ties = vec![0; player_count];
+
+        for round in 0..rounds {
+            if options.cancelled(round) {
+                return None;
+            }
+            let mut dealt_cards = simulator.deal(rng, known_cards, &mut hands)?;
+
+            let mut community_cards = start_community_cards;
+            for _ in 0..remaining_community_cards {
+                let card = loop {
+                    let card: Card = rng.r#gen();
+                    if !dealt_cards.has(card) {
+                        break card;
+                    }
+                };
+                dealt_cards.add(card);
+                community_cards.add(card);
+            }
+
+            scores[0] = options.rules.score(hero_cards, community_cards);
+            for (score, hand) in scores[1..].iter_mut().zip(hands.iter()) {
+                *score = options.rules.score(hand.to_cards(), community_cards);
+            }
+            if H::ACTIVE {
+                all_hands[1..].copy_from_slice(&hands);
+                options.hook.showdown(&Showdown { board: community_cards, hands: &all_hands, scores: &scores });
+            }
+
+            let (hero_wins, hero_ties) = (wins[0], ties[0]);
+            showdown(&scores, &mut wins, &mut ties);
+            let share = try_u64_to_f64(wins[0] - hero_wins).unwrap() + tie_pots(ties[0] - hero_ties);
+            options.round_done(round, rounds, share, false);
+        }
+
+        Some(Self::simulation_result(rounds, villain_count, &wins, &ties, start))
+    }
+
+    pub fn equity_percent(self) -> f64 {
+        (u128::from(self.wins) * TIE_UNITS + self.ties) as f64
+            / (u128::from(self.total) * TIE_UNITS) as f64
+    }
+
+    pub fn win_percent(self) -> f64 {
+        try_u64_to_f64(self.wins).unwrap() / try_u64_to_f64(self.total).unwrap()
+    }
+
+    pub fn tie_percent(self) -> f64 {
+        self.ties as f64 / (u128::from(self.total) * TIE_UNITS) as f64
+    }
+
+    // Showdowns (or rounds of a simulation) won outright.
+    pub fn wins(self) -> u64 {
+        self.wins
+    }
+
+    // Sum of the pot shares of tied showdowns, e.g. 0.5 for a tie with one other player.
+    pub fn ties(self) -> f64 {
+        tie_pots(self.ties)
+    }
+
+    // The exact sum of the pot shares of tied showdowns, in units of TIE_UNITS.
+    pub fn tie_units(self) -> u128 {
+        self.ties
+    }
+
+    // Showdowns (or rounds of a simulation) counted.
+    pub fn total(self) -> u64 {
+        self.total
+    }
+
+    // Variance of the pot share of a single round, as a fraction.
+    // Exact without ties, otherwise an upper bound (a share is between 0 and 1).
+    pub fn variance(self) -> f64 {
+        let equity = self.equity_percent();
+        equity * (1.0 - equity)
+    }
+
+    // Standard error of the equity of a simulation with total rounds, as a fraction.
+    // Enumerations are exact, there it has no meaning.
+    pub fn std_error(self) -> f64 {
+        (self.variance() / try_u64_to_f64(self.total).unwrap()).sqrt()
+    }
+}
+
+struct BoardSample {
+    total: u64,
+    wins: Vec<u64>,
+    ties: Vec<u128>,
+}
+
+impl EquityEstimate {
+    // Stratified ratio estimator: the exactly enumerated boards contribute their
+    // counts directly, the sampled boards are scaled up to all remaining boards.
+    fn from_exact_and_samples(
+        exact_total: u64,
+        exact_wins: u64,
+        exact_ties: f64,
+        remaining_boards: usize,
+        samples: impl Iterator<Item = (u64, u64, f64)> + Clone,
+    ) -> Option<Self> {
+        let n = samples.clone().count() as f64;
+        let scale = if n == 0.0 { 0.0 } else { remaining_boards as f64 / n };
+
+        let mut sampled_total = 0.0;
+        let mut sampled_wins = 0.0;
+        let mut sampled_ties = 0.0;
+        for (total, wins, ties) in samples.clone() {
+            sampled_total += total as f64;
+            sampled_wins += wins as f64;
+            sampled_ties += ties;
+        }
+
+        let total = exact_total as f64 + scale * sampled_total;
+        if total == 0.0 {
+            return None;
+        }
+        let wins = exact_wins as f64 + scale * sampled_wins;
+        let ties = exact_ties + scale * sampled_ties;
+        let equity = (wins + ties) / total;
+
+        let std_error = if n < 2.0 || n as usize == remaining_boards {
+            0.0
+        } else {
+            let residuals = samples.map(|(total, wins, ties)| {
+                wins as f64 + ties - equity * total as f64
+            });
+            let mean = residuals.clone().sum::<f64>() / n;
+            let variance = residuals.map(|r| (r - mean) * (r - mean)).sum::<f64>() / (n - 1.0);
+            let remaining = remaining_boards as f64;
+            let finite_population_correction = 1.0 - n / remaining;
+            (remaining * remaining * finite_population_correction * variance / n).sqrt() / total
+        };
+
+        Some(EquityEstimate {
+            equity,
+            win: wins / total,
+            tie: ties / total,
+            std_error,
+        })
+    }
+}
+
+pub fn remaining_boards(community_cards: Cards, hero_cards: Cards) -> Vec<Cards> {
+    boards_of_size(community_cards, hero_cards, BOARD_SIZE)
+}
+
+// All boards with size cards, starting with the community cards,
+// in the same stable order as remaining_boards.
+pub fn boards_of_size(community_cards: Cards, dead_cards: Cards, size: u8) -> Vec<Cards> {
+    fn recurse(
+        boards: &mut Vec<Cards>,
+        board: Cards,
+        available: Cards,
+        remainder: u8,
+    ) {
+        if remainder == 0 {
+            boards.push(board);
+            return;
+        }
+        let mut available = available;
+        while let Some(card) = available.first() {
+            available.remove(card);
+            recurse(boards, board.with(card), available, remainder - 1);
+        }
+    }
+
+    assert!(community_cards.count() <= size);
+    let mut boards = Vec::new();
+    let available = !(community_cards | dead_cards);
+    recurse(&mut boards, community_cards, available, size - community_cards.count());
+    boards
+}
+
+// Boards are enumerated in a stable order (see remaining_boards),
+// the cursor is the index of the next board.
+// Shards of the boards can be enumerated separately and merged afterwards.
+pub struct Enumeration<'a, RT: HandRange> {
+    calculator: EquityCalculator<'a, RT>,
+    boards: Vec<Cards>,
+    fingerprint: u64,
+    start: usize,
+    cursor: usize,
+    end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    fingerprint: u64,
+    start: usize,
+    cursor: usize,
+    end: usize,
+    total: u64,
+    wins: Vec<u64>,
+    ties: Vec<u128>,
+}
+
+impl fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let wins: Vec<_> = self.wins.iter().map(|wins| wins.to_string()).collect();
+        let ties: Vec<_> = self.ties.iter().map(|ties| ties.to_string()).collect();
+        write!(
+            f,
+            "v2 fingerprint={:016x} start={} cursor={} end={} total={} wins={} ties={}",
+            self.fingerprint,
+            self.start,
+            self.cursor,
+            self.end,
+            self.total,
+            wins.join(","),
+            ties.join(","),
+        )
+    }
+}
+
+impl Checkpoint {
+    pub fn parse(s: &str) -> Result<Self> {
+        // v1 stored the ties as the bits of pot shares, their units are rounded from these.
+        let mut fields = s.split_whitespace();
+        let version = fields.next();
+        if version != Some("v1") && version != Some("v2") {
+            return Err("invalid checkpoint: unsupported version".into());
+        }
+        let mut field = |name: &str| -> Result<&str> {
+            match fields.next().and_then(|field| field.strip_prefix(name)) {
+                Some(value) => Ok(value.strip_prefix('=').unwrap_or(value)),
+                None => Err(format!("invalid checkpoint: missing {name}").into()),
+            }
+        };
+        let fingerprint = u64::from_str_radix(field("fingerprint")?, 16)?;
+        let start = field("start")?.parse()?;
+        let cursor = field("cursor")?.parse()?;
+        let end = field("end")?.parse()?;
+        let total = field("total")?.parse()?;
+        let wins = field("wins")?.split(',')
+            .map(|wins| wins.parse::<u64>())
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let ties = if version == Some("v1") {
+            field("ties")?.split(',')
+                .map(|ties| u64::from_str_radix(ties, 16)
+                    .map(|bits| tie_units_of_pots(f64::from_bits(bits))))
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        } else {
+            field("ties")?.split(',')
+                .map(|ties| ties.parse::<u128>())
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        };
+        if wins.len() != ties.len() || !(start <= cursor && cursor <= end) {
+            return Err("invalid checkpoint: inconsistent fields".into());
+        }
+        Ok(Checkpoint { fingerprint, start, cursor, end, total, wins, ties })
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.cursor == self.end
+    }
+
+    // Merges two finished, adjacent shards of the same enumeration.
+    pub fn merge(&self, other: &Checkpoint) -> Result<Checkpoint> {
+        if self.fingerprint != other.fingerprint {
+            return Err("merge failed: checkpoints of different inputs".into());
+        }
+        if !self.is_done() || !other.is_done() {
+            return Err("merge failed: unfinished checkpoint".into());
+        }
+        let (first, second) = if self.end == other.start {
+            (self, other)
+        } else if other.end == self.start {
+            (other, self)
+        } else {
+            return Err("merge failed: checkpoints are not adjacent".into());
+        };
+        let equities = Equity::merge_all(&first.partial_equities(), &second.partial_equities())
+            .ok_or("merge failed: checkpoints with a different number of players")?;
+        Ok(Checkpoint {
+            fingerprint: self.fingerprint,
+            start: first.start,
+            cursor: second.end,
+            end: second.end,
+            total: first.total + second.total,
+            wins: equities.iter().map(|equity| equity.wins).collect(),
+            ties: equities.iter().map(|equity| equity.ties).collect(),
+        })
+    }
+
+    // Unlike equities, also for a shard without a showdown (a total of 0).
+    fn partial_equities(&self) -> Vec<Equity> {
+        self.wins.iter()
+            .zip(&self.ties)
+            .map(|(&wins, &ties)| Equity { wins, ties, total: self.total })
+            .collect()
+    }
+
+    pub fn equities(&self) -> Option<Vec<Equity>> {
+        if self.total == 0 {
+            None
+        } else {
+            Some(Equity::from_total_wins_ties(self.total, &self.wins, &self.ties))
+        }
+    }
+}
+
+// FNV-1a over the card indices of all inputs, stable across platforms and versions.
+fn fingerprint(
+    community_cards: Cards,
+    hero_cards: Cards,
+    villain_ranges: &[impl HandRange],
+) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    let mut write = |byte: u8| {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    };
+    for card in community_cards.iter() {
+        write(card.to_index() as u8);
+    }
+    write(0xff);
+    for card in hero_cards.iter() {
+        write(card.to_index() as u8);
+    }
+    for range in villain_ranges {
+        write(0xff);
+        range.for_each_hand(|hand| {
+            write(hand.high().to_index() as u8);
+            write(hand.low().to_index() as u8);
+        });
+    }
+    hash
+}
+
+impl <'a, RT: HandRange> Enumeration<'a, RT> {
+    pub fn new(
+        community_cards: Cards,
+        hero_hand: Hand,
+        villain_ranges: &'a [RT],
+    ) -> Option<Self> {
+        let hero_cards = hero_hand.to_cards();
+        let calculator = EquityCalculator::new(community_cards, hero_cards, villain_ranges)?;
+        let boards = remaining_boards(community_cards, hero_cards);
+        let end = boards.len();
+        Some(Self {
+            calculator,
+            boards,
+            fingerprint: fingerprint(community_cards, hero_cards, villain_ranges),
+            start: 0,
+            cursor: 0,
+            end,
+        })
+    }
+
+    // Restricts the enumeration to the boards start..end.
+    pub fn shard(mut self, start: usize, end: usize) -> Option<Self> {
+        if self.cursor != self.start || start > end || end > self.boards.len() {
+            return None;
+        }
+        self.start = start;
+        self.cursor = start;
+        self.end = end;
+        Some(self)
+    }
+
+    pub fn resume(mut self, checkpoint: &Checkpoint) -> Result<Self> {
+        if checkpoint.fingerprint != self.fingerprint {
+            return Err("resume failed: checkpoint of different inputs".into());
+        }
+        if checkpoint.end > self.boards.len()
+            || checkpoint.wins.len() != self.calculator.wins.len() {
+            return Err("resume failed: invalid checkpoint".into());
+        }
+        self.start = checkpoint.start;
+        self.cursor = checkpoint.cursor;
+        self.end = checkpoint.end;
+        self.calculator.total = checkpoint.total;
+        self.calculator.wins = checkpoint.wins.clone();
+        self.calculator.ties = checkpoint.ties.clone();
+        Ok(self)
+    }
+
+    pub fn board_count(&self) -> usize {
+        self.boards.len()
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
 
-pub fn total_combos_upper_bound(
-    community_cards: Cards,
-    villain_ranges: &[impl AsRef<RangeTable>],
-) -> u128 {
-    assert!(villain_ranges.len() <= 8);
-    assert!(villain_ranges.iter().all(|range| !range.as_ref().is_empty()));
-    let community_cards_count = community_cards.count();
-    assert!(community_cards_count <= 5);
-    let mut remaining_cards = {
-        let remaining_cards = Card::COUNT - usize::from(community_cards_count) - 2;
-        u128::try_from(remaining_cards).unwrap()
-    };
-    let mut count = 1u128;
+    pub fn is_done(&self) -> bool {
+        self.cursor == self.end
+    }
 
-    for _ in community_cards_count..5 {
-        count *= remaining_cards;
-        remaining_cards -= 1;
+    pub fn run_until(&mut self, end: usize) {
+        while self.cursor < end.min(self.end) {
+            self.calculator.board(self.boards[self.cursor]);
+            self.cursor += 1;
+        }
     }
 
-    let mut max_count = count;
-    for _ in 0..villain_ranges.len()*2 {
-        max_count *= remaining_cards;
-        remaining_cards -= 1;
+    // Enumerates boards until done or the time limit is reached.
+    pub fn run_for(&mut self, time_limit: Duration) {
+        let deadline = Instant::now() + time_limit;
+        while !self.is_done() && Instant::now() < deadline {
+            self.calculator.board(self.boards[self.cursor]);
+            self.cursor += 1;
+        }
     }
 
-    for range in villain_ranges {
-        let next_count = count.checked_mul(u128::from(range.as_ref().count_cards()));
-        match next_count {
-            Some(n) => count = n,
-            None => return u128::MAX,
-        };
+    // The equities of the boards enumerated so far, None before the first showdown.
+    pub fn result(&self) -> Option<EquityResult> {
+        (self.calculator.total != 0).then(|| self.calculator.result())
     }
 
-    min(count, max_count)
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            fingerprint: self.fingerprint,
+            start: self.start,
+            cursor: self.cursor,
+            end: self.end,
+            total: self.calculator.total,
+            wins: self.calculator.wins.clone(),
+            ties: self.calculator.ties.clone(),
+        }
+    }
 }
 
-impl Equity {
-    fn from_total_wins_ties(total: u64, wins: &[u64], ties: &[f64]) -> Vec<Self> {
-        assert_ne!(total, 0);
-        assert_eq!(wins.len(), ties.len());
-        let mut equities = Vec::with_capacity(wins.len());
-        for (wins, ties) in wins.iter().copied().zip(ties.iter().copied()) {
-            equities.push(Equity { wins, ties, total });
+// Writes one CSV row per showdown:
+// board,hero,villain 1,...,villain n,winners
+// winners are the indices of the players with the best hand (0 = hero),
+// separated by '|' on a tie.
+// Stops writing after the first error, which is returned after the enumeration.
+struct OutcomeWriter<'a> {
+    out: &'a mut dyn Write,
+    hero_hand: Hand,
+    villain_hands: Vec<Hand>,
+    line: String,
+    error: Option<std::io::Error>,
+}
+
+impl <'a> OutcomeWriter<'a> {
+    fn new(out: &'a mut dyn Write, hero_hand: Hand, villain_count: usize) -> Self {
+        let mut writer = Self {
+            out,
+            hero_hand,
+            villain_hands: vec![Hand::MIN; villain_count],
+            line: String::new(),
+            error: None,
+        };
+        writer.line.push_str("board,hero");
+        for index in 0..villain_count {
+            writer.line.push_str(&format!(",villain {}", index + 1));
         }
-        equities
+        writer.line.push_str(",winners\n");
+        writer.flush_line();
+        writer
     }
 
-    pub fn enumerate(
-        community_cards: Cards,
-        hero_hand: Hand,
-        villain_ranges: &[impl AsRef<RangeTable>],
-    ) -> Option<Vec<Equity>> {
-        EquityCalculator::new(
-            community_cards,
-            hero_hand.to_cards(),
-            villain_ranges,
-        )?.enumerate()
+    fn flush_line(&mut self) {
+        if self.error.is_none() {
+            if let Err(err) = self.out.write_all(self.line.as_bytes()) {
+                self.error = Some(err);
+            }
+        }
+        self.line.clear();
     }
 
-    pub fn simulate(
-        start_community_cards: Cards,
-        hero_hand: Hand,
-        villain_count: usize,
-        rounds: u64,
-    ) -> Option<Vec<Equity>> {
-        let hero_cards = hero_hand.to_cards();
-        if !valid_input_without_ranges(start_community_cards, hero_cards, villain_count) {
-            return None;
+    fn showdown(&mut self, community_cards: Cards, scores: &[Score]) {
+        use std::fmt::Write;
+        if self.error.is_some() {
+            return;
         }
-        if rounds == 0 {
-            return None;
+        for card in community_cards.iter() {
+            write!(self.line, "{}", card).unwrap();
         }
+        write!(self.line, ",{}", self.hero_hand).unwrap();
+        for hand in &self.villain_hands {
+            write!(self.line, ",{}", hand).unwrap();
+        }
+        let max_score = scores.iter().copied().max().unwrap();
+        let mut separator = ',';
+        for (index, score) in scores.iter().enumerate() {
+            if *score == max_score {
+                write!(self.line, "{}{}", separator, index).unwrap();
+                separator = '|';
+            }
+        }
+        self.line.push('\n');
+        self.flush_line();
+    }
 
-        let mut rng = SmallRng::from_entropy();
-        let remaining_community_cards = 5 - start_community_cards.count();
-        let player_count = villain_count + 1;
-
-        let mut scores = vec![Score::ZERO; player_count];
-        let mut wins = vec![0u64; player_count];
-        let mut ties = vec![0.0; player_count];
-        let mut deck = Deck::from_cards(&mut rng, start_community_cards | hero_cards);
+    fn finish(self) -> std::io::Result<()> {
+        match self.error {
+            Some(err) => Err(err),
+            None => self.out.flush(),
+        }
+    }
+}
 
-        for _ in 0..rounds {
-            deck.reset();
+// Tags every showdown by whether hero was ahead of all villains
+// with the community cards known at the start of the enumeration.
+struct SplitTracker {
+    hero_score: Score,
+    villain_scores: Vec<Score>,
+    current_scores: Vec<Score>,
+    ahead: Equity,
+    behind: Equity,
+}
 
-            let community_cards = {
-                let mut community_cards = start_community_cards;
-                for _ in 0..remaining_community_cards {
-                    community_cards.add(deck.draw(&mut rng).unwrap());
+impl SplitTracker {
+    fn new(
+        community_cards: Cards,
+        hero_cards: Cards,
+        villain_ranges: &[impl HandRange],
+    ) -> Self {
+        let mut villain_scores = vec![Score::ZERO; Card::COUNT_INDEX * Card::COUNT_INDEX];
+        for range in villain_ranges {
+            range.for_each_hand(|hand| {
+                if community_cards.has(hand.high()) || community_cards.has(hand.low()) {
+                    return;
                 }
-                community_cards
-            };
+                let cards = community_cards.with(hand.high()).with(hand.low());
+                villain_scores[Self::hand_index(hand)] = cards.top5().to_score();
+            });
+        }
+        let empty = Equity { wins: 0, ties: 0, total: 0 };
+        SplitTracker {
+            hero_score: (community_cards | hero_cards).top5().to_score(),
+            villain_scores,
+            current_scores: vec![Score::ZERO; villain_ranges.len()],
+            ahead: empty,
+            behind: empty,
+        }
+    }
 
-            scores[0] = (community_cards | hero_cards).score_fast();
-            for i in 1..player_count {
-                let hand = deck.hand(&mut rng).unwrap();
-                let player_cards = community_cards.with(hand.high()).with(hand.low());
-                scores[i] = player_cards.score_fast();
-            }
+    fn hand_index(hand: Hand) -> usize {
+        hand.high().to_usize() * Card::COUNT_INDEX + hand.low().to_usize()
+    }
 
-            showdown(&scores, &mut wins, &mut ties);
+    fn set_villain(&mut self, villain_index: usize, hand: Hand) {
+        self.current_scores[villain_index] = self.villain_scores[Self::hand_index(hand)];
+    }
+
+    fn showdown(&mut self, wins: u64, ties: u128) {
+        let villain_best = self.current_scores.iter().copied().max().unwrap();
+        let equity = if self.hero_score >= villain_best {
+            &mut self.ahead
+        } else {
+            &mut self.behind
+        };
+        equity.total += 1;
+        equity.wins += wins;
+        equity.ties += ties;
+    }
+
+    fn to_split(&self) -> EquitySplit {
+        EquitySplit {
+            total: self.ahead.total + self.behind.total,
+            ahead: self.ahead,
+            behind: self.behind,
         }
+    }
+}
 
-        Some(Self::from_total_wins_ties(rounds, &wins, &ties))
+// The optional parts of a simulation, by default hold'em
+// with five community cards without anything tracked.
+struct SimulationOptions<'a, H: ShowdownHook = (), R: GameRules = Holdem> {
+    board_size: u8,
+    antithetic: bool,
+    risk: Option<EquityRisk>,
+    convergence: Option<Convergence>,
+    progress: Option<&'a mut dyn FnMut(u64) -> bool>,
+    hook: H,
+    rules: R,
+    // Hero's pot share of the last round, waiting for its antithetic partner.
+    partner_share: f64,
+}
+
+impl Default for SimulationOptions<'_> {
+    fn default() -> Self {
+        Self {
+            board_size: BOARD_SIZE,
+            antithetic: false,
+            risk: None,
+            convergence: None,
+            progress: None,
+            hook: (),
+            rules: Holdem::new(),
+            partner_share: 0.0,
+        }
     }
+}
 
-    pub fn equity_percent(self) -> f64 {
-        (try_u64_to_f64(self.wins).unwrap() + self.ties)
-            / try_u64_to_f64(self.total).unwrap()
+impl <'a, H: ShowdownHook, R: GameRules> SimulationOptions<'a, H, R> {
+    // The board size of the rules.
+    fn with_parts<H2: ShowdownHook, R2: GameRules>(self, hook: H2, rules: R2) -> SimulationOptions<'a, H2, R2> {
+        SimulationOptions {
+            board_size: rules.board_size(),
+            antithetic: self.antithetic,
+            risk: self.risk,
+            convergence: self.convergence,
+            progress: self.progress,
+            hook,
+            rules,
+            partner_share: self.partner_share,
+        }
     }
 
-    pub fn win_percent(self) -> f64 {
-        try_u64_to_f64(self.wins).unwrap() / try_u64_to_f64(self.total).unwrap()
+    // Reports the progress before the round, true if the simulation is cancelled.
+    fn cancelled(&mut self, round: u64) -> bool {
+        match &mut self.progress {
+            Some(progress) => round.is_multiple_of(PROGRESS_INTERVAL) && !progress(round),
+            None => false,
+        }
     }
 
-    pub fn tie_percent(self) -> f64 {
-        self.ties / try_u64_to_f64(self.total).unwrap()
+    // Tracks hero's pot share of a finished round.
+    fn round_done(&mut self, round: u64, rounds: u64, share: f64, is_partner: bool) {
+        if let Some(risk) = &mut self.risk {
+            risk.showdown(share);
+            risk.runouts += 1;
+            risk.runout_share_squares += share * share;
+        }
+        if let Some(convergence) = &mut self.convergence {
+            if is_partner {
+                convergence.sample((self.partner_share + share) / 2.0);
+            } else if self.antithetic && round + 1 < rounds {
+                self.partner_share = share;
+            } else {
+                convergence.sample(share);
+            }
+            convergence.round_done(round + 1, rounds);
+        }
     }
 }
 
-struct EquityCalculator<'a, RT: AsRef<RangeTable>> {
+struct EquityCalculator<'a, RT: HandRange, H: ShowdownHook = (), R: GameRules = Holdem> {
     known_cards: Cards,
     hero_cards: Cards,
     visited_community_cards: Cards,
     community_cards: Cards,
     villain_ranges: &'a [RT],
+    // Neither on the board nor in a villain hand.
+    dead_cards: Cards,
     hand_ranking_scores: Vec<Score>,
+    // Scores of every villain combo on the current board, None if blocked.
+    villain_scores: Vec<Vec<Option<Score>>>,
+    lock: bool,
+    drawing_dead: bool,
     total: u64,
     wins: Vec<u64>,
-    ties: Vec<f64>,
+    ties: Vec<u128>,
+    split: Option<SplitTracker>,
+    chops: Option<ChopTracker>,
+    risk: Option<RiskTracker>,
+    improve: Option<ImproveTracker>,
+    side_pots: Option<SidePotTracker>,
+    outcomes: Option<OutcomeWriter<'a>>,
+    hook: H,
+    // Hero and villain hands of the current showdown, only tracked with an active hook.
+    hands: Vec<Hand>,
+    rules: R,
+    board_size: u8,
+    work: Work,
 }
 
-impl <'a, RT: AsRef<RangeTable>> EquityCalculator<'a, RT> {
+impl <'a, RT: HandRange> EquityCalculator<'a, RT> {
     fn new(
         community_cards: Cards,
         hero_cards: Cards,
         villain_ranges: &'a [RT],
     ) -> Option<Self> {
-        if !valid_input(community_cards, hero_cards, villain_ranges) {
-            None
-        } else {
-            Some(Self {
-                known_cards: Cards::EMPTY,
-                hero_cards,
-                community_cards,
-                visited_community_cards: community_cards | hero_cards,
-                villain_ranges,
-                hand_ranking_scores: vec![Score::ZERO; villain_ranges.len() + 1],
-                total: 0,
-                wins: vec![0; villain_ranges.len() + 1],
-                ties: vec![0.0; villain_ranges.len() + 1],
-            })
+        Self::with_rules(community_cards, hero_cards, villain_ranges, Holdem::new())
+    }
+}
+
+impl <'a, RT: HandRange, R: GameRules> EquityCalculator<'a, RT, (), R> {
+    // The cards outside the deck of the rules are dead.
+    fn with_rules(
+        community_cards: Cards,
+        hero_cards: Cards,
+        villain_ranges: &'a [RT],
+        rules: R,
+    ) -> Option<Self> {
+        let board_size = rules.board_size();
+        if !valid_input(community_cards, hero_cards, villain_ranges, board_size)
+            || !valid_rules(&rules, community_cards | hero_cards, villain_ranges.len(), board_size) {
+            return None;
+        }
+        let dead_cards = !rules.deck();
+        Some(Self {
+            known_cards: Cards::EMPTY,
+            hero_cards,
+            community_cards,
+            visited_community_cards: community_cards | hero_cards | dead_cards,
+            villain_ranges,
+            dead_cards,
+            hand_ranking_scores: vec![Score::ZERO; villain_ranges.len() + 1],
+            villain_scores: vec![Vec::new(); villain_ranges.len()],
+            lock: false,
+            drawing_dead: false,
+            total: 0,
+            wins: vec![0; villain_ranges.len() + 1],
+            ties: vec![0; villain_ranges.len() + 1],
+            split: None,
+            chops: None,
+            risk: None,
+            improve: None,
+            side_pots: None,
+            outcomes: None,
+            hook: (),
+            hands: Vec::new(),
+            rules,
+            board_size,
+            work: Work::default(),
+        })
+    }
+
+    fn with_hook<H: ShowdownHook>(self, hook: H) -> EquityCalculator<'a, RT, H, R> {
+        let hero_hand = Hand::try_of_cards(self.hero_cards).unwrap();
+        EquityCalculator {
+            known_cards: self.known_cards,
+            hero_cards: self.hero_cards,
+            visited_community_cards: self.visited_community_cards,
+            community_cards: self.community_cards,
+            villain_ranges: self.villain_ranges,
+            dead_cards: self.dead_cards,
+            hand_ranking_scores: self.hand_ranking_scores,
+            villain_scores: self.villain_scores,
+            lock: self.lock,
+            drawing_dead: self.drawing_dead,
+            total: self.total,
+            wins: self.wins,
+            ties: self.ties,
+            split: self.split,
+            chops: self.chops,
+            risk: self.risk,
+            improve: self.improve,
+            side_pots: self.side_pots,
+            outcomes: self.outcomes,
+            hands: if H::ACTIVE { vec![hero_hand; self.villain_ranges.len() + 1] } else { Vec::new() },
+            hook,
+            rules: self.rules,
+            board_size: self.board_size,
+            work: self.work,
+        }
+    }
+}
+
+impl <'a, RT: HandRange, H: ShowdownHook, R: GameRules> EquityCalculator<'a, RT, H, R> {
+    fn with_board_size(mut self, board_size: u8) -> Option<Self> {
+        let known_cards = self.community_cards | self.hero_cards;
+        let valid = valid_input(self.community_cards, self.hero_cards, self.villain_ranges, board_size)
+            && valid_rules(&self.rules, known_cards, self.villain_ranges.len(), board_size);
+        self.board_size = board_size;
+        valid.then_some(self)
+    }
+
+    fn with_dead_cards(mut self, dead_cards: Cards) -> Option<Self> {
+        if (dead_cards & self.visited_community_cards) != Cards::EMPTY {
+            return None;
         }
+        self.dead_cards |= dead_cards;
+        self.visited_community_cards |= dead_cards;
+        Some(self)
     }
 
-    fn enumerate(mut self) -> Option<Vec<Equity>> {
+    fn enumerate(mut self) -> Option<Self> {
         let upper_bound = total_combos_upper_bound(
             self.community_cards,
             self.villain_ranges,
+            self.board_size,
         );
         if u64::try_from(upper_bound).is_err() {
             return None;
         }
-        let remaining_community_cards = 5 - self.community_cards.count();
+        let start = Instant::now();
+        let remaining_community_cards = self.board_size - self.community_cards.count();
         self.community_cards(remaining_community_cards.into());
+        self.work.elapsed = start.elapsed();
         if self.total != 0 {
-            Some(Equity::from_total_wins_ties(self.total, &self.wins, &self.ties))
+            Some(self)
         } else {
             None
         }
     }
 
+    fn result(&self) -> EquityResult {
+        EquityResult {
+            equities: Equity::from_total_wins_ties(self.total, &self.wins, &self.ties),
+            work: self.work,
+        }
+    }
+
     fn community_cards(&mut self, remainder: usize) {
         if remainder == 0 {
-            let known_cards = self.hero_cards | self.community_cards;
-            self.hand_ranking_scores[0] = known_cards.top5().to_score();
-            self.known_cards = known_cards;
-            self.players(self.villain_ranges.len() - 1);
+            self.board(self.community_cards);
             return;
         }
 
@@ -245,20 +2341,103 @@ impl <'a, RT: AsRef<RangeTable>> EquityCalculator<'a, RT> {
         }
     }
 
+    fn board(&mut self, community_cards: Cards) {
+        debug_assert_eq!(community_cards.count(), self.board_size);
+        self.community_cards = community_cards;
+        self.hand_ranking_scores[0] = self.rules.score(self.hero_cards, community_cards);
+        let known_cards = self.hero_cards | community_cards | self.dead_cards;
+        self.known_cards = known_cards;
+        if let Some(chops) = &mut self.chops {
+            chops.board_score = self.rules.score(Cards::EMPTY, community_cards);
+        }
+        self.work.boards += 1;
+
+        let hero_score = self.hand_ranking_scores[0];
+        self.lock = true;
+        self.drawing_dead = false;
+        for (range, scores) in self.villain_ranges.iter().zip(self.villain_scores.iter_mut()) {
+            scores.clear();
+            range.for_each_hand(|hand| {
+                let score = (!known_cards.has(hand.high()) && !known_cards.has(hand.low()))
+                    .then(|| self.rules.score(hand.to_cards(), community_cards));
+                scores.push(score);
+            });
+            let live_scores = || scores.iter().flatten().copied();
+            self.lock &= live_scores().all(|score| score < hero_score);
+            self.drawing_dead |= live_scores().all(|score| score > hero_score);
+        }
+
+        // Without trackers the showdowns on the board don't have to be compared one by one.
+        let trackers = self.split.is_some()
+            || self.chops.is_some()
+            || self.risk.is_some()
+            || self.improve.is_some()
+            || self.side_pots.is_some()
+            || self.outcomes.is_some()
+            || H::ACTIVE;
+        if !trackers && self.lock {
+            let showdowns = self.count_showdowns(0, known_cards);
+            self.total += showdowns;
+            self.wins[0] += showdowns;
+            self.work.showdowns += showdowns;
+            self.work.locks += showdowns;
+        } else if !trackers && self.drawing_dead && self.villain_ranges.len() == 1 {
+            let showdowns = self.villain_scores[0].iter().flatten().count() as u64;
+            self.work.villain_combos += self.villain_scores[0].len() as u64;
+            self.total += showdowns;
+            self.wins[1] += showdowns;
+            self.work.showdowns += showdowns;
+            self.work.drawing_dead += showdowns;
+        } else {
+            self.players(self.villain_ranges.len() - 1);
+        }
+        if let Some(risk) = &mut self.risk {
+            risk.finish_board();
+        }
+    }
+
+    fn count_showdowns(&mut self, player_index: usize, known_cards: Cards) -> u64 {
+        let Some(villain) = self.villain_ranges.get(player_index) else {
+            return 1;
+        };
+        let mut count = 0;
+        villain.for_each_hand(|hand| {
+            self.work.villain_combos += 1;
+            if known_cards.has(hand.high()) || known_cards.has(hand.low()) {
+                return;
+            }
+            count += self.count_showdowns(player_index + 1, known_cards.with(hand.high()).with(hand.low()));
+        });
+        count
+    }
+
     fn players(&mut self, remainder: usize) {
         let player_index = self.villain_ranges.len() - remainder - 1;
-        let villain = self.villain_ranges[player_index].as_ref();
+        let villain = &self.villain_ranges[player_index];
         let current_known_cards = self.known_cards;
+        let mut combo_index = 0;
         villain.for_each_hand(|hand| {
+            self.work.villain_combos += 1;
+            let score = self.villain_scores[player_index][combo_index];
+            combo_index += 1;
             if current_known_cards.has(hand.high()) || current_known_cards.has(hand.low()) {
                 return;
             }
 
-            self.hand_ranking_scores[player_index+1] = self.community_cards
-                .with(hand.high())
-                .with(hand.low())
-                .score_fast();
+            self.hand_ranking_scores[player_index+1] = score.unwrap();
             self.known_cards = current_known_cards.with(hand.high()).with(hand.low());
+            if let Some(split) = &mut self.split {
+                split.set_villain(player_index, hand);
+            }
+            if let Some(improve) = &mut self.improve {
+                improve.set_villain(player_index, hand);
+            }
+            if let Some(outcomes) = &mut self.outcomes {
+                outcomes.villain_hands[player_index] = hand;
+            }
+            if H::ACTIVE {
+                self.hands[player_index + 1] = hand;
+            }
 
             if remainder != 0 {
                 self.players(remainder - 1);
@@ -270,14 +2449,144 @@ impl <'a, RT: AsRef<RangeTable>> EquityCalculator<'a, RT> {
 
     fn showdown(&mut self) {
         self.total += 1;
-        showdown(&self.hand_ranking_scores, &mut self.wins, &mut self.ties)
+        self.work.showdowns += 1;
+        if self.lock {
+            self.work.locks += 1;
+        }
+        if self.drawing_dead {
+            self.work.drawing_dead += 1;
+        }
+        let hero_wins = self.wins[0];
+        let hero_ties = self.ties[0];
+        showdown(&self.hand_ranking_scores, &mut self.wins, &mut self.ties);
+        if let Some(split) = &mut self.split {
+            split.showdown(self.wins[0] - hero_wins, self.ties[0] - hero_ties);
+        }
+        if let Some(risk) = &mut self.risk {
+            risk.showdown(try_u64_to_f64(self.wins[0] - hero_wins).unwrap() + tie_pots(self.ties[0] - hero_ties));
+        }
+        if let Some(chops) = &mut self.chops {
+            chops.showdown(&self.hand_ranking_scores);
+        }
+        if let Some(improve) = &mut self.improve {
+            improve.showdown(&self.hand_ranking_scores);
+        }
+        if let Some(side_pots) = &mut self.side_pots {
+            side_pots.showdown(&self.hand_ranking_scores);
+        }
+        if let Some(outcomes) = &mut self.outcomes {
+            outcomes.showdown(self.community_cards, &self.hand_ranking_scores);
+        }
+        if H::ACTIVE {
+            self.hook.showdown(&Showdown {
+                board: self.community_cards,
+                hands: &self.hands,
+                scores: &self.hand_ranking_scores,
+            });
+        }
+    }
+}
+
+// A single showdown of an enumeration or simulation, players in order (hero first).
+pub struct Showdown<'a> {
+    pub board: Cards,
+    pub hands: &'a [Hand],
+    pub scores: &'a [Score],
+}
+
+impl Showdown<'_> {
+    pub fn best(&self) -> Score {
+        self.scores.iter().copied().max().unwrap()
+    }
+
+    pub fn is_winner(&self, player: usize) -> bool {
+        self.scores[player] == self.best()
+    }
+
+    // The players with the best score, more than one on a tie.
+    pub fn winners(&self) -> impl Iterator<Item = usize> + '_ {
+        let best = self.best();
+        (0..self.scores.len()).filter(move |player| self.scores[*player] == best)
+    }
+}
+
+// Called for every showdown of an enumeration or simulation, e.g. for custom statistics,
+// implemented for closures taking a &Showdown. The hook is a generic parameter,
+// without one (the unit type) the calculations compile exactly as before.
+// A hook costs the call per showdown and, in an enumeration, the shortcuts for boards
+// the hero wins or loses against every combo, which can be many times slower.
+pub trait ShowdownHook {
+    const ACTIVE: bool = true;
+
+    fn showdown(&mut self, showdown: &Showdown);
+}
+
+impl ShowdownHook for () {
+    const ACTIVE: bool = false;
+
+    fn showdown(&mut self, _showdown: &Showdown) {}
+}
+
+impl <F: FnMut(&Showdown)> ShowdownHook for F {
+    fn showdown(&mut self, showdown: &Showdown) {
+        self(showdown)
+    }
+}
+
+// The winners of a showdown between any number of players, in player order.
+// Every winner gets the same share of the pot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShowdownResult {
+    pub winners: Vec<usize>,
+    pub best: Score,
+}
+
+impl ShowdownResult {
+    pub fn new(scores: &[Score]) -> Option<Self> {
+        let best = scores.iter().copied().max()?;
+        let winners = scores.iter()
+            .enumerate()
+            .filter(|(_, score)| **score == best)
+            .map(|(index, _)| index)
+            .collect();
+        Some(Self { winners, best })
+    }
+
+    pub fn is_tie(&self) -> bool {
+        self.winners.len() > 1
+    }
+
+    pub fn is_winner(&self, player: usize) -> bool {
+        self.winners.contains(&player)
+    }
+
+    // Pot share of the player (1 for a single winner, 1/n for an n-way tie, 0 otherwise).
+    pub fn share(&self, player: usize) -> f64 {
+        if self.is_winner(player) {
+            1.0 / self.winners.len() as f64
+        } else {
+            0.0
+        }
+    }
+
+    // Splits a pot of chips among the winners, the odd chips go
+    // one each to the first winners in player order.
+    pub fn award(&self, pot: u64, player_count: usize) -> Vec<u64> {
+        let mut awards = vec![0; player_count];
+        let winner_count = self.winners.len() as u64;
+        for (position, winner) in self.winners.iter().enumerate() {
+            awards[*winner] = pot / winner_count + u64::from((position as u64) < pot % winner_count);
+        }
+        awards
     }
 }
 
-fn showdown(
+// Accumulates a showdown with the same rules as ShowdownResult without allocating:
+// a single winner gets a win, tied winners share a tie.
+pub fn showdown(
     hand_ranking_scores: &[Score],
     wins: &mut [u64],
-    ties: &mut [f64],
+    ties: &mut [u128],
 ) {
     let max_score = hand_ranking_scores.iter().copied().max().unwrap();
     let winners = hand_ranking_scores.iter()
@@ -290,15 +2599,27 @@ fn showdown(
             .unwrap();
         wins[winner_index] += 1;
     } else {
-        let ratio = 1.0 / try_u64_to_f64(u64::try_from(winners).unwrap()).unwrap();
+        let share = tie_units(winners);
         for (index, score) in hand_ranking_scores.iter().copied().enumerate() {
             if score == max_score {
-                ties[index] += ratio;
+                ties[index] += share;
             }
         }
     }
 }
 
+// Maps every available card to the card at the mirrored position
+// of the available cards ordered by rank, the known cards are unused.
+fn mirrored_cards(known_cards: Cards) -> [Card; Card::COUNT_INDEX] {
+    let mut available: Vec<_> = Card::all().filter(|card| !known_cards.has(*card)).collect();
+    available.sort_by(|a, b| a.cmp_by_rank(*b));
+    let mut mirrors = [Card::MIN; Card::COUNT_INDEX];
+    for (card, mirror) in available.iter().zip(available.iter().rev()) {
+        mirrors[card.to_usize()] = *mirror;
+    }
+    mirrors
+}
+
 pub struct Deck {
     cards: [Card; Card::COUNT],
     max_len: usize,
@@ -320,6 +2641,11 @@ impl Deck {
         Deck { cards, max_len: index, len: index }
     }
 
+    // The cards of the deck of the rules without the known cards.
+    pub fn from_rules(rng: &mut impl Rng, rules: &(impl GameRules + ?Sized), known_cards: Cards) -> Self {
+        Self::from_cards(rng, known_cards | !rules.deck())
+    }
+
     pub fn draw(&mut self, rng: &mut impl Rng) -> Option<Card> {
         if self.len == 0 {
             None
@@ -335,10 +2661,206 @@ impl Deck {
     pub fn hand(&mut self, rng: &mut impl Rng) -> Option<Hand> {
         let a = self.draw(rng)?;
         let b = self.draw(rng)?;
-        Some(Hand::of_two_cards(a, b))
+        Hand::try_of_two_cards(a, b).ok()
     }
 
     pub fn reset(&mut self) {
         self.len = self.max_len;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeInclusive;
+
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    use crate::evaluator::NaiveEvaluator;
+    use crate::range::RangeTable;
+
+    use super::*;
+
+    // Hold'em without the given cards in the deck.
+    struct StrippedDeck(Cards);
+
+    impl GameRules for StrippedDeck {
+        fn name(&self) -> &'static str {
+            "stripped"
+        }
+
+        fn hole_cards(&self) -> RangeInclusive<u8> {
+            2..=2
+        }
+
+        fn deck(&self) -> Cards {
+            !self.0
+        }
+
+        fn score(&self, hole_cards: Cards, board: Cards) -> Score {
+            Holdem::new().score(hole_cards, board)
+        }
+    }
+
+    fn naive_holdem() -> Holdem<NaiveEvaluator> {
+        Holdem::with_evaluator(NaiveEvaluator)
+    }
+
+    fn flop_spot() -> (Cards, Hand, Vec<RangeTable>) {
+        let ranges = vec![RangeTable::parse("QQ+,AKs,AKo").unwrap(), RangeTable::parse("TT+,AQs+,KQs").unwrap()];
+        ("AsTd3h".parse().unwrap(), "AhTh".parse().unwrap(), ranges)
+    }
+
+    #[test]
+    fn enumerate_hook_sees_every_showdown() {
+        let (community_cards, hero_hand, villain_ranges) = flop_spot();
+        let expected = Equity::enumerate(community_cards, hero_hand, &villain_ranges).unwrap();
+        let mut calls = 0u64;
+        let (result, _) = Equity::enumerate_with_hook(community_cards, hero_hand, &villain_ranges, |_: &Showdown| {
+            calls += 1;
+        }).unwrap();
+        assert_eq!(calls, result.work.showdowns);
+        assert_eq!(result.equities, expected.equities);
+    }
+
+    #[test]
+    fn simulate_hook_sees_every_showdown() {
+        let (community_cards, hero_hand, _) = flop_spot();
+        let expected = Equity::simulate(community_cards, hero_hand, 2, 10_000, &mut SmallRng::seed_from_u64(1)).unwrap();
+        let mut calls = 0u64;
+        let (result, _) = Equity::simulate_with_hook(
+            community_cards,
+            hero_hand,
+            2,
+            10_000,
+            &mut SmallRng::seed_from_u64(1),
+            |_: &Showdown| calls += 1,
+        ).unwrap();
+        assert_eq!(calls, result.work.showdowns);
+        assert_eq!(result.equities, expected.equities);
+    }
+
+    #[test]
+    fn simulate_ranges_hook_sees_every_showdown() {
+        let (community_cards, hero_hand, villain_ranges) = flop_spot();
+        let weighted: Vec<_> = villain_ranges.iter().map(WeightedRange::from_range).collect();
+        let simulator = || RangeSimulator::new(&weighted, SamplingStrategy::ExactRejection).unwrap();
+        let dead_cards = Cards::EMPTY;
+        let mut rng = SmallRng::seed_from_u64(1);
+        let expected = Equity::simulate_ranges(community_cards, hero_hand, dead_cards, simulator(), 10_000, &mut rng)
+            .unwrap();
+        let mut calls = 0u64;
+        let hook = |showdown: &Showdown| {
+            assert_eq!(showdown.hands[0], hero_hand);
+            calls += 1;
+        };
+        let mut rng = SmallRng::seed_from_u64(1);
+        let (result, _) = Equity::simulate_ranges_with_hook(
+            community_cards,
+            hero_hand,
+            dead_cards,
+            simulator(),
+            10_000,
+            &mut rng,
+            hook,
+        ).unwrap();
+        assert_eq!(calls, result.work.showdowns);
+        assert_eq!(result.equities, expected.equities);
+    }
+
+    #[test]
+    fn naive_evaluator_matches_enumerate() {
+        let spots = [
+            ("AsTd3h", "AhTh", "QQ+,AKs,AKo"),
+            ("5c4d3h2s", "AhKh", "TT+,A5s,66"),
+            ("9s8s7d2c2h", "JsTs", "22+,A2s+,KQo"),
+        ];
+        for (community_cards, hero_hand, villain_range) in spots {
+            let community_cards: Cards = community_cards.parse().unwrap();
+            let hero_hand: Hand = hero_hand.parse().unwrap();
+            let villain_ranges = [RangeTable::parse(villain_range).unwrap()];
+            let expected = Equity::enumerate(community_cards, hero_hand, &villain_ranges).unwrap();
+            let result = Equity::enumerate_with_rules(community_cards, hero_hand, &villain_ranges, naive_holdem())
+                .unwrap();
+            assert_eq!(result.equities, expected.equities, "{community_cards} {hero_hand}");
+        }
+    }
+
+    #[test]
+    fn naive_evaluator_matches_simulate() {
+        let (community_cards, hero_hand, villain_ranges) = flop_spot();
+        let expected = Equity::simulate(community_cards, hero_hand, 2, 10_000, &mut SmallRng::seed_from_u64(1)).unwrap();
+        let mut rng = SmallRng::seed_from_u64(1);
+        let result = Equity::simulate_with_rules(community_cards, hero_hand, 2, 10_000, &mut rng, naive_holdem())
+            .unwrap();
+        assert_eq!(result.equities, expected.equities);
+
+        let weighted: Vec<_> = villain_ranges.iter().map(WeightedRange::from_range).collect();
+        let simulator = || RangeSimulator::new(&weighted, SamplingStrategy::ExactRejection).unwrap();
+        let mut rng = SmallRng::seed_from_u64(1);
+        let expected = Equity::simulate_ranges(community_cards, hero_hand, Cards::EMPTY, simulator(), 10_000, &mut rng)
+            .unwrap();
+        let mut rng = SmallRng::seed_from_u64(1);
+        let result = Equity::simulate_ranges_with_rules(
+            community_cards,
+            hero_hand,
+            Cards::EMPTY,
+            simulator(),
+            10_000,
+            &mut rng,
+            naive_holdem(),
+        ).unwrap();
+        assert_eq!(result.equities, expected.equities);
+    }
+
+    #[test]
+    fn merged_shards_match_enumerate() {
+        let (community_cards, hero_hand, villain_ranges) = flop_spot();
+        let expected = Equity::enumerate(community_cards, hero_hand, &villain_ranges).unwrap();
+        let board_count = Enumeration::new(community_cards, hero_hand, &villain_ranges).unwrap().board_count();
+        let shard = |start, end| {
+            let mut enumeration = Enumeration::new(community_cards, hero_hand, &villain_ranges)
+                .unwrap()
+                .shard(start, end)
+                .unwrap();
+            enumeration.run_until(end);
+            enumeration.checkpoint()
+        };
+        let (first, second) = (shard(0, board_count / 3), shard(board_count / 3, board_count));
+        for merged in [first.merge(&second).unwrap(), second.merge(&first).unwrap()] {
+            assert!(merged.is_done());
+            assert_eq!(merged.equities().unwrap(), expected.equities);
+        }
+        assert!(first.merge(&first).is_err());
+    }
+
+    #[test]
+    fn deck_of_the_rules_is_dealt() {
+        let (community_cards, hero_hand, villain_ranges) = flop_spot();
+        let stripped: Cards = "KcQd9h".parse().unwrap();
+        let expected = Equity::enumerate_with_dead_cards(community_cards, hero_hand, stripped, &villain_ranges).unwrap();
+        let result = Equity::enumerate_with_rules(community_cards, hero_hand, &villain_ranges, StrippedDeck(stripped))
+            .unwrap();
+        assert_eq!(result.equities, expected.equities);
+
+        let weighted: Vec<_> = villain_ranges.iter().map(WeightedRange::from_range).collect();
+        let simulator = || RangeSimulator::new(&weighted, SamplingStrategy::ExactRejection).unwrap();
+        let mut rng = SmallRng::seed_from_u64(1);
+        let expected = Equity::simulate_ranges(community_cards, hero_hand, stripped, simulator(), 10_000, &mut rng)
+            .unwrap();
+        let mut rng = SmallRng::seed_from_u64(1);
+        let result = Equity::simulate_ranges_with_rules(
+            community_cards,
+            hero_hand,
+            Cards::EMPTY,
+            simulator(),
+            10_000,
+            &mut rng,
+            StrippedDeck(stripped),
+        ).unwrap();
+        assert_eq!(result.equities, expected.equities);
+
+        let board: Cards = "AsTd3hQd".parse().unwrap();
+        assert!(Equity::enumerate_with_rules(board, hero_hand, &villain_ranges, StrippedDeck(stripped)).is_none());
+    }
+}
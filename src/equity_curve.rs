@@ -0,0 +1,108 @@
+use rand::Rng;
+
+use crate::cards::Cards;
+use crate::equity::{tie_units, Deck, Equity};
+use crate::error::Error;
+use crate::hand::Hand;
+use crate::range::RangeTable;
+use crate::result::Result;
+
+pub const MAX_CURVE_OPPONENTS: usize = 9;
+
+// Hero equity against 1 to max_opponents opponents, either random hands
+// or all playing the same range. Every round deals the board and all opponents once
+// and compares hero against the first k opponents for every k.
+//
+// Random opponents are dealt from the remaining deck, so every round
+// is a showdown for every opponent count. Range opponents are drawn from the
+// full range and only the opponents before the first overlapping hand are used
+// (the accepted prefixes are exact samples), larger opponent counts get fewer
+// showdowns. The curve ends before the first count without any showdown.
+pub fn equity_curve(
+    community_cards: Cards,
+    hero_hand: Hand,
+    range: Option<&RangeTable>,
+    max_opponents: usize,
+    rounds: u64,
+    rng: &mut impl Rng,
+) -> Result<Vec<Equity>> {
+    let hero_cards = hero_hand.to_cards();
+    if community_cards.count() > 5 {
+        return Err(Error::usage("curve failed: expected at most 5 community cards"));
+    }
+    if let Some(card) = (community_cards & hero_cards).first() {
+        return Err(Error::DuplicateCard(card));
+    }
+    if !(1..=MAX_CURVE_OPPONENTS).contains(&max_opponents) || rounds == 0 {
+        return Err(format!(
+            "curve failed: expected 1 to {MAX_CURVE_OPPONENTS} opponents and at least one round",
+        ).into());
+    }
+    let range_hands = range.map(|range| {
+        let mut hands = Vec::new();
+        range.for_each_hand(|hand| hands.push(hand));
+        hands
+    });
+    if range_hands.as_ref().is_some_and(|hands| hands.is_empty()) {
+        return Err("curve failed: empty range".into());
+    }
+
+    let known_cards = community_cards | hero_cards;
+    let mut deck = Deck::from_cards(rng, known_cards);
+    let mut totals = vec![0u64; max_opponents];
+    let mut wins = vec![0u64; max_opponents];
+    let mut ties = vec![0; max_opponents];
+    let mut villain_scores = Vec::with_capacity(max_opponents);
+    for _ in 0..rounds {
+        deck.reset();
+        let mut board = community_cards;
+        while board.count() < 5 {
+            board.add(deck.draw(rng).unwrap());
+        }
+        let hero_score = (board | hero_cards).score_fast();
+        villain_scores.clear();
+        match &range_hands {
+            None => for _ in 0..max_opponents {
+                let hand = deck.hand(rng).unwrap();
+                villain_scores.push((board | hand.to_cards()).score_fast());
+            },
+            Some(hands) => {
+                let mut dealt_cards = known_cards | board;
+                for _ in 0..max_opponents {
+                    let hand = hands[rng.gen_range(0..hands.len())];
+                    if (hand.to_cards() & dealt_cards) != Cards::EMPTY {
+                        break;
+                    }
+                    dealt_cards |= hand.to_cards();
+                    villain_scores.push((board | hand.to_cards()).score_fast());
+                }
+            },
+        }
+
+        // Hero against the first k + 1 opponents.
+        let mut tied_villains = 0;
+        for (k, score) in villain_scores.iter().enumerate() {
+            if *score > hero_score {
+                for total in &mut totals[k..villain_scores.len()] {
+                    *total += 1;
+                }
+                break;
+            }
+            if *score == hero_score {
+                tied_villains += 1;
+            }
+            totals[k] += 1;
+            if tied_villains == 0 {
+                wins[k] += 1;
+            } else {
+                ties[k] += tie_units(tied_villains + 1);
+            }
+        }
+    }
+
+    let curve: Vec<_> = (0..max_opponents)
+        .take_while(|k| totals[*k] != 0)
+        .map(|k| Equity::from_total_wins_ties(totals[k], &wins[k..k+1], &ties[k..k+1])[0])
+        .collect();
+    Ok(curve)
+}
@@ -0,0 +1,216 @@
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::card::Card;
+use crate::cards::Cards;
+use crate::equity::{check_live_combos, total_combos_upper_bound, Equity, EquityResult, BOARD_SIZE};
+use crate::error::Error;
+use crate::hand::Hand;
+use crate::range::RangeTable;
+use crate::range_simulator::{RangeSimulator, SamplingStrategy};
+use crate::result::Result;
+use crate::weighted_range::WeightedRange;
+
+pub const MAX_VILLAINS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    // Every board and every villain combo.
+    Enumerate,
+    // Random boards and villain hands from their ranges.
+    Simulate { rounds: u64 },
+}
+
+// The inputs of an equity calculation, e.g.
+// EquityRequest::new().board(flop).hero(hand).villain_range(range).run()?.
+// Unlike the Equity functions, invalid input is an error saying what is wrong:
+// a duplicate card is Error::DuplicateCard, a range without live combos
+// Error::NoLiveCombos, an enumeration too large Error::TooLarge
+// and any other invalid input Error::Usage.
+#[derive(Clone)]
+pub struct EquityRequest {
+    board: Cards,
+    hero: Option<Hand>,
+    villain_ranges: Vec<RangeTable>,
+    dead_cards: Cards,
+    mode: Mode,
+    sampling: SamplingStrategy,
+}
+
+impl Default for EquityRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EquityRequest {
+    // No board, no dead cards and enumerated (simulated with ExactRejection).
+    pub fn new() -> Self {
+        Self {
+            board: Cards::EMPTY,
+            hero: None,
+            villain_ranges: Vec::new(),
+            dead_cards: Cards::EMPTY,
+            mode: Mode::Enumerate,
+            sampling: SamplingStrategy::ExactRejection,
+        }
+    }
+
+    pub fn board(mut self, board: Cards) -> Self {
+        self.board = board;
+        self
+    }
+
+    pub fn hero(mut self, hero: Hand) -> Self {
+        self.hero = Some(hero);
+        self
+    }
+
+    // Adds the next villain.
+    pub fn villain_range(mut self, range: RangeTable) -> Self {
+        self.villain_ranges.push(range);
+        self
+    }
+
+    // Cards out of play, e.g. folded or burnt, they are neither dealt to the board nor to a villain.
+    pub fn dead_cards(mut self, dead_cards: Cards) -> Self {
+        self.dead_cards = dead_cards;
+        self
+    }
+
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    // How a simulation deals the villain hands, both follow the weights exactly.
+    pub fn sampling(mut self, sampling: SamplingStrategy) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    // Returns the hero hand if the request can be run.
+    pub fn validate(&self) -> Result<Hand> {
+        let hero = self.hero.ok_or_else(|| Error::usage("equity request failed: no hero hand"))?;
+        if self.board.count() > BOARD_SIZE {
+            return Err(Error::usage(format!(
+                "equity request failed: {} community cards, expected at most {}",
+                self.board.count(),
+                BOARD_SIZE,
+            )));
+        }
+        let hero_cards = hero.to_cards();
+        let duplicate = (self.board & hero_cards)
+            .first()
+            .or_else(|| (self.dead_cards & (self.board | hero_cards)).first());
+        if let Some(card) = duplicate {
+            return Err(Error::DuplicateCard(card));
+        }
+        if !(1..=MAX_VILLAINS).contains(&self.villain_ranges.len()) {
+            return Err(Error::usage(format!(
+                "equity request failed: {} villains, expected 1 to {}",
+                self.villain_ranges.len(),
+                MAX_VILLAINS,
+            )));
+        }
+        if let Some(villain) = self.villain_ranges.iter().position(|range| range.is_empty()) {
+            return Err(Error::usage(format!("equity request failed: villain {} has an empty range", villain + 1)));
+        }
+        check_live_combos(self.board | self.dead_cards, hero, &self.villain_ranges)?;
+        let known = usize::from((self.board | hero_cards | self.dead_cards).count());
+        let dealt = usize::from(BOARD_SIZE - self.board.count()) + 2*self.villain_ranges.len();
+        if known + dealt > Card::COUNT {
+            return Err(Error::usage("equity request failed: not enough cards left to deal"));
+        }
+        match self.mode {
+            Mode::Enumerate => {
+                let upper_bound = total_combos_upper_bound(self.board, &self.villain_ranges, BOARD_SIZE);
+                if u64::try_from(upper_bound).is_err() {
+                    return Err(Error::too_large("equity request failed: expected sample to large"));
+                }
+            },
+            Mode::Simulate { rounds: 0 } => {
+                return Err(Error::usage("equity request failed: no rounds to simulate"));
+            },
+            Mode::Simulate { .. } => (),
+        }
+        Ok(hero)
+    }
+
+    pub fn run(&self) -> Result<EquityResult> {
+        self.run_with_rng(&mut SmallRng::from_entropy())
+    }
+
+    // Like run, the rng deals the simulated boards and villain hands.
+    pub fn run_with_rng(&self, rng: &mut impl Rng) -> Result<EquityResult> {
+        let hero = self.validate()?;
+        let result = match self.mode {
+            Mode::Enumerate => {
+                Equity::enumerate_with_dead_cards(self.board, hero, self.dead_cards, &self.villain_ranges)
+            },
+            Mode::Simulate { rounds } => {
+                let weighted: Vec<_> = self.villain_ranges.iter().map(WeightedRange::from_range).collect();
+                let simulator = RangeSimulator::new(&weighted, self.sampling)
+                    .ok_or_else(|| Error::usage("equity request failed: invalid villain range"))?;
+                Equity::simulate_ranges(self.board, hero, self.dead_cards, simulator, rounds, rng)
+            },
+        };
+        // Every range has live combos, but not together, e.g. two villains holding AA on an ace high board.
+        result.ok_or_else(|| "equity request failed: the villain ranges can't be dealt without sharing a card".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flop_request() -> EquityRequest {
+        EquityRequest::new()
+            .board("AsTd3h".parse().unwrap())
+            .hero("AhTh".parse().unwrap())
+            .villain_range(RangeTable::parse("QQ+,AKs,AKo").unwrap())
+            .villain_range(RangeTable::parse("TT+,AQs+,KQs").unwrap())
+    }
+
+    #[test]
+    fn duplicate_card_is_an_error() {
+        let request = flop_request().dead_cards("Td".parse().unwrap());
+        assert!(matches!(request.validate(), Err(Error::DuplicateCard(card)) if card == "Td".parse().unwrap()));
+        let request = flop_request().hero("AsKs".parse().unwrap());
+        assert!(matches!(request.validate(), Err(Error::DuplicateCard(card)) if card == "As".parse().unwrap()));
+    }
+
+    #[test]
+    fn no_live_combos_is_an_error() {
+        let request = flop_request().villain_range(RangeTable::parse("AA").unwrap()).dead_cards("Ac".parse().unwrap());
+        assert!(matches!(request.validate(), Err(Error::NoLiveCombos(_))));
+    }
+
+    #[test]
+    fn too_large_enumeration_is_an_error() {
+        let request = (0..7).fold(EquityRequest::new().hero("AhKh".parse().unwrap()), |request, _| {
+            request.villain_range(RangeTable::parse("full").unwrap())
+        });
+        assert!(matches!(request.validate(), Err(Error::TooLarge(_))));
+        let request = request.mode(Mode::Simulate { rounds: 1_000 });
+        assert!(request.validate().is_ok());
+    }
+
+    #[test]
+    fn simulate_matches_enumerate() {
+        let request = flop_request().dead_cards("Kc".parse().unwrap());
+        let expected = request.run().unwrap();
+        for sampling in [SamplingStrategy::ExactRejection, SamplingStrategy::Sequential] {
+            let request = request.clone().mode(Mode::Simulate { rounds: 50_000 }).sampling(sampling);
+            let result = request.run_with_rng(&mut SmallRng::seed_from_u64(1)).unwrap();
+            for (simulated, enumerated) in result.equities.iter().zip(&expected.equities) {
+                // Fractions, within 4 standard errors of the simulation.
+                let tolerance = 4.0 * simulated.std_error();
+                let (simulated, enumerated) = (simulated.equity_percent(), enumerated.equity_percent());
+                assert!(
+                    (simulated - enumerated).abs() < tolerance,
+                    "{:?}: {} expected {}±{}", sampling, simulated, enumerated, tolerance,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use crate::cards::Cards;
+use crate::equity::{Equity, EquityResult};
+use crate::hand::Hand;
+use crate::range::HandRange;
+use crate::result::Result;
+
+// Villain ranges expanded into their combos once, reused by every enumeration
+// against them, e.g. for many hero hands or boards. Iterating a range table
+// works out the combos again for every board and every combo of the previous villains.
+#[derive(Debug, Clone)]
+pub struct EquitySession {
+    villain_combos: Vec<Vec<Hand>>,
+}
+
+impl EquitySession {
+    pub fn new(villain_ranges: &[impl HandRange]) -> Self {
+        let villain_combos = villain_ranges.iter()
+            .map(|range| {
+                let mut combos = Vec::new();
+                range.for_each_hand(|hand| combos.push(hand));
+                combos
+            })
+            .collect();
+        Self { villain_combos }
+    }
+
+    pub fn villain_combos(&self) -> &[Vec<Hand>] {
+        &self.villain_combos
+    }
+
+    // Same as Equity::enumerate with the ranges of the session.
+    pub fn enumerate(&self, community_cards: Cards, hero_hand: Hand) -> Option<EquityResult> {
+        Equity::enumerate(community_cards, hero_hand, &self.villain_combos)
+    }
+
+    // Same as Equity::try_enumerate with the ranges of the session.
+    pub fn try_enumerate(&self, community_cards: Cards, hero_hand: Hand) -> Result<EquityResult> {
+        Equity::try_enumerate(community_cards, hero_hand, &self.villain_combos)
+    }
+}
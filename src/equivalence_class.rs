@@ -0,0 +1,131 @@
+use core::fmt;
+use std::sync::OnceLock;
+
+use crate::card::Card;
+use crate::cards::{Cards, HandRanking, Score};
+use crate::rank::Rank;
+use crate::suite::Suite;
+
+// Every score of a five card hand with a representative hand, best first.
+static CLASSES: OnceLock<Vec<(Score, Cards)>> = OnceLock::new();
+
+// Heap memory in bytes of the equivalence classes, if they are loaded.
+pub fn loaded_memory() -> usize {
+    CLASSES.get().map_or(0, |classes| classes.capacity() * size_of::<(Score, Cards)>())
+}
+
+// Number of equivalence classes of every category,
+// indexed by HandRanking::category (high card to royal flush).
+pub const CATEGORY_CLASS_COUNTS: [usize; HandRanking::CATEGORY_COUNT] = [
+    1277,
+    2860,
+    858,
+    858,
+    10,
+    1277,
+    156,
+    156,
+    9,
+    1,
+];
+
+// One of the 7462 distinct five card hand strengths, the hands of a class always tie.
+// Numbered like most other evaluators do: 1 is the royal flush, 7462 is 7-5-4-3-2 offsuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EquivalenceClass(u16);
+
+impl fmt::Display for EquivalenceClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl EquivalenceClass {
+    pub const COUNT: usize = 7462;
+
+    pub const BEST: Self = Self(1);
+
+    pub const WORST: Self = Self(Self::COUNT as u16);
+
+    // None if the score isn't the score of five or more cards.
+    pub fn of_score(score: Score) -> Option<Self> {
+        let index = classes().binary_search_by(|(class_score, _)| score.cmp(class_score)).ok()?;
+        Some(Self(u16::try_from(index + 1).unwrap()))
+    }
+
+    // The class of the best five of 5 or more cards.
+    pub fn of_cards(cards: Cards) -> Self {
+        Self::of_score(cards.score()).unwrap()
+    }
+
+    pub fn from_index(index: u16) -> Option<Self> {
+        (1..=Self::WORST.0).contains(&index).then_some(Self(index))
+    }
+
+    pub fn index(self) -> u16 {
+        self.0
+    }
+
+    pub fn score(self) -> Score {
+        classes()[usize::from(self.0 - 1)].0
+    }
+
+    // Five cards of the class, the lowest suits first.
+    pub fn representative(self) -> Cards {
+        classes()[usize::from(self.0 - 1)].1
+    }
+
+    pub fn all() -> impl Iterator<Item = Self> {
+        (1..=Self::WORST.0).map(Self)
+    }
+}
+
+fn classes() -> &'static [(Score, Cards)] {
+    CLASSES.get_or_init(|| {
+        let mut classes = Vec::with_capacity(EquivalenceClass::COUNT);
+        let mut counts = [0; Rank::COUNT];
+        for_each_rank_counts(&mut counts, 0, 5, &mut |counts| {
+            // Spread over the suits, so that five different ranks are no flush.
+            let mut cards = Cards::EMPTY;
+            let mut suites = Suite::SUITES.iter().copied().cycle();
+            for rank in Rank::RANKS {
+                for _ in 0..counts[rank.to_usize()] {
+                    cards.add(Card::of(rank, suites.next().unwrap()));
+                }
+            }
+            classes.push((cards.top5().to_score(), cards));
+
+            if counts.iter().all(|count| *count <= 1) {
+                let flush = Rank::RANKS.iter()
+                    .filter(|rank| counts[rank.to_usize()] == 1)
+                    .map(|rank| Card::of(*rank, Suite::SUITES[0]))
+                    .collect::<Cards>();
+                classes.push((flush.top5().to_score(), flush));
+            }
+        });
+        classes.sort_unstable_by(|(a, _), (b, _)| b.cmp(a));
+        assert_eq!(classes.len(), EquivalenceClass::COUNT);
+        classes
+    })
+}
+
+// Every way to pick remaining ranks from rank_index on, at most four of a rank.
+fn for_each_rank_counts(
+    counts: &mut [u8; Rank::COUNT],
+    rank_index: usize,
+    remaining: u8,
+    f: &mut impl FnMut(&[u8; Rank::COUNT]),
+) {
+    if remaining == 0 {
+        f(counts);
+        return;
+    }
+    if rank_index == Rank::COUNT {
+        return;
+    }
+    for count in 0..=remaining.min(4) {
+        counts[rank_index] = count;
+        for_each_rank_counts(counts, rank_index + 1, remaining - count, f);
+    }
+    counts[rank_index] = 0;
+}
@@ -0,0 +1,121 @@
+use core::fmt;
+use std::io;
+use std::num::{ParseFloatError, ParseIntError};
+
+use crate::card::Card;
+use crate::equity::NoLiveCombos;
+use crate::parse_error::ParseError;
+
+// Every error of the crate, match on the variant to handle a kind of failure.
+#[derive(Debug)]
+pub enum Error {
+    // Malformed cards, hands, ranges and so on (ParseError::kind names what),
+    // with the position of the offending token in the input.
+    Parse(ParseError),
+    // Malformed integers.
+    Int(ParseIntError),
+    // Malformed floating point numbers.
+    Float(ParseFloatError),
+    // The same card in two places, e.g. on the board and in the hero hand, or twice in one input.
+    DuplicateCard(Card),
+    // Every combo of a villain range is blocked by the known cards.
+    NoLiveCombos(NoLiveCombos),
+    // Unknown command or arguments of the wrong number or shape.
+    Usage(String),
+    // Valid input, but too large to enumerate.
+    TooLarge(String),
+    Io(io::Error),
+    // The calculation was stopped, see TaskHandle::cancel.
+    Cancelled,
+    // Everything else.
+    Other(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(err) => err.fmt(f),
+            Error::Int(err) => err.fmt(f),
+            Error::Float(err) => err.fmt(f),
+            Error::DuplicateCard(card) => write!(f, "duplicate card {}", card),
+            Error::NoLiveCombos(err) => err.fmt(f),
+            Error::Usage(message) | Error::TooLarge(message) | Error::Other(message) => {
+                f.write_str(message)
+            },
+            Error::Io(err) => err.fmt(f),
+            Error::Cancelled => f.write_str("calculation cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse(err) => Some(err),
+            Error::Int(err) => Some(err),
+            Error::Float(err) => Some(err),
+            Error::NoLiveCombos(err) => Some(err),
+            Error::Io(err) => Some(err),
+            Error::DuplicateCard(_)
+                | Error::Usage(_)
+                | Error::TooLarge(_)
+                | Error::Cancelled
+                | Error::Other(_) => None,
+        }
+    }
+}
+
+impl Error {
+    pub fn usage(message: impl Into<String>) -> Self {
+        Error::Usage(message.into())
+    }
+
+    pub fn too_large(message: impl Into<String>) -> Self {
+        Error::TooLarge(message.into())
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        match err.duplicate {
+            Some(card) => Error::DuplicateCard(card),
+            None => Error::Parse(err),
+        }
+    }
+}
+
+impl From<ParseIntError> for Error {
+    fn from(err: ParseIntError) -> Self {
+        Error::Int(err)
+    }
+}
+
+impl From<ParseFloatError> for Error {
+    fn from(err: ParseFloatError) -> Self {
+        Error::Float(err)
+    }
+}
+
+impl From<NoLiveCombos> for Error {
+    fn from(err: NoLiveCombos) -> Self {
+        Error::NoLiveCombos(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Other(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::Other(message.to_string())
+    }
+}
@@ -0,0 +1,28 @@
+use crate::cards::{Cards, Score};
+
+// Scores the best five card hand out of 5 or more cards, a higher score is a better hand.
+// The equity calculations are generic over the evaluator, e.g. to check them
+// against a reference evaluator or to plug in one with different hand rankings.
+pub trait Evaluator {
+    fn score(&self, cards: Cards) -> Score;
+}
+
+// The lookup tables, or the direct evaluation below a memory limit, see ScoreBackend.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FastEvaluator;
+
+impl Evaluator for FastEvaluator {
+    fn score(&self, cards: Cards) -> Score {
+        cards.score()
+    }
+}
+
+// Picks the best five cards by hand ranking first, slow but straightforward.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NaiveEvaluator;
+
+impl Evaluator for NaiveEvaluator {
+    fn score(&self, cards: Cards) -> Score {
+        cards.top5().to_score()
+    }
+}
@@ -0,0 +1,243 @@
+use core::fmt;
+use std::cmp::Ordering;
+use std::fmt::Write;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::canonical::CanonicalBoard;
+use crate::cards::{Cards, Score};
+use crate::equity::boards_of_size;
+use crate::hand::Hand;
+use crate::range::HandRange;
+use crate::result::Result;
+
+// Range a against range b on one flop:
+// equity: of range a, over the sampled runouts and every combo matchup,
+// nut share: share of the live combos of a range among the strongest combos
+// (by made hand on the flop) of both ranges together,
+// density: share of the live combos of a range that make a better hand category
+// than the flop itself, e.g. a pair or better on an unpaired flop.
+#[derive(Debug, Clone, Copy)]
+pub struct FlopMetrics {
+    pub board: CanonicalBoard,
+    pub equity_a: f64,
+    pub nut_share_a: f64,
+    pub nut_share_b: f64,
+    pub density_a: f64,
+    pub density_b: f64,
+}
+
+impl fmt::Display for FlopMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} equity-advantage={:+.2} nut-advantage={:+.2} density a={:2.2} b={:2.2}",
+            self.board,
+            self.equity_advantage() * 100.0,
+            self.nut_advantage() * 100.0,
+            self.density_a * 100.0,
+            self.density_b * 100.0,
+        )
+    }
+}
+
+impl FlopMetrics {
+    // Equity of range a minus equity of range b.
+    pub fn equity_advantage(&self) -> f64 {
+        2.0 * self.equity_a - 1.0
+    }
+
+    pub fn nut_advantage(&self) -> f64 {
+        self.nut_share_a - self.nut_share_b
+    }
+}
+
+// Equity of one combo of range a on a flop, over the same runouts as FlopMetrics::equity_a.
+#[derive(Debug, Clone, Copy)]
+pub struct ComboEquity {
+    pub hand: Hand,
+    pub equity: f64,
+}
+
+pub const CSV_HEADER: &str =
+    "board,weight,equity_a,equity_advantage,nut_share_a,nut_share_b,nut_advantage,density_a,density_b";
+
+// One CSV row in the order of CSV_HEADER, shares in percent.
+pub fn to_csv_row(metrics: &FlopMetrics) -> String {
+    let mut cards: Vec<_> = metrics.board.cards.iter().collect();
+    cards.sort_by_key(|card| core::cmp::Reverse(card.rank()));
+    let mut row = String::new();
+    for card in cards {
+        write!(row, "{}", card).unwrap();
+    }
+    write!(
+        row,
+        ",{},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2}",
+        metrics.board.weight,
+        metrics.equity_a * 100.0,
+        metrics.equity_advantage() * 100.0,
+        metrics.nut_share_a * 100.0,
+        metrics.nut_share_b * 100.0,
+        metrics.nut_advantage() * 100.0,
+        metrics.density_a * 100.0,
+        metrics.density_b * 100.0,
+    ).unwrap();
+    row
+}
+
+pub const COMBO_CSV_HEADER: &str = "board,weight,hand,equity";
+
+// One CSV row per combo in the order of COMBO_CSV_HEADER, the equity in percent.
+pub fn to_combo_csv_row(board: &CanonicalBoard, combo: &ComboEquity) -> String {
+    let mut cards: Vec<_> = board.cards.iter().collect();
+    cards.sort_by_key(|card| core::cmp::Reverse(card.rank()));
+    let mut row = String::new();
+    for card in cards {
+        write!(row, "{}", card).unwrap();
+    }
+    write!(row, ",{},{},{:.2}", board.weight, combo.hand, combo.equity * 100.0).unwrap();
+    row
+}
+
+// The metrics of every flop. Each flop samples at most max_runouts turns and rivers,
+// all of them if there are no more. The nut fraction (e.g. 0.1) selects the strongest
+// combos of both ranges for the nut shares.
+pub fn flop_metrics(
+    boards: &[CanonicalBoard],
+    range_a: &impl HandRange,
+    range_b: &impl HandRange,
+    max_runouts: usize,
+    nut_fraction: f64,
+    rng: &mut impl Rng,
+) -> Result<Vec<FlopMetrics>> {
+    let mut metrics = Vec::with_capacity(boards.len());
+    for_each_flop_metrics(boards, range_a, range_b, max_runouts, nut_fraction, rng, |flop, _| {
+        metrics.push(flop);
+        Ok(())
+    })?;
+    Ok(metrics)
+}
+
+// Like flop_metrics, but hands every flop to f as soon as it is computed,
+// with the equities of the combos of range a that have a matchup on it.
+// Only one flop is held in memory at a time, e.g. to write all 1755 flops
+// times every combo of a range without collecting the rows first.
+pub fn for_each_flop_metrics(
+    boards: &[CanonicalBoard],
+    range_a: &impl HandRange,
+    range_b: &impl HandRange,
+    max_runouts: usize,
+    nut_fraction: f64,
+    rng: &mut impl Rng,
+    mut f: impl FnMut(FlopMetrics, &[ComboEquity]) -> Result<()>,
+) -> Result<()> {
+    if max_runouts == 0 {
+        return Err("flop metrics failed: expected at least one runout".into());
+    }
+    if !(nut_fraction > 0.0 && nut_fraction <= 1.0) {
+        return Err("flop metrics failed: expected a nut fraction above 0 and at most 1".into());
+    }
+    let mut hands_a = Vec::new();
+    range_a.for_each_hand(|hand| hands_a.push(hand));
+    let mut hands_b = Vec::new();
+    range_b.for_each_hand(|hand| hands_b.push(hand));
+    let mut combos = Vec::new();
+    for board in boards {
+        if board.cards.count() != 3 {
+            return Err(format!("flop metrics failed: expected a flop, got {}", board).into());
+        }
+        let metrics = board_metrics(*board, &hands_a, &hands_b, max_runouts, nut_fraction, rng, &mut combos)?;
+        f(metrics, &combos)?;
+    }
+    Ok(())
+}
+
+fn board_metrics(
+    board: CanonicalBoard,
+    hands_a: &[Hand],
+    hands_b: &[Hand],
+    max_runouts: usize,
+    nut_fraction: f64,
+    rng: &mut impl Rng,
+    combos: &mut Vec<ComboEquity>,
+) -> Result<FlopMetrics> {
+    let live = |hands: &[Hand]| -> Vec<Hand> {
+        hands.iter().copied().filter(|hand| (hand.to_cards() & board.cards) == Cards::EMPTY).collect()
+    };
+    let (live_a, live_b) = (live(hands_a), live(hands_b));
+    if live_a.is_empty() || live_b.is_empty() {
+        return Err(format!("flop metrics failed: no live combos on {}", board).into());
+    }
+
+    let flop_scores = |hands: &[Hand]| -> Vec<Score> {
+        hands.iter().map(|hand| (board.cards | hand.to_cards()).score()).collect()
+    };
+    let (scores_a, scores_b) = (flop_scores(&live_a), flop_scores(&live_b));
+    let board_category = board.cards.score().to_hand_ranking().category();
+    let density = |scores: &[Score]| {
+        let improved = scores.iter()
+            .filter(|score| score.to_hand_ranking().category() > board_category)
+            .count();
+        improved as f64 / scores.len() as f64
+    };
+
+    let mut all_scores: Vec<_> = scores_a.iter().chain(&scores_b).copied().collect();
+    all_scores.sort_unstable_by(|a, b| b.cmp(a));
+    let nut_count = ((all_scores.len() as f64 * nut_fraction).ceil() as usize).max(1);
+    let nut_threshold = all_scores[nut_count - 1];
+    let nut_share = |scores: &[Score]| {
+        scores.iter().filter(|score| **score >= nut_threshold).count() as f64 / scores.len() as f64
+    };
+
+    let mut runouts = boards_of_size(board.cards, Cards::EMPTY, 5);
+    if runouts.len() > max_runouts {
+        runouts.shuffle(rng);
+        runouts.truncate(max_runouts);
+    }
+    // Pot shares in halves per combo of a, so ties stay integers.
+    let mut shares = vec![0u64; live_a.len()];
+    let mut totals = vec![0u64; live_a.len()];
+    for runout in runouts {
+        let river_scores = |hands: &[Hand]| -> Vec<(usize, Cards, Score)> {
+            hands.iter()
+                .enumerate()
+                .filter(|(_, hand)| (hand.to_cards() & runout) == Cards::EMPTY)
+                .map(|(index, hand)| (index, hand.to_cards(), (runout | hand.to_cards()).score_fast()))
+                .collect()
+        };
+        let river_b = river_scores(&live_b);
+        for (index, cards_a, score_a) in river_scores(&live_a) {
+            for (_, cards_b, score_b) in &river_b {
+                if (cards_a & *cards_b) != Cards::EMPTY {
+                    continue;
+                }
+                totals[index] += 2;
+                shares[index] += match score_a.cmp(score_b) {
+                    Ordering::Greater => 2,
+                    Ordering::Equal => 1,
+                    Ordering::Less => 0,
+                };
+            }
+        }
+    }
+    let (share, total): (u64, u64) = (shares.iter().sum(), totals.iter().sum());
+    if total == 0 {
+        return Err(format!("flop metrics failed: no valid matchups on {}", board).into());
+    }
+    combos.clear();
+    for ((hand, share), total) in live_a.iter().zip(&shares).zip(&totals) {
+        if *total != 0 {
+            combos.push(ComboEquity { hand: *hand, equity: *share as f64 / *total as f64 });
+        }
+    }
+
+    Ok(FlopMetrics {
+        board,
+        equity_a: share as f64 / total as f64,
+        nut_share_a: nut_share(&scores_a),
+        nut_share_b: nut_share(&scores_b),
+        density_a: density(&scores_a),
+        density_b: density(&scores_b),
+    })
+}
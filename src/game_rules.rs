@@ -0,0 +1,454 @@
+use std::ops::RangeInclusive;
+use std::time::Instant;
+
+use rand::Rng;
+
+use crate::card::Card;
+use crate::cards::{Cards, Score};
+use crate::equity::{boards_of_size, tie_units, Deck, Equity, EquityResult, Work, TIE_UNITS};
+use crate::evaluator::{Evaluator, FastEvaluator};
+use crate::rank::Rank;
+
+pub const MAX_PLAYERS: usize = 8;
+
+// Single or double board.
+pub const MAX_BOARDS: usize = 2;
+
+// Runouts an enumeration visits at most, e.g. double board from the flop has 894,470.
+const MAX_RUNOUTS: u128 = 10_000_000;
+
+// What makes a variant: how many hole cards, how the board is dealt,
+// which cards form a hand and whether the pot is split with the best low.
+// The engine below runs any variant with known hole cards,
+// the Equity engine the variants with two hole cards against ranges (see Holdem).
+pub trait GameRules {
+    fn name(&self) -> &'static str;
+
+    fn hole_cards(&self) -> RangeInclusive<u8>;
+
+    fn board_size(&self) -> u8 {
+        5
+    }
+
+    // Every board is an equal part of the pot, scored on its own.
+    fn board_counts(&self) -> RangeInclusive<usize> {
+        1..=MAX_BOARDS
+    }
+
+    // The cards in play, dealt boards never contain the others.
+    fn deck(&self) -> Cards {
+        !Cards::EMPTY
+    }
+
+    // The best high hand of a player on a complete board.
+    fn score(&self, hole_cards: Cards, board: Cards) -> Score;
+
+    // The scores of every player on the same board, e.g. to share the work on the board.
+    fn scores(&self, hands: &[Cards], board: Cards, scores: &mut [Score]) {
+        for (score, hand) in scores.iter_mut().zip(hands) {
+            *score = self.score(*hand, board);
+        }
+    }
+
+    // The best qualifying low, None if the variant has no low half
+    // or the player doesn't qualify.
+    fn low_score(&self, _hole_cards: Cards, _board: Cards) -> Option<LowScore> {
+        None
+    }
+}
+
+// Any five of the two hole cards and the board, scored by the evaluator.
+// The rules of Equity::enumerate, Equity::simulate and their variants.
+#[derive(Debug, Clone, Copy)]
+pub struct Holdem<E: Evaluator = FastEvaluator> {
+    evaluator: E,
+}
+
+impl Holdem {
+    pub fn new() -> Self {
+        Self { evaluator: FastEvaluator }
+    }
+}
+
+impl Default for Holdem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl <E: Evaluator> Holdem<E> {
+    pub fn with_evaluator(evaluator: E) -> Self {
+        Self { evaluator }
+    }
+}
+
+impl <E: Evaluator> GameRules for Holdem<E> {
+    fn name(&self) -> &'static str {
+        "holdem"
+    }
+
+    fn hole_cards(&self) -> RangeInclusive<u8> {
+        2..=2
+    }
+
+    fn score(&self, hole_cards: Cards, board: Cards) -> Score {
+        self.evaluator.score(hole_cards | board)
+    }
+}
+
+// Exactly two of 4 to 6 hole cards and three board cards,
+// hi/lo splits the pot with the best eight or better low.
+#[derive(Debug, Clone, Copy)]
+pub struct Omaha {
+    pub hi_lo: bool,
+}
+
+impl GameRules for Omaha {
+    fn name(&self) -> &'static str {
+        if self.hi_lo {
+            "omaha8"
+        } else {
+            "omaha"
+        }
+    }
+
+    fn hole_cards(&self) -> RangeInclusive<u8> {
+        4..=6
+    }
+
+    fn score(&self, hole_cards: Cards, board: Cards) -> Score {
+        let mut scores = [Score::ZERO];
+        self.scores(&[hole_cards], board, &mut scores);
+        scores[0]
+    }
+
+    // The board triples are built once for all players.
+    fn scores(&self, hands: &[Cards], board: Cards, scores: &mut [Score]) {
+        let triples = OmahaSubsets::board_triples(board);
+        for (score, hand) in scores.iter_mut().zip(hands) {
+            let pairs = OmahaSubsets::hole_pairs(*hand);
+            *score = Score::ZERO;
+            pairs.for_each_hand(&triples, |cards| *score = (*score).max(cards.score_five()));
+        }
+    }
+
+    fn low_score(&self, hole_cards: Cards, board: Cards) -> Option<LowScore> {
+        // A low needs three board cards of eight or lower.
+        if !self.hi_lo || LowScore::low_ranks(board).count_ones() < 3 {
+            return None;
+        }
+        let mut best = None;
+        let triples = OmahaSubsets::board_triples(board);
+        OmahaSubsets::hole_pairs(hole_cards).for_each_hand(&triples, |cards| if let Some(low) = LowScore::of(cards) {
+            best = Some(best.map_or(low, |best: LowScore| best.min(low)));
+        });
+        best
+    }
+}
+
+pub fn parse_game_rules(s: &str) -> Option<Box<dyn GameRules>> {
+    match s {
+        "holdem" => Some(Box::new(Holdem::new())),
+        "omaha" => Some(Box::new(Omaha { hi_lo: false })),
+        "omaha8" => Some(Box::new(Omaha { hi_lo: true })),
+        _ => None,
+    }
+}
+
+// Index pairs of up to 6 hole cards and index triples of up to 5 board cards,
+// ordered by their highest index: the first n choose 2 pairs (n choose 3 triples)
+// are exactly those of the first n cards.
+const HOLE_PAIRS: [[usize; 2]; 15] = [
+    [0, 1], [0, 2], [1, 2], [0, 3], [1, 3], [2, 3], [0, 4], [1, 4],
+    [2, 4], [3, 4], [0, 5], [1, 5], [2, 5], [3, 5], [4, 5],
+];
+const BOARD_TRIPLES: [[usize; 3]; 10] = [
+    [0, 1, 2], [0, 1, 3], [0, 2, 3], [1, 2, 3], [0, 1, 4],
+    [0, 2, 4], [1, 2, 4], [0, 3, 4], [1, 3, 4], [2, 3, 4],
+];
+
+// The hole pairs or board triples of some cards, looked up in the tables above.
+struct OmahaSubsets<const N: usize> {
+    subsets: [Cards; N],
+    len: usize,
+}
+
+impl OmahaSubsets<15> {
+    fn hole_pairs(hole_cards: Cards) -> Self {
+        Self::of(hole_cards, &HOLE_PAIRS)
+    }
+
+    // Every hand of exactly two hole cards and three board cards.
+    fn for_each_hand(&self, triples: &OmahaSubsets<10>, mut f: impl FnMut(Cards)) {
+        for pair in self.subsets() {
+            for triple in triples.subsets() {
+                f(*pair | *triple);
+            }
+        }
+    }
+}
+
+impl OmahaSubsets<10> {
+    fn board_triples(board: Cards) -> Self {
+        Self::of(board, &BOARD_TRIPLES)
+    }
+}
+
+impl <const N: usize> OmahaSubsets<N> {
+    fn of<const K: usize>(cards: Cards, table: &[[usize; K]; N]) -> Self {
+        let mut by_index = [Card::MIN; 6];
+        let mut count = 0;
+        for (slot, card) in by_index.iter_mut().zip(cards.iter()) {
+            *slot = card;
+            count += 1;
+        }
+        let mut subsets = [Cards::EMPTY; N];
+        let mut len = 0;
+        for (subset, indices) in subsets.iter_mut().zip(table) {
+            if indices[K - 1] >= count {
+                break;
+            }
+            *subset = indices.iter().fold(Cards::EMPTY, |subset, index| subset.with(by_index[*index]));
+            len += 1;
+        }
+        Self { subsets, len }
+    }
+
+    fn subsets(&self) -> &[Cards] {
+        &self.subsets[..self.len]
+    }
+}
+
+// Ace to five low with eight or better to qualify, straights and flushes don't count.
+// Lower is better, ordered by the highest card first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LowScore(u32);
+
+impl LowScore {
+    // The best low of any five of the cards.
+    pub fn of(cards: Cards) -> Option<Self> {
+        let ranks = Self::low_ranks(cards);
+        let mut lows = [0u32; 5];
+        let mut count = 0;
+        for low_rank in 1..=8 {
+            if count < lows.len() && ranks & (1 << low_rank) != 0 {
+                lows[count] = low_rank;
+                count += 1;
+            }
+        }
+        // Compared by the highest rank first.
+        (count == lows.len()).then(|| Self(lows.iter().rev().fold(0, |score, rank| score << 4 | rank)))
+    }
+
+    // Bit 1 (ace) to 8 set for every rank of eight or lower.
+    fn low_ranks(cards: Cards) -> u32 {
+        let mut ranks = 0u32;
+        for card in cards.iter() {
+            let low_rank = match card.rank() {
+                Rank::Ace => 1,
+                rank if rank <= Rank::Eight => rank.to_u32() + 2,
+                _ => continue,
+            };
+            ranks |= 1 << low_rank;
+        }
+        ranks
+    }
+}
+
+fn known_cards(rules: &dyn GameRules, boards: &[Cards], hands: &[Cards]) -> Option<Cards> {
+    if !(2..=MAX_PLAYERS).contains(&hands.len())
+        || !rules.board_counts().contains(&boards.len())
+        || boards.iter().any(|board| board.count() > rules.board_size())
+        || hands.iter().any(|hand| !rules.hole_cards().contains(&hand.count())) {
+        return None;
+    }
+    let mut known = Cards::EMPTY;
+    for cards in boards.iter().chain(hands).copied() {
+        if (known & cards) != Cards::EMPTY || (cards & !rules.deck()) != Cards::EMPTY {
+            return None;
+        }
+        known |= cards;
+    }
+    let available = (rules.deck() & !known).count();
+    let missing: u8 = boards.iter().map(|board| rules.board_size() - board.count()).sum();
+    (missing <= available).then_some(known)
+}
+
+fn runout_count(rules: &dyn GameRules, boards: &[Cards], known: Cards) -> u128 {
+    let mut available = u128::from((rules.deck() & !known).count());
+    let mut count = 1u128;
+    for board in boards {
+        let missing = u128::from(rules.board_size() - board.count());
+        let mut combinations = 1u128;
+        for i in 0..missing {
+            combinations = combinations * (available - i) / (i + 1);
+        }
+        count = count.saturating_mul(combinations);
+        available -= missing;
+    }
+    count
+}
+
+// Completes the boards one after the other, the later boards from the cards
+// not used by the earlier ones.
+fn for_each_runout(
+    rules: &dyn GameRules,
+    boards: &mut [Cards],
+    index: usize,
+    dead: Cards,
+    f: &mut impl FnMut(&[Cards]),
+) {
+    if index == boards.len() {
+        f(boards);
+        return;
+    }
+    let board = boards[index];
+    for runout in boards_of_size(board, dead, rules.board_size()) {
+        boards[index] = runout;
+        for_each_runout(rules, boards, index + 1, dead | runout, f);
+    }
+    boards[index] = board;
+}
+
+struct Showdowns {
+    scores: Vec<Score>,
+    low_scores: Vec<Option<LowScore>>,
+    // In tie units, a whole pot is TIE_UNITS.
+    shares: Vec<u128>,
+    wins: Vec<u64>,
+    ties: Vec<u128>,
+}
+
+impl Showdowns {
+    fn new(player_count: usize) -> Self {
+        Self {
+            scores: vec![Score::ZERO; player_count],
+            low_scores: vec![None; player_count],
+            shares: vec![0; player_count],
+            wins: vec![0; player_count],
+            ties: vec![0; player_count],
+        }
+    }
+
+    // A player winning the whole pot (all boards, high and low) gets a win,
+    // any smaller share of the pot counts as a tie.
+    fn runout(&mut self, rules: &dyn GameRules, boards: &[Cards], hands: &[Cards]) {
+        self.shares.fill(0);
+        let board_share = tie_units(boards.len());
+        for board in boards.iter().copied() {
+            rules.scores(hands, board, &mut self.scores);
+            for (low_score, hand) in self.low_scores.iter_mut().zip(hands) {
+                *low_score = rules.low_score(*hand, board);
+            }
+            let best_low = self.low_scores.iter().flatten().min().copied();
+            let high_share = if best_low.is_some() { board_share / 2 } else { board_share };
+            let best = self.scores.iter().copied().max().unwrap();
+            split(&mut self.shares, high_share, self.scores.iter().map(|score| *score == best));
+            if let Some(best_low) = best_low {
+                split(&mut self.shares, board_share / 2, self.low_scores.iter().map(|low| *low == Some(best_low)));
+            }
+        }
+        for (index, share) in self.shares.iter().copied().enumerate() {
+            if share == TIE_UNITS {
+                self.wins[index] += 1;
+            } else {
+                self.ties[index] += share;
+            }
+        }
+    }
+}
+
+fn split(shares: &mut [u128], pot: u128, winners: impl Iterator<Item = bool> + Clone) {
+    let count = winners.clone().filter(|winner| *winner).count();
+    for (share, winner) in shares.iter_mut().zip(winners) {
+        if winner {
+            *share += pot / u128::try_from(count).unwrap();
+        }
+    }
+}
+
+// Every runout of the boards with known hole cards.
+pub fn rules_enumerate(
+    rules: &dyn GameRules,
+    boards: &[Cards],
+    hands: &[Cards],
+) -> Option<EquityResult> {
+    let known = known_cards(rules, boards, hands)?;
+    if runout_count(rules, boards, known) > MAX_RUNOUTS {
+        return None;
+    }
+    let start = Instant::now();
+    let mut showdowns = Showdowns::new(hands.len());
+    let mut runouts = 0u64;
+    for_each_runout(rules, &mut boards.to_vec(), 0, known | !rules.deck(), &mut |runout| {
+        showdowns.runout(rules, runout, hands);
+        runouts += 1;
+    });
+    Some(rules_result(runouts, boards.len(), &showdowns, start))
+}
+
+pub fn rules_simulate(
+    rules: &dyn GameRules,
+    boards: &[Cards],
+    hands: &[Cards],
+    rounds: u64,
+    rng: &mut impl Rng,
+) -> Option<EquityResult> {
+    let known = known_cards(rules, boards, hands)?;
+    if rounds == 0 {
+        return None;
+    }
+    let start = Instant::now();
+    let mut deck = Deck::from_rules(rng, rules, known);
+    let mut runout = boards.to_vec();
+    let mut showdowns = Showdowns::new(hands.len());
+    for _ in 0..rounds {
+        deck.reset();
+        for (board, start_board) in runout.iter_mut().zip(boards) {
+            *board = *start_board;
+            while board.count() < rules.board_size() {
+                board.add(deck.draw(rng).unwrap());
+            }
+        }
+        showdowns.runout(rules, &runout, hands);
+    }
+    Some(rules_result(rounds, boards.len(), &showdowns, start))
+}
+
+fn rules_result(runouts: u64, board_count: usize, showdowns: &Showdowns, start: Instant) -> EquityResult {
+    let work = Work {
+        boards: runouts * board_count as u64,
+        showdowns: runouts,
+        elapsed: start.elapsed(),
+        ..Work::default()
+    };
+    EquityResult {
+        equities: Equity::from_total_wins_ties(runouts, &showdowns.wins, &showdowns.ties),
+        work,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn check_subsets<const N: usize>(subsets: &OmahaSubsets<N>, cards: Cards, size: u8, expected: usize) {
+        let distinct: HashSet<_> = subsets.subsets().iter().copied().collect();
+        assert_eq!(distinct.len(), expected, "{cards}");
+        assert!(distinct.iter().all(|subset| subset.count() == size && (*subset & !cards) == Cards::EMPTY));
+    }
+
+    #[test]
+    fn subsets_are_every_pair_and_triple() {
+        let cards: Cards = "AsKdQh9c7s3d".parse().unwrap();
+        let prefix = |count| cards.iter().take(count).fold(Cards::EMPTY, |cards, card| cards.with(card));
+        for (count, pairs) in [(4, 6), (5, 10), (6, 15)] {
+            check_subsets(&OmahaSubsets::hole_pairs(prefix(count)), prefix(count), 2, pairs);
+        }
+        for (count, triples) in [(3, 1), (4, 4), (5, 10)] {
+            check_subsets(&OmahaSubsets::board_triples(prefix(count)), prefix(count), 3, triples);
+        }
+    }
+}
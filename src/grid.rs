@@ -0,0 +1,106 @@
+use std::fmt::Write;
+
+use crate::buckets::range_equity;
+use crate::card::Card;
+use crate::cards::Cards;
+use crate::equity_session::EquitySession;
+use crate::hand::Hand;
+use crate::preflop::{
+    class_hands, class_index, class_name, class_representatives, PreflopTable, CLASS_COUNT,
+};
+use crate::range::RangeTable;
+use crate::rank::Rank;
+use crate::result::Result;
+use crate::suite::Suite;
+
+const RESET: &str = "\x1b[0m";
+const DIM: &str = "\x1b[2m";
+
+// The hand of a cell, rows and columns from ace to two,
+// suited above the diagonal, offsuit below.
+fn cell_hand(row: Rank, column: Rank) -> Hand {
+    let (high, low) = (row.max(column), row.min(column));
+    let low_suite = if column < row { Suite::Spades } else { Suite::Hearts };
+    Hand::of_two_cards(Card::of(high, Suite::Spades), Card::of(low, low_suite))
+}
+
+// Red at 0, yellow at 0.5 and green at 1.
+fn equity_color(equity: f64) -> (u8, u8, u8) {
+    let equity = equity.clamp(0.0, 1.0);
+    let red = if equity < 0.5 { 255.0 } else { 255.0 * (1.0 - equity) * 2.0 };
+    let green = if equity < 0.5 { 255.0 * equity * 2.0 } else { 255.0 };
+    (red as u8, green as u8, 0)
+}
+
+// The 13x13 grid of starting hand classes for a terminal. Classes outside the range are dimmed.
+// With equities (per preflop::class_index, as fractions) every class of the range
+// with an equity gets a background from red (0%) to green (100%).
+pub fn render_grid(range: &RangeTable, equities: Option<&[Option<f64>]>) -> String {
+    let mut grid = String::new();
+    for row in Rank::descending() {
+        for (index, column) in Rank::descending().enumerate() {
+            if index != 0 {
+                grid.push(' ');
+            }
+            let hand = cell_hand(row, column);
+            let name = class_name(hand);
+            let equity = equities.and_then(|equities| equities[class_index(hand)]);
+            match (range.contains(hand), equity) {
+                (false, _) => write!(grid, "{}{:<3}{}", DIM, name, RESET).unwrap(),
+                (true, None) => write!(grid, "{:<3}", name).unwrap(),
+                (true, Some(equity)) => {
+                    let (red, green, blue) = equity_color(equity);
+                    write!(
+                        grid,
+                        "\x1b[30;48;2;{};{};{}m{:<3}{}",
+                        red,
+                        green,
+                        blue,
+                        name,
+                        RESET,
+                    ).unwrap();
+                },
+            }
+        }
+        grid.push('\n');
+    }
+    grid
+}
+
+// Equity of every class of the hero range against the villain range, averaged over
+// the live combos of the class. Preflop from the shipped preflop table,
+// otherwise every combo is enumerated. None for classes outside the range
+// or without a possible matchup.
+pub fn class_equities(
+    community_cards: Cards,
+    hero_range: &RangeTable,
+    villain_range: &RangeTable,
+) -> Result<Vec<Option<f64>>> {
+    if !matches!(community_cards.count(), 0 | 3..=5) {
+        return Err("grid failed: expected 0 or 3 to 5 community cards".into());
+    }
+    let mut equities = vec![None; CLASS_COUNT];
+    let session = EquitySession::new(&[villain_range]);
+    for representative in class_representatives() {
+        if !hero_range.contains(representative) {
+            continue;
+        }
+        equities[class_index(representative)] = if community_cards == Cards::EMPTY {
+            range_equity(PreflopTable::active(), representative, villain_range)
+        } else {
+            let mut total = 0.0;
+            let mut count = 0u32;
+            for hand in class_hands(representative) {
+                if (hand.to_cards() & community_cards) != Cards::EMPTY {
+                    continue;
+                }
+                if let Some(result) = session.enumerate(community_cards, hand) {
+                    total += result.equities[0].equity_percent();
+                    count += 1;
+                }
+            }
+            (count != 0).then(|| total / f64::from(count))
+        };
+    }
+    Ok(equities)
+}
@@ -1,6 +1,6 @@
 use std::{cmp::Ordering, fmt};
 
-use crate::{card::Card, cards::Cards, result::Result};
+use crate::{card::Card, cards::Cards, error::Error, parse_error::{normalize_cards, ParseError}, result::Result};
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Hand(Card, Card);
@@ -17,31 +17,66 @@ impl fmt::Debug for Hand {
     }
 }
 
+impl std::str::FromStr for Hand {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
 impl Hand {
     pub const MIN: Self = Self(Card::MIN, Card::MIN);
 
+    pub const COUNT: usize = Card::COUNT * (Card::COUNT - 1) / 2;
+
+    // Only for two cards known to be different, see try_of_two_cards.
     pub fn of_two_cards(a: Card, b: Card) -> Self {
+        match Self::try_of_two_cards(a, b) {
+            Ok(hand) => hand,
+            Err(_) => panic!("hand of duplicate card {}", a),
+        }
+    }
+
+    pub fn try_of_two_cards(a: Card, b: Card) -> Result<Self> {
         match a.rank().cmp(&b.rank()) {
-            Ordering::Less => Self(b, a),
+            Ordering::Less => Ok(Self(b, a)),
             Ordering::Equal => match a.suite().to_usize().cmp(&b.suite().to_usize()) {
-                Ordering::Less => Self(b, a),
-                Ordering::Equal => unreachable!(),
-                Ordering::Greater => Self(a, b),
+                Ordering::Less => Ok(Self(b, a)),
+                Ordering::Equal => Err(Error::DuplicateCard(a)),
+                Ordering::Greater => Ok(Self(a, b)),
             },
-            Ordering::Greater => Self(a, b),
+            Ordering::Greater => Ok(Self(a, b)),
         }
     }
 
-    fn from_cards(cards: Cards) -> Result<Self> {
-        if cards.count() != 2 {
-            Err(format!("hand: expected 2 cards, got {}", cards.count()).into())
-        } else {
-            Ok(cards.to_hand().unwrap())
+    // Exactly two cards.
+    pub fn try_of_cards(cards: Cards) -> Result<Self> {
+        let mut iter = cards.iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(a), Some(b), None) => Self::try_of_two_cards(a, b),
+            _ => Err(format!("invalid hand: expected 2 cards, got {}", cards.count()).into()),
         }
     }
 
-    pub fn from_str(s: &str) -> Result<Self> {
-        Self::from_cards(Cards::from_str(s)?)
+    pub fn parse(s: &str) -> std::result::Result<Self, ParseError> {
+        Self::parse_hand(s).map_err(|err| err.suggest(normalize_cards(s), |suggestion| {
+            Self::parse_hand(suggestion).is_ok()
+        }))
+    }
+
+    fn parse_hand(s: &str) -> std::result::Result<Self, ParseError> {
+        let cards = Cards::parse(s).map_err(|err| err.within("hand", s, 0))?;
+        match cards.to_hand() {
+            Some(hand) => Ok(hand),
+            None => Err(ParseError::new(
+                "hand",
+                s,
+                0,
+                s,
+                format!("expected 2 cards, got {}", cards.count()),
+            )),
+        }
     }
 
     pub fn high(self) -> Card {
@@ -71,7 +106,28 @@ impl Hand {
         Cards::EMPTY.with(self.high()).with(self.low())
     }
 
+    // Dense, every hand has its own index from 0 to Hand::COUNT - 1,
+    // e.g. for arrays of Hand::COUNT. HandClass::index is the index of the class.
     pub fn to_index(self) -> usize {
-        self.high().to_usize() * self.low().to_usize()
+        let (a, b) = (self.high().to_dense_index(), self.low().to_dense_index());
+        let (a, b) = (a.max(b), a.min(b));
+        a * (a - 1) / 2 + b
+    }
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        if index >= Self::COUNT {
+            return None;
+        }
+        let mut a = 1;
+        while (a + 1) * a / 2 <= index {
+            a += 1;
+        }
+        let b = index - a * (a - 1) / 2;
+        Some(Self::of_two_cards(Card::from_dense_index(a)?, Card::from_dense_index(b)?))
+    }
+
+    // Every hand ordered by index, HandClass::all for the classes.
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..Self::COUNT).map(|index| Self::from_index(index).unwrap())
     }
 }
@@ -0,0 +1,169 @@
+use core::fmt;
+
+use crate::card::Card;
+use crate::hand::Hand;
+use crate::parse_error::ParseError;
+use crate::rank::Rank;
+use crate::result::Result;
+use crate::suite::Suite;
+
+// One of the 169 suit-agnostic starting hand classes: a pair, suited or offsuit.
+// Ordered by index: AA = 0, AKs = 1, AKo = 2, ..., 22 = 168.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HandClass {
+    high: Rank,
+    low: Rank,
+    suited: bool,
+}
+
+impl fmt::Display for HandClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.high, self.low)?;
+        if self.is_pair() {
+            Ok(())
+        } else if self.suited {
+            write!(f, "s")
+        } else {
+            write!(f, "o")
+        }
+    }
+}
+
+impl std::str::FromStr for HandClass {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl HandClass {
+    pub const COUNT: usize = 169;
+
+    pub fn pair(rank: Rank) -> Self {
+        Self { high: rank, low: rank, suited: false }
+    }
+
+    // The ranks in any order, pairs can't be suited.
+    pub fn try_new(a: Rank, b: Rank, suited: bool) -> Result<Self> {
+        if a == b && suited {
+            return Err(format!("invalid hand class: suited pair {}{}", a, b).into());
+        }
+        Ok(Self { high: a.max(b), low: a.min(b), suited })
+    }
+
+    pub fn of_hand(hand: Hand) -> Self {
+        Self {
+            high: hand.high().rank(),
+            low: hand.low().rank(),
+            suited: hand.suited(),
+        }
+    }
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        let mut before = 0;
+        for high in Rank::descending() {
+            let size = 2*high.to_usize() + 1;
+            if index < before + size {
+                let within = index - before;
+                let low = Rank::RANKS[high.to_usize() - within.div_ceil(2)];
+                return Some(Self { high, low, suited: within % 2 == 1 });
+            }
+            before += size;
+        }
+        None
+    }
+
+    // Every class ordered by index.
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..Self::COUNT).map(|index| Self::from_index(index).unwrap())
+    }
+
+    // E.g. "AA", "AKs" or "AKo".
+    pub fn parse(s: &str) -> std::result::Result<Self, ParseError> {
+        let err = |message: &str| ParseError::new("hand class", s, 0, s, message);
+        let (high, low, suited) = match s.as_bytes() {
+            [high, low] => (high, low, None),
+            [high, low, b's'] => (high, low, Some(true)),
+            [high, low, b'o'] => (high, low, Some(false)),
+            _ => return Err(err("expected two ranks and s or o, e.g. AKs")),
+        };
+        let (Ok(high), Ok(low)) = (Rank::from_ascii(*high), Rank::from_ascii(*low)) else {
+            return Err(err("invalid rank"));
+        };
+        match suited {
+            None if high == low => Ok(Self::pair(high)),
+            None => Err(err("expected s or o after unpaired ranks")),
+            Some(_) if high == low => Err(err("pairs can't be suited or offsuit")),
+            Some(_) if high < low => Err(err("expected the higher rank first")),
+            Some(suited) => Ok(Self { high, low, suited }),
+        }
+    }
+
+    pub fn high(self) -> Rank {
+        self.high
+    }
+
+    pub fn low(self) -> Rank {
+        self.low
+    }
+
+    pub fn suited(self) -> bool {
+        self.suited
+    }
+
+    pub fn is_pair(self) -> bool {
+        self.high == self.low
+    }
+
+    pub fn index(self) -> usize {
+        let high = self.high.to_usize();
+        let low = self.low.to_usize();
+        let before: usize = (high+1..Rank::COUNT).map(|rank| 2*rank + 1).sum();
+        let within = if high == low {
+            0
+        } else if self.suited {
+            2 * (high - low) - 1
+        } else {
+            2 * (high - low)
+        };
+        before + within
+    }
+
+    pub fn combos(self) -> u32 {
+        if self.is_pair() {
+            6
+        } else if self.suited {
+            4
+        } else {
+            12
+        }
+    }
+
+    pub fn contains(self, hand: Hand) -> bool {
+        Self::of_hand(hand) == self
+    }
+
+    // A fixed hand of the class, spades first.
+    pub fn representative(self) -> Hand {
+        let low_suite = if self.suited { Suite::Spades } else { Suite::Hearts };
+        Hand::of_two_cards(Card::of(self.high, Suite::Spades), Card::of(self.low, low_suite))
+    }
+
+    pub fn hands(self) -> Vec<Hand> {
+        let mut hands = Vec::with_capacity(self.combos() as usize);
+        for suite_a in Suite::SUITES {
+            for suite_b in Suite::SUITES {
+                if (suite_a == suite_b) != self.suited {
+                    continue;
+                }
+                if self.is_pair() && suite_a.to_usize() >= suite_b.to_usize() {
+                    continue;
+                }
+                hands.push(Hand::of_two_cards(Card::of(self.high, suite_a), Card::of(self.low, suite_b)));
+            }
+        }
+        debug_assert_eq!(hands.len() as u32, self.combos());
+        hands
+    }
+}
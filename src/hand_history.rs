@@ -0,0 +1,184 @@
+use std::io::Write;
+
+use rand::Rng;
+
+use crate::card::Card;
+use crate::cards::{Cards, HandRanking, Score};
+use crate::equity::ShowdownResult;
+use crate::error::Error;
+use crate::hand::Hand;
+use crate::range::HandRange;
+use crate::result::Result;
+
+// Attempts to deal villain hands without conflicts before a runout is given up.
+const MAX_DEAL_ATTEMPTS: u32 = 1000;
+
+// A sampled runout: the complete board in dealing order,
+// the hero hand and one hand of every villain.
+#[derive(Debug, Clone)]
+pub struct Runout {
+    pub board: Vec<Card>,
+    pub hero_hand: Hand,
+    pub villain_hands: Vec<Hand>,
+}
+
+impl Runout {
+    pub fn hands(&self) -> impl Iterator<Item = Hand> + '_ {
+        std::iter::once(self.hero_hand).chain(self.villain_hands.iter().copied())
+    }
+
+    pub fn scores(&self) -> Vec<Score> {
+        let board = Cards::from_slice(&self.board).unwrap();
+        self.hands().map(|hand| (board | hand.to_cards()).score_fast()).collect()
+    }
+}
+
+// Villain hands are drawn uniformly from their ranges,
+// the missing community cards uniformly from the remaining deck.
+pub fn sample_runouts(
+    community_cards: Cards,
+    hero_hand: Hand,
+    villain_ranges: &[impl HandRange],
+    rounds: u64,
+    rng: &mut impl Rng,
+) -> Result<Vec<Runout>> {
+    if community_cards.count() > 5 {
+        return Err(Error::usage("sample failed: expected at most 5 community cards"));
+    }
+    if let Some(card) = (community_cards & hero_hand.to_cards()).first() {
+        return Err(Error::DuplicateCard(card));
+    }
+    let known_cards = community_cards | hero_hand.to_cards();
+    let ranges: Vec<Vec<Hand>> = villain_ranges.iter()
+        .map(|range| {
+            let mut hands = Vec::new();
+            range.for_each_hand(|hand| if (hand.to_cards() & known_cards) == Cards::EMPTY {
+                hands.push(hand);
+            });
+            hands
+        })
+        .collect();
+    if ranges.is_empty() || ranges.iter().any(|hands| hands.is_empty()) {
+        return Err("sample failed: expected villain ranges with live combos".into());
+    }
+
+    let mut runouts = Vec::new();
+    for _ in 0..rounds {
+        let mut attempts = 0;
+        let (villain_hands, mut dealt_cards) = 'deal: loop {
+            attempts += 1;
+            if attempts > MAX_DEAL_ATTEMPTS {
+                return Err("sample failed: villain ranges conflict too often".into());
+            }
+            let mut dealt_cards = known_cards;
+            let mut villain_hands = Vec::with_capacity(ranges.len());
+            for hands in &ranges {
+                let hand = hands[rng.gen_range(0..hands.len())];
+                if (hand.to_cards() & dealt_cards) != Cards::EMPTY {
+                    continue 'deal;
+                }
+                dealt_cards |= hand.to_cards();
+                villain_hands.push(hand);
+            }
+            break (villain_hands, dealt_cards);
+        };
+        let mut board: Vec<_> = community_cards.iter().collect();
+        while board.len() < 5 {
+            let card: Card = rng.r#gen();
+            if dealt_cards.try_add(card) {
+                board.push(card);
+            }
+        }
+        runouts.push(Runout { board, hero_hand, villain_hands });
+    }
+    Ok(runouts)
+}
+
+// Writes the runouts as hand histories in the common PokerStars text format,
+// without betting: every player is all-in with the given stack before any card is dealt.
+pub fn write_hand_histories(out: &mut dyn Write, runouts: &[Runout], stack: u64) -> Result<()> {
+    for (index, runout) in runouts.iter().enumerate() {
+        write_hand_history(out, index as u64 + 1, runout, stack)?;
+    }
+    Ok(())
+}
+
+fn player_name(index: usize) -> String {
+    if index == 0 {
+        "Hero".to_string()
+    } else {
+        format!("Villain {}", index)
+    }
+}
+
+fn cards_string(cards: &[Card]) -> String {
+    cards.iter().map(|card| card.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+fn hand_string(hand: Hand) -> String {
+    format!("{} {}", hand.high(), hand.low())
+}
+
+fn ranking_name(score: Score) -> String {
+    let ranking = score.to_hand_ranking();
+    HandRanking::CATEGORY_NAMES[ranking.category()].replace('-', " ")
+}
+
+fn write_hand_history(out: &mut dyn Write, number: u64, runout: &Runout, stack: u64) -> Result<()> {
+    let players = runout.villain_hands.len() + 1;
+    let pot = stack * players as u64;
+    let scores = runout.scores();
+    let result = ShowdownResult::new(&scores).unwrap();
+    let awards = result.award(pot, players);
+    let board = &runout.board;
+
+    writeln!(out, "PokerStars Hand #{}: Hold'em No Limit (0/0) - 1970/01/01 00:00:00 ET", number)?;
+    writeln!(out, "Table 'poker-equity' {}-max Seat #1 is the button", players.max(2))?;
+    for index in 0..players {
+        writeln!(out, "Seat {}: {} ({} in chips)", index + 1, player_name(index), stack)?;
+    }
+    writeln!(out, "*** HOLE CARDS ***")?;
+    writeln!(out, "Dealt to Hero [{}]", hand_string(runout.hero_hand))?;
+    for index in 0..players {
+        writeln!(out, "{}: is all-in", player_name(index))?;
+    }
+    writeln!(out, "*** FLOP *** [{}]", cards_string(&board[..3]))?;
+    writeln!(out, "*** TURN *** [{}] [{}]", cards_string(&board[..3]), board[3])?;
+    writeln!(out, "*** RIVER *** [{}] [{}]", cards_string(&board[..4]), board[4])?;
+    writeln!(out, "*** SHOW DOWN ***")?;
+    for (index, (hand, score)) in runout.hands().zip(scores.iter()).enumerate() {
+        let name = player_name(index);
+        writeln!(out, "{}: shows [{}] ({})", name, hand_string(hand), ranking_name(*score))?;
+    }
+    for winner in &result.winners {
+        writeln!(out, "{} collected {} from pot", player_name(*winner), awards[*winner])?;
+    }
+    writeln!(out, "*** SUMMARY ***")?;
+    writeln!(out, "Total pot {} | Rake 0", pot)?;
+    writeln!(out, "Board [{}]", cards_string(board))?;
+    for (index, (hand, score)) in runout.hands().zip(scores.iter()).enumerate() {
+        if result.is_winner(index) {
+            writeln!(
+                out,
+                "Seat {}: {} showed [{}] and won ({}) with {}",
+                index + 1,
+                player_name(index),
+                hand_string(hand),
+                awards[index],
+                ranking_name(*score),
+            )?;
+        } else {
+            writeln!(
+                out,
+                "Seat {}: {} showed [{}] and lost with {}",
+                index + 1,
+                player_name(index),
+                hand_string(hand),
+                ranking_name(*score),
+            )?;
+        }
+    }
+    writeln!(out)?;
+    writeln!(out)?;
+    Ok(())
+}
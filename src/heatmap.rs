@@ -0,0 +1,173 @@
+use std::cmp::Ordering;
+use std::fmt::Write;
+
+use crate::cards::{Cards, Score};
+use crate::equity::{live_hands, remaining_boards, score_hands};
+use crate::hand::Hand;
+use crate::preflop::{class_index, class_name, class_representatives, PreflopTable, CLASS_COUNT};
+use crate::range::HandRange;
+use crate::result::Result;
+
+// Equities of every hero starting hand class against every villain class,
+// restricted to the combos of the two ranges.
+// Preflop the shipped preflop table is used, with community cards
+// every combo matchup is enumerated exactly and aggregated per class.
+pub struct EquityHeatmap {
+    cells: Vec<Option<f64>>,
+}
+
+struct Scored {
+    cards: Cards,
+    score: Score,
+    class: usize,
+}
+
+impl EquityHeatmap {
+    pub fn new(
+        community_cards: Cards,
+        hero_range: &impl HandRange,
+        villain_range: &impl HandRange,
+    ) -> Result<Self> {
+        match community_cards.count() {
+            0 => Ok(Self::preflop(hero_range, villain_range)),
+            3..=5 => Self::enumerate(community_cards, hero_range, villain_range),
+            _ => Err("heatmap failed: expected 0 or 3 to 5 community cards".into()),
+        }
+    }
+
+    fn preflop(hero_range: &impl HandRange, villain_range: &impl HandRange) -> Self {
+        let hero_classes = classes(hero_range);
+        let villain_classes = classes(villain_range);
+        let table = PreflopTable::active();
+        let mut cells = vec![None; CLASS_COUNT * CLASS_COUNT];
+        for hero in class_representatives() {
+            for villain in class_representatives() {
+                let (hero_class, villain_class) = (class_index(hero), class_index(villain));
+                if hero_classes[hero_class] && villain_classes[villain_class] {
+                    cells[hero_class*CLASS_COUNT + villain_class] = Some(table.heads_up(hero, villain));
+                }
+            }
+        }
+        Self { cells }
+    }
+
+    fn enumerate(
+        community_cards: Cards,
+        hero_range: &impl HandRange,
+        villain_range: &impl HandRange,
+    ) -> Result<Self> {
+        let hero_hands = live_hands(community_cards, hero_range);
+        let villain_hands = live_hands(community_cards, villain_range);
+        // Pot shares in halves, so ties stay integers.
+        let mut shares = vec![0u64; CLASS_COUNT * CLASS_COUNT];
+        let mut totals = vec![0u64; CLASS_COUNT * CLASS_COUNT];
+        for board in remaining_boards(community_cards, Cards::EMPTY) {
+            let heroes = scored(board, &hero_hands);
+            let villains = scored(board, &villain_hands);
+            for hero in &heroes {
+                for villain in &villains {
+                    if (hero.cards & villain.cards) != Cards::EMPTY {
+                        continue;
+                    }
+                    let index = hero.class*CLASS_COUNT + villain.class;
+                    totals[index] += 2;
+                    shares[index] += match hero.score.cmp(&villain.score) {
+                        Ordering::Greater => 2,
+                        Ordering::Equal => 1,
+                        Ordering::Less => 0,
+                    };
+                }
+            }
+        }
+        if totals.iter().all(|total| *total == 0) {
+            return Err("heatmap failed: no valid matchups".into());
+        }
+        let cells = shares.iter()
+            .zip(totals.iter())
+            .map(|(share, total)| (*total != 0).then(|| *share as f64 / *total as f64))
+            .collect();
+        Ok(Self { cells })
+    }
+
+    pub fn equity(&self, hero: Hand, villain: Hand) -> Option<f64> {
+        self.cells[class_index(hero)*CLASS_COUNT + class_index(villain)]
+    }
+
+    // Hero classes as rows, villain classes as columns, equities in percent.
+    // Only classes with at least one matchup are included.
+    pub fn to_csv(&self) -> String {
+        let (rows, columns) = self.used_classes();
+        let mut csv = String::from("hero");
+        for column in &columns {
+            write!(csv, ",{}", class_name(*column)).unwrap();
+        }
+        csv.push('\n');
+        for row in &rows {
+            csv.push_str(&class_name(*row));
+            for column in &columns {
+                csv.push(',');
+                if let Some(equity) = self.equity(*row, *column) {
+                    write!(csv, "{:.2}", equity * 100.0).unwrap();
+                }
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    // {"hero": [...], "villain": [...], "equities": [[...], ...]},
+    // missing matchups are null.
+    pub fn to_json(&self) -> String {
+        let (rows, columns) = self.used_classes();
+        let names = |hands: &[Hand]| hands.iter()
+            .map(|hand| format!("\"{}\"", class_name(*hand)))
+            .collect::<Vec<_>>()
+            .join(",");
+        let equities = rows.iter()
+            .map(|row| {
+                let row = columns.iter()
+                    .map(|column| match self.equity(*row, *column) {
+                        Some(equity) => format!("{:.2}", equity * 100.0),
+                        None => "null".to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!("[{}]", row)
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "{{\"hero\":[{}],\"villain\":[{}],\"equities\":[{}]}}\n",
+            names(&rows),
+            names(&columns),
+            equities,
+        )
+    }
+
+    fn used_classes(&self) -> (Vec<Hand>, Vec<Hand>) {
+        let representatives: Vec<_> = class_representatives().collect();
+        let used = |f: &dyn Fn(usize) -> bool| representatives.iter()
+            .copied()
+            .filter(|hand| f(class_index(*hand)))
+            .collect();
+        let rows = used(&|row| (0..CLASS_COUNT).any(|column| {
+            self.cells[row*CLASS_COUNT + column].is_some()
+        }));
+        let columns = used(&|column| (0..CLASS_COUNT).any(|row| {
+            self.cells[row*CLASS_COUNT + column].is_some()
+        }));
+        (rows, columns)
+    }
+}
+
+fn classes(range: &impl HandRange) -> Vec<bool> {
+    let mut classes = vec![false; CLASS_COUNT];
+    range.for_each_hand(|hand| classes[class_index(hand)] = true);
+    classes
+}
+
+fn scored(board: Cards, hands: &[Hand]) -> Vec<Scored> {
+    score_hands(board, hands)
+        .map(|(hand, score)| Scored { cards: hand.to_cards(), score, class: class_index(hand) })
+        .collect()
+}
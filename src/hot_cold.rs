@@ -0,0 +1,101 @@
+use core::fmt;
+
+use crate::card::Card;
+use crate::cards::Cards;
+use crate::equity::enumeration_error;
+use crate::equity_session::EquitySession;
+use crate::hand::Hand;
+use crate::range::HandRange;
+use crate::result::Result;
+
+// Hero equity after the next card, as a fraction.
+#[derive(Debug, Clone, Copy)]
+pub struct CardEquity {
+    pub card: Card,
+    pub equity: f64,
+}
+
+// The next cards grouped by how much they change hero's equity:
+// good raises it by at least the threshold, bad lowers it by at least the threshold.
+// Every group is ordered by equity, best first.
+#[derive(Debug, Clone)]
+pub struct HotCold {
+    pub start_equity: f64,
+    pub threshold: f64,
+    pub good: Vec<CardEquity>,
+    pub neutral: Vec<CardEquity>,
+    pub bad: Vec<CardEquity>,
+}
+
+impl fmt::Display for HotCold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "equity: {:2.2}", self.start_equity * 100.0)?;
+        let groups = [("good", &self.good), ("neutral", &self.neutral), ("bad", &self.bad)];
+        for (index, (name, cards)) in groups.into_iter().enumerate() {
+            write!(f, "{} ({}):", name, cards.len())?;
+            for card in cards {
+                write!(f, " {}={:2.2}", card.card, card.equity * 100.0)?;
+            }
+            if index != groups.len() - 1 {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Exact equity for every card that can come next on the flop or turn.
+// Cards after which no villain combo is left are skipped.
+pub fn next_card_equities(
+    community_cards: Cards,
+    hero_hand: Hand,
+    villain_ranges: &[impl HandRange],
+) -> Result<(f64, Vec<CardEquity>)> {
+    if !(3..=4).contains(&community_cards.count()) {
+        return Err("hot cold failed: expected 3 or 4 community cards".into());
+    }
+    let session = EquitySession::new(villain_ranges);
+    let equity = |board: Cards| session.enumerate(board, hero_hand)
+        .map(|result| result.equities[0].equity_percent());
+    let Some(start_equity) = equity(community_cards) else {
+        return Err(enumeration_error("hot cold", community_cards, hero_hand, villain_ranges));
+    };
+    let cards = (!(community_cards | hero_hand.to_cards())).iter()
+        .filter_map(|card| {
+            equity(community_cards.with(card)).map(|equity| CardEquity { card, equity })
+        })
+        .collect();
+    Ok((start_equity, cards))
+}
+
+// Threshold is the equity change as a fraction, e.g. 0.1 for 10 percentage points.
+pub fn hot_cold(
+    community_cards: Cards,
+    hero_hand: Hand,
+    villain_ranges: &[impl HandRange],
+    threshold: f64,
+) -> Result<HotCold> {
+    if !(threshold.is_finite() && threshold >= 0.0) {
+        return Err("hot cold failed: expected a non-negative threshold".into());
+    }
+    let (start_equity, mut cards) = next_card_equities(community_cards, hero_hand, villain_ranges)?;
+    cards.sort_by(|a, b| b.equity.total_cmp(&a.equity));
+    let mut hot_cold = HotCold {
+        start_equity,
+        threshold,
+        good: Vec::new(),
+        neutral: Vec::new(),
+        bad: Vec::new(),
+    };
+    for card in cards {
+        let change = card.equity - start_equity;
+        if change >= threshold && change > 0.0 {
+            hot_cold.good.push(card);
+        } else if change <= -threshold && change < 0.0 {
+            hot_cold.bad.push(card);
+        } else {
+            hot_cold.neutral.push(card);
+        }
+    }
+    Ok(hot_cold)
+}
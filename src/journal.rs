@@ -0,0 +1,180 @@
+use core::fmt;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::daemon::{check_schema_version, json_string, result_json};
+use crate::equity::EquityResult;
+use crate::error::Error;
+use crate::json::JsonValue;
+use crate::labels::PlayerLabels;
+use crate::result::Result;
+
+// Append-only journal of computed spots, one JSON object per line, e.g.
+// {"time":1760000000,"command":"enumerate","inputs":["AsTd3h","AhTh","QQ+"],"method":"enumerate","result":{...}},
+// where the result is the same as a daemon result.
+// Lines are only ever appended, so the journal is an audit trail of every quoted number.
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    // Removes --journal <path> from the arguments.
+    pub fn from_args(args: &mut Vec<String>) -> Result<Option<Self>> {
+        let Some(index) = args.iter().position(|arg| arg == "--journal") else {
+            return Ok(None);
+        };
+        if index + 1 >= args.len() {
+            return Err(Error::usage("expected a path after --journal"));
+        }
+        let path = args.remove(index + 1);
+        args.remove(index);
+        Ok(Some(Self::new(path)))
+    }
+
+    pub fn record(
+        &self,
+        command: &str,
+        inputs: &[String],
+        method: &str,
+        result: &EquityResult,
+        labels: &PlayerLabels,
+    ) -> Result<()> {
+        let time = SystemTime::now().duration_since(UNIX_EPOCH)
+            .map_err(|err| format!("journal failed: {}", err))?
+            .as_secs();
+        let inputs = inputs.iter()
+            .map(|input| json_string(input))
+            .collect::<Vec<_>>()
+            .join(",");
+        let line = format!(
+            "{{\"time\":{},\"command\":{},\"inputs\":[{}],\"method\":{},\"result\":{}}}\n",
+            time,
+            json_string(command),
+            inputs,
+            json_string(method),
+            result_json(result, labels)?,
+        );
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        // A single write, so concurrent processes don't interleave lines.
+        file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn entries(&self) -> Result<Vec<JournalEntry>> {
+        let file = std::fs::File::open(&self.path)
+            .map_err(|err| format!("invalid journal {}: {}", self.path.display(), err))?;
+        let mut entries = Vec::new();
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = JournalEntry::parse(&line).map_err(|err| {
+                format!("invalid journal {}: line {}: {}", self.path.display(), index + 1, err)
+            })?;
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct JournalEquity {
+    pub player: String,
+    pub equity: f64,
+    pub win: f64,
+    pub tie: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct JournalEntry {
+    // Seconds since the unix epoch.
+    pub time: u64,
+    pub command: String,
+    pub inputs: Vec<String>,
+    pub method: String,
+    // In percent.
+    pub equities: Vec<JournalEquity>,
+}
+
+impl fmt::Display for JournalEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", format_utc(self.time), self.command)?;
+        for input in &self.inputs {
+            write!(f, " {}", input)?;
+        }
+        write!(f, " ({}):", self.method)?;
+        for equity in &self.equities {
+            write!(f, " {}={:.2}", equity.player, equity.equity)?;
+        }
+        Ok(())
+    }
+}
+
+impl JournalEntry {
+    pub fn parse(line: &str) -> Result<Self> {
+        let value = JsonValue::parse(line)?;
+        let result = value.get("result")?;
+        check_schema_version(result)?;
+        let equities = result.get("equities")?.as_array()?
+            .iter()
+            .map(|equity| Ok(JournalEquity {
+                player: equity.get("player")?.as_str()?.to_string(),
+                equity: equity.get("equity")?.as_f64()?,
+                win: equity.get("win")?.as_f64()?,
+                tie: equity.get("tie")?.as_f64()?,
+            }))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            time: value.get("time")?.as_f64()? as u64,
+            command: value.get("command")?.as_str()?.to_string(),
+            inputs: value.get("inputs")?.as_array()?
+                .iter()
+                .map(|input| input.as_str().map(str::to_string))
+                .collect::<Result<Vec<_>>>()?,
+            method: value.get("method")?.as_str()?.to_string(),
+            equities,
+        })
+    }
+
+    // Every term has to be the command, the method, a player or part of an input,
+    // e.g. "enumerate AhTh" finds the enumerations with hero holding AhTh.
+    pub fn matches(&self, terms: &[String]) -> bool {
+        terms.iter().all(|term| {
+            self.command == *term
+                || self.method == *term
+                || self.equities.iter().any(|equity| equity.player == *term)
+                || self.inputs.iter().any(|input| input.contains(term.as_str()))
+        })
+    }
+}
+
+// E.g. 2026-10-16 12:30:00 UTC.
+fn format_utc(time: u64) -> String {
+    let (days, seconds) = (time / 86400, time % 86400);
+    // Civil from days, see https://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z / 146097;
+    let day_of_era = z - era * 146097;
+    let year_of_era = (day_of_era - day_of_era/1460 + day_of_era/36524 - day_of_era/146096) / 365;
+    let day_of_year = day_of_era - (365*year_of_era + year_of_era/4 - year_of_era/100);
+    let mp = (5*day_of_year + 2) / 153;
+    let day = day_of_year - (153*mp + 2)/5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+    )
+}
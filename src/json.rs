@@ -0,0 +1,210 @@
+use crate::error::Error;
+use crate::result::Result;
+
+// Just enough JSON to read back what this crate writes (journal, results).
+#[derive(Debug, Clone)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    pub fn parse(s: &str) -> Result<Self> {
+        JsonParser { input: s.as_bytes(), offset: 0 }.parse_document()
+    }
+
+    pub fn get(&self, key: &str) -> Result<&JsonValue> {
+        let JsonValue::Object(fields) = self else {
+            return Err(format!("expected an object with '{}'", key).into());
+        };
+        fields.iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value)
+            .ok_or_else(|| format!("missing field '{}'", key).into())
+    }
+
+    pub fn as_str(&self) -> Result<&str> {
+        match self {
+            JsonValue::String(s) => Ok(s),
+            _ => Err("expected a string".into()),
+        }
+    }
+
+    pub fn as_f64(&self) -> Result<f64> {
+        match self {
+            JsonValue::Number(n) => Ok(*n),
+            _ => Err("expected a number".into()),
+        }
+    }
+
+    pub fn as_array(&self) -> Result<&[JsonValue]> {
+        match self {
+            JsonValue::Array(values) => Ok(values),
+            _ => Err("expected an array".into()),
+        }
+    }
+}
+
+struct JsonParser<'a> {
+    input: &'a [u8],
+    offset: usize,
+}
+
+impl JsonParser<'_> {
+    fn parse_document(mut self) -> Result<JsonValue> {
+        let value = self.parse_value()?;
+        self.skip_whitespace();
+        if self.offset != self.input.len() {
+            return Err(self.error("trailing characters"));
+        }
+        Ok(value)
+    }
+
+    fn error(&self, message: &str) -> Error {
+        format!("invalid json: {} at offset {}", message, self.offset).into()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.input.get(self.offset).is_some_and(|b| b.is_ascii_whitespace()) {
+            self.offset += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<()> {
+        self.skip_whitespace();
+        if self.input.get(self.offset) == Some(&expected) {
+            self.offset += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", char::from(expected))))
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue> {
+        self.skip_whitespace();
+        match self.input.get(self.offset) {
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_array(),
+            Some(b'"') => Ok(JsonValue::String(self.parse_string()?)),
+            Some(b't') => self.parse_literal("true", JsonValue::Bool(true)),
+            Some(b'f') => self.parse_literal("false", JsonValue::Bool(false)),
+            Some(b'n') => self.parse_literal("null", JsonValue::Null),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: JsonValue) -> Result<JsonValue> {
+        if self.input[self.offset..].starts_with(literal.as_bytes()) {
+            self.offset += literal.len();
+            Ok(value)
+        } else {
+            Err(self.error("invalid literal"))
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<JsonValue> {
+        let start = self.offset;
+        while self.input.get(self.offset)
+            .is_some_and(|b| b.is_ascii_digit() || matches!(b, b'-' | b'+' | b'.' | b'e' | b'E'))
+        {
+            self.offset += 1;
+        }
+        let raw = std::str::from_utf8(&self.input[start..self.offset])
+            .map_err(|_| self.error("invalid number"))?;
+        raw.parse().map(JsonValue::Number).map_err(|_| self.error("invalid number"))
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+        loop {
+            let Some(b) = self.input.get(self.offset).copied() else {
+                return Err(self.error("unterminated string"));
+            };
+            self.offset += 1;
+            match b {
+                b'"' => break,
+                b'\\' => {
+                    let Some(escaped) = self.input.get(self.offset).copied() else {
+                        return Err(self.error("unterminated escape"));
+                    };
+                    self.offset += 1;
+                    let unescaped = match escaped {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'n' => '\n',
+                        b't' => '\t',
+                        b'r' => '\r',
+                        b'u' => {
+                            let raw = self.input.get(self.offset..self.offset + 4)
+                                .ok_or_else(|| self.error("invalid unicode escape"))?;
+                            self.offset += 4;
+                            std::str::from_utf8(raw).ok()
+                                .and_then(|raw| u32::from_str_radix(raw, 16).ok())
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.error("invalid unicode escape"))?
+                        },
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(unescaped.encode_utf8(&mut buffer).as_bytes());
+                },
+                b => bytes.push(b),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| self.error("invalid utf-8"))
+    }
+
+    fn parse_array(&mut self) -> Result<JsonValue> {
+        self.expect(b'[')?;
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if self.input.get(self.offset) == Some(&b']') {
+            self.offset += 1;
+            return Ok(JsonValue::Array(values));
+        }
+        loop {
+            values.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.input.get(self.offset) {
+                Some(b',') => self.offset += 1,
+                Some(b']') => {
+                    self.offset += 1;
+                    return Ok(JsonValue::Array(values));
+                },
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<JsonValue> {
+        self.expect(b'{')?;
+        let mut fields = Vec::new();
+        self.skip_whitespace();
+        if self.input.get(self.offset) == Some(&b'}') {
+            self.offset += 1;
+            return Ok(JsonValue::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.expect(b':')?;
+            fields.push((key, self.parse_value()?));
+            self.skip_whitespace();
+            match self.input.get(self.offset) {
+                Some(b',') => self.offset += 1,
+                Some(b'}') => {
+                    self.offset += 1;
+                    return Ok(JsonValue::Object(fields));
+                },
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+}
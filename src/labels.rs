@@ -0,0 +1,85 @@
+use crate::error::Error;
+use crate::result::Result;
+
+// Names of the players, hero first, and the order they are reported in,
+// e.g. --players hero,BTN,BB --order BB,BTN.
+// Without names the players are hero, villain 1, villain 2, ...
+#[derive(Debug, Clone, Default)]
+pub struct PlayerLabels {
+    names: Vec<String>,
+    order: Vec<String>,
+}
+
+impl PlayerLabels {
+    // Removes --players <names> and --order <names> (comma separated) from the arguments.
+    pub fn from_args(args: &mut Vec<String>) -> Result<Self> {
+        let names = take_option(args, "--players")?.unwrap_or_default();
+        let order = take_option(args, "--order")?.unwrap_or_default();
+        for (index, name) in names.iter().enumerate() {
+            if names[..index].contains(name) {
+                return Err(Error::usage(format!("invalid players: duplicate name '{}'", name)));
+            }
+        }
+        for (index, name) in order.iter().enumerate() {
+            if order[..index].contains(name) {
+                return Err(Error::usage(format!("invalid order: duplicate name '{}'", name)));
+            }
+        }
+        Ok(Self { names, order })
+    }
+
+    pub fn label(&self, player: usize) -> String {
+        match self.names.get(player) {
+            Some(name) => name.clone(),
+            None if player == 0 => "hero".to_string(),
+            None => format!("villain {}", player),
+        }
+    }
+
+    // Player indices in report order: the players listed by --order first,
+    // then the others in input order.
+    pub fn order(&self, player_count: usize) -> Result<Vec<usize>> {
+        if !self.names.is_empty() && self.names.len() != player_count {
+            return Err(Error::usage(format!(
+                "invalid players: expected {} names, got {}",
+                player_count,
+                self.names.len(),
+            )));
+        }
+        let labels: Vec<_> = (0..player_count).map(|player| self.label(player)).collect();
+        let mut order = Vec::with_capacity(player_count);
+        for name in &self.order {
+            let Some(player) = labels.iter().position(|label| label == name) else {
+                return Err(Error::usage(format!("invalid order: unknown player '{}'", name)));
+            };
+            order.push(player);
+        }
+        let rest: Vec<_> = (0..player_count).filter(|player| !order.contains(player)).collect();
+        order.extend(rest);
+        Ok(order)
+    }
+
+    // The items of every player with its label, in report order.
+    pub fn labeled<'a, T>(&self, items: &'a [T]) -> Result<Vec<(String, &'a T)>> {
+        Ok(self.order(items.len())?
+            .into_iter()
+            .map(|player| (self.label(player), &items[player]))
+            .collect())
+    }
+}
+
+fn take_option(args: &mut Vec<String>, option: &str) -> Result<Option<Vec<String>>> {
+    let Some(index) = args.iter().position(|arg| arg == option) else {
+        return Ok(None);
+    };
+    if index + 1 >= args.len() {
+        return Err(Error::usage(format!("expected names after {}", option)));
+    }
+    let raw = args.remove(index + 1);
+    args.remove(index);
+    let names: Vec<_> = raw.split(',').map(str::to_string).collect();
+    if names.iter().any(|name| name.is_empty()) {
+        return Err(Error::usage(format!("invalid {} '{}': expected comma separated names", option, raw)));
+    }
+    Ok(Some(names))
+}
@@ -0,0 +1,66 @@
+use core::fmt;
+
+use crate::cards::{Cards, HandRanking};
+use crate::equity::boards_of_size;
+use crate::error::Error;
+use crate::hand::Hand;
+use crate::result::Result;
+
+// How often hero ends up with every hand category by the river,
+// counted over every runout of the community cards (villain cards unknown).
+#[derive(Debug, Clone, Copy)]
+pub struct MadeHandOdds {
+    pub boards: u64,
+    // Indexed by HandRanking::category.
+    pub by_category: [u64; HandRanking::CATEGORY_COUNT],
+}
+
+impl fmt::Display for MadeHandOdds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (category, name) in HandRanking::CATEGORY_NAMES.iter().enumerate() {
+            if category != 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "{}: exactly={:2.2} or-better={:2.2}",
+                name,
+                self.exactly(category) * 100.0,
+                self.at_least(category) * 100.0,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl MadeHandOdds {
+    // Probability of the category, as a fraction.
+    pub fn exactly(&self, category: usize) -> f64 {
+        self.by_category[category] as f64 / self.boards as f64
+    }
+
+    // Probability of the category or any higher one, e.g. two pair or better.
+    pub fn at_least(&self, category: usize) -> f64 {
+        let count: u64 = self.by_category[category..].iter().sum();
+        count as f64 / self.boards as f64
+    }
+}
+
+// Enumerates every river from 0 to 5 community cards,
+// preflop these are about 2.1 million boards.
+pub fn made_hand_odds(community_cards: Cards, hero_hand: Hand) -> Result<MadeHandOdds> {
+    let hero_cards = hero_hand.to_cards();
+    if community_cards.count() > 5 {
+        return Err(Error::usage("made hands failed: expected at most 5 community cards"));
+    }
+    if let Some(card) = (community_cards & hero_cards).first() {
+        return Err(Error::DuplicateCard(card));
+    }
+    let mut odds = MadeHandOdds { boards: 0, by_category: [0; HandRanking::CATEGORY_COUNT] };
+    for board in boards_of_size(community_cards, hero_cards, 5) {
+        let category = (board | hero_cards).score_fast().to_hand_ranking().category();
+        odds.by_category[category] += 1;
+        odds.boards += 1;
+    }
+    Ok(odds)
+}
@@ -1,78 +1,1712 @@
 #![allow(dead_code)] // TODO
 
+mod bankroll;
+mod bluff_catch;
+mod board_odds;
+mod buckets;
+mod cache;
+mod canonical;
 mod card;
 mod cards;
+mod cli_error;
+mod cooler;
+mod daemon;
+mod dealer;
+mod dirs;
+mod draw;
+mod duplicate;
 mod equity;
+mod equity_curve;
+mod equity_request;
+mod equity_session;
+mod equivalence_class;
+mod error;
+mod evaluator;
+mod flop_metrics;
+mod game_rules;
+mod grid;
 mod hand;
+mod hand_class;
+mod hand_history;
+mod heatmap;
+mod hot_cold;
+mod journal;
+mod json;
+mod labels;
+mod made_hands;
+mod memory;
+mod mixture;
+mod ofc;
+mod omaha;
+mod parse_error;
+mod preflop;
+mod presets;
+mod push_fold;
 mod range;
+mod range_comparison;
+mod range_distribution;
+mod range_registry;
+mod range_simulator;
 mod rank;
+mod recommend;
 mod result;
+mod scenarios;
+mod sensitivity;
+#[cfg(feature = "serde")]
+mod serde_support;
+mod shove;
+mod showdown_read;
+mod solver_csv;
+mod stud;
 mod suite;
+#[cfg(feature = "svg")]
+mod svg;
+mod swing;
+mod task;
+mod verify;
+mod weighted_range;
+mod widening;
 
-use std::sync::Arc;
+use std::fmt;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
-use crate::equity::Equity;
-use crate::cards::Cards;
-use crate::range::RangeTable;
+use rand::{rngs::SmallRng, SeedableRng};
+
+use crate::equity::{Checkpoint, Enumeration, EnumerationPlan, Equity, EquityResult};
+use crate::equity_curve::equity_curve;
+use crate::flop_metrics::{
+    for_each_flop_metrics, to_combo_csv_row, to_csv_row, COMBO_CSV_HEADER, CSV_HEADER,
+};
+use crate::game_rules::{parse_game_rules, rules_enumerate, rules_simulate};
+use crate::bankroll::Bankroll;
+use crate::bluff_catch::BluffCatch;
+use crate::board_odds::board_odds;
+use crate::buckets::{BucketField, PreflopBuckets};
+use crate::canonical::{canonical_extensions, canonical_flops, CanonicalBoard};
+use crate::cli_error::{error_json, ErrorKind};
+use crate::error::Error;
+use crate::evaluator::{FastEvaluator, NaiveEvaluator};
+use crate::cards::{Cards, HandRanking, ScoreBackend};
+use crate::cooler::CoolerStats;
+use crate::daemon::{result_json, simulation_json, Daemon};
+use crate::dealer::RangePlayer;
+use crate::draw::{draw_simulate, DrawPlayer};
+use crate::duplicate::duplicate;
+use crate::range::{split_exposed_card, HandRange, Opponent, RangeTable};
+use crate::range_comparison::{compare_ranges, compare_runs, RunSummary};
+use crate::range_distribution::range_distribution;
+use crate::range_simulator::{RangeSimulator, SamplingStrategy};
+use crate::recommend::{recommend, Throughput};
 use crate::result::Result;
+use crate::scenarios::{reference_scenarios, shipped_scenarios};
+use crate::sensitivity::Sensitivity;
+use crate::showdown_read::{read_showdown, SessionReads};
 use crate::hand::Hand;
+use crate::hand_history::{sample_runouts, write_hand_histories};
+use crate::grid::{class_equities, render_grid};
+use crate::heatmap::EquityHeatmap;
+use crate::hot_cold::hot_cold;
+use crate::journal::Journal;
+use crate::labels::PlayerLabels;
+use crate::made_hands::made_hand_odds;
+use crate::memory::MemoryReport;
+use crate::mixture::{mixture_enumerate, mixture_enumeration_error, mixture_simulate, VillainMixture};
+use crate::ofc::{fantasyland_probability, head_to_head, OfcHand};
+use crate::omaha::{omaha_enumerate_boards, omaha_simulate_boards, OmahaHand};
+use crate::preflop::{PreflopTable, MAX_OPPONENTS, TABLE_FILE};
+use crate::presets::parse_preset;
+use crate::push_fold::PushFold;
+use crate::shove::{Icm, ShoveCall};
+use crate::solver_csv::parse_solver_csv;
+use crate::stud::{stud_enumerate, stud_enumeration_error, stud_simulate, StudPlayer, ThirdStreet};
+use crate::suite::Suite;
+use crate::swing::equity_swing;
+use crate::verify::{CategoryCensus, ClassCensus};
+use crate::weighted_range::WeightedRange;
+use crate::widening::widen_ranges;
+
+const INVALID_COMMAND_ERROR: &str = "Invalid command. See README for usage.";
+
+static PLAYER_LABELS: OnceLock<PlayerLabels> = OnceLock::new();
+
+static JOURNAL: OnceLock<Journal> = OnceLock::new();
 
-const INVALID_COMMAND_ERROR: &'static str = "Invalid command. See README for usage.";
+// Whether --json was given, errors and some results are then printed as JSON.
+static JSON_OUTPUT: OnceLock<bool> = OnceLock::new();
 
-fn main() -> Result<()> {
-    unsafe { Cards::init() };
+// The range registry of the daemon in the config directory.
+const RANGES_FILE: &str = "ranges.txt";
+
+fn main() -> ExitCode {
+    let mut args: Vec<_> = std::env::args().collect();
+    let json = args.iter().any(|arg| arg == "--json");
+    args.retain(|arg| arg != "--json");
+    JSON_OUTPUT.set(json).unwrap();
+    let symbols = args.iter().any(|arg| arg == "--symbols");
+    args.retain(|arg| arg != "--symbols");
+    let result = memory::limit_from_args(&mut args).and_then(|limit| {
+        Suite::set_symbols(symbols)?;
+        Cards::set_score_backend(ScoreBackend::within(limit))?;
+        PlayerLabels::from_args(&mut args)
+    }).and_then(|labels| {
+        PLAYER_LABELS.set(labels).unwrap();
+        if let Some(journal) = Journal::from_args(&mut args)? {
+            let _ = JOURNAL.set(journal);
+        }
+        run(&args)
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            if json {
+                eprintln!("{}", error_json(&err));
+            } else {
+                eprintln!("Error: {}", err);
+            }
+            ExitCode::from(ErrorKind::of(&err).exit_code())
+        },
+    }
+}
 
-    let args: Vec<_> = std::env::args().collect();
+fn run(args: &[String]) -> Result<()> {
     if args.get(1).is_some_and(|cmd| cmd == "enumerate") {
         enumerate(&args[2..])
     } else if args.get(1).is_some_and(|cmd| cmd == "simulate") {
         simulate(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "bench") {
+        bench(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "scenarios") {
+        scenarios(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "verify") {
+        verify(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "recommend") {
+        recommend_calculation(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "boardsize") {
+        board_size(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "export") {
+        export(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "histories") {
+        histories(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "history") {
+        history(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "plan") {
+        plan(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "chops") {
+        chops(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "sidepots") {
+        side_pots(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "improve") {
+        improve(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "risk") {
+        risk(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "madehands") {
+        made_hands(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "boardodds") {
+        board_odds_command(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "best") {
+        best(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "flops") {
+        flops(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "flopmetrics") {
+        flop_metrics_csv(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "hotcold") {
+        hot_cold_cards(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "curve") {
+        curve(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "compare") {
+        compare(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "significance") {
+        significance(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "sensitivity") {
+        sensitivity(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "swing") {
+        swing(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "split") {
+        split(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "combos") {
+        combos(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "filter") {
+        filter(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "showdown") {
+        showdown(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "distribution") {
+        distribution(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "shove") {
+        shove(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "bluffcatch") {
+        bluff_catch(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "heatmap") {
+        heatmap(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "grid") {
+        grid(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "svg") {
+        svg_image(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "duplicate") {
+        duplicate_match(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "coolers") {
+        coolers(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "stud") {
+        stud(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "bringin") {
+        bring_in(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "mixture") {
+        mixture(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "omaha") {
+        omaha(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "game") {
+        game(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "ofc") {
+        ofc(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "fantasyland") {
+        fantasyland(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "pushfold") {
+        push_fold(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "bankroll") {
+        bankroll(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "draw") {
+        draw(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "import") {
+        import(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "buckets") {
+        buckets(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "preflop") {
+        preflop(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "generate-preflop") {
+        generate_preflop(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "dirs") {
+        print_dirs(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "memory") {
+        print_memory(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "shard") {
+        shard(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "resume") {
+        resume(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "merge") {
+        merge(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "daemon") {
+        daemon(&args[2..])
+    } else if args.get(1).is_some_and(|cmd| cmd == "hybrid") {
+        hybrid(&args[2..])
     } else {
-        Err(INVALID_COMMAND_ERROR.into())
+        Err(Error::usage(INVALID_COMMAND_ERROR))
     }
 }
 
 fn enumerate(args: &[String]) -> Result<()> {
+    let mut args = args.to_vec();
+    let min_combos: Option<usize> = take_option(&mut args, "--widen")?
+        .map(|raw| raw.parse())
+        .transpose()?;
+    let fallback_raw = take_option(&mut args, "--fallback")?.unwrap_or_else(|| "full".to_string());
+    let fallback = RangeTable::parse(&fallback_raw)?;
+    let [community_cards_raw, hero_hand_raw, ..] = args.as_slice() else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let mut villain_ranges = args[2..].iter()
+        .map(|raw_range| Opponent::parse(raw_range))
+        .collect::<Result<Vec<_>>>()?;
+    let widenings = match min_combos {
+        Some(min_combos) => widen_ranges(community_cards, hero_hand, &mut villain_ranges, min_combos, &fallback),
+        None => Vec::new(),
+    };
+    let Some(result) = Equity::enumerate(community_cards, hero_hand, &villain_ranges) else {
+        return Err(enumeration_error("enumerate", community_cards, hero_hand, &villain_ranges));
+    };
+    let method = if widenings.is_empty() { "enumerate" } else { "enumerate-widened" };
+    journal_result("enumerate", &args, method, &result)?;
+    print_equities(&result.equities)?;
+    let labels = PLAYER_LABELS.get_or_init(PlayerLabels::default);
+    for widening in widenings {
+        println!(
+            "widened: {} live-combos={} -> {} fallback={}",
+            labels.label(widening.villain + 1),
+            widening.live_before,
+            widening.live_after,
+            fallback_raw,
+        );
+    }
+    println!("work: {}", result.work);
+    Ok(())
+}
+
+// Removes the option and its value from the arguments, None without the option.
+fn take_option(args: &mut Vec<String>, option: &str) -> Result<Option<String>> {
+    let Some(index) = args.iter().position(|arg| arg == option) else {
+        return Ok(None);
+    };
+    if index + 1 >= args.len() {
+        return Err(Error::usage(format!("expected a value after {}", option)));
+    }
+    let value = args.remove(index + 1);
+    args.remove(index);
+    Ok(Some(value))
+}
+
+// Appends the result to the journal given by --journal, if any.
+fn journal_result(command: &str, inputs: &[String], method: &str, result: &EquityResult) -> Result<()> {
+    let Some(journal) = JOURNAL.get() else {
+        return Ok(());
+    };
+    let labels = PLAYER_LABELS.get_or_init(PlayerLabels::default);
+    journal.record(command, inputs, method, result, labels)
+}
+
+fn history(args: &[String]) -> Result<()> {
+    let [journal_path, terms @ ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let entries = Journal::new(journal_path).entries()?;
+    let mut matches = 0;
+    for entry in entries.iter().filter(|entry| entry.matches(terms)) {
+        println!("{}", entry);
+        matches += 1;
+    }
+    println!("entries: {}/{}", matches, entries.len());
+    Ok(())
+}
+
+// Why an enumeration failed (see equity::enumeration_error),
+// a villain without live combos is a usage error naming the villain.
+fn enumeration_error(
+    command: &str,
+    community_cards: Cards,
+    hero_hand: Hand,
+    villain_ranges: &[impl HandRange],
+) -> Error {
+    match equity::enumeration_error(command, community_cards, hero_hand, villain_ranges) {
+        Error::NoLiveCombos(err) => {
+            let label = PLAYER_LABELS.get_or_init(PlayerLabels::default).label(err.villain + 1);
+            Error::usage(format!(
+                "{} failed: {} has no live combos, every combo of the range is blocked by the community cards or the hero hand",
+                command,
+                label,
+            ))
+        },
+        err => err,
+    }
+}
+
+fn verify(args: &[String]) -> Result<()> {
+    let mut failed = 0;
+    for scenario in reference_scenarios()? {
+        if !args.is_empty() && !args.contains(&scenario.name) {
+            continue;
+        }
+        let outcome = scenario.run()?;
+        if !outcome.passed() {
+            failed += 1;
+        }
+        println!("{}: {}", scenario.name, outcome);
+    }
+    type Census = fn() -> CategoryCensus;
+    let censuses: [(&str, Census); 3] = [
+        ("five-card-categories", || CategoryCensus::five_cards(&FastEvaluator)),
+        ("five-card-categories-direct", CategoryCensus::five_cards_direct),
+        ("five-card-categories-naive", || CategoryCensus::five_cards(&NaiveEvaluator)),
+    ];
+    for (census_name, census) in censuses {
+        if args.is_empty() || args.iter().any(|arg| arg == census_name) {
+            let census = census();
+            if !census.passed() {
+                failed += 1;
+            }
+            println!("{}: {}", census_name, census);
+        }
+    }
+    let census_name = "equivalence-classes";
+    if args.is_empty() || args.iter().any(|arg| arg == census_name) {
+        let census = ClassCensus::equivalence_classes();
+        if !census.passed() {
+            failed += 1;
+        }
+        println!("{}: {}", census_name, census);
+    }
+    if failed != 0 {
+        return Err(format!("verify failed: {} checks differ from their reference", failed).into());
+    }
+    Ok(())
+}
+
+fn bench(args: &[String]) -> Result<()> {
+    let [] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    println!("{}", Throughput::measure(Duration::from_secs(1))?);
+    Ok(())
+}
+
+fn scenarios(args: &[String]) -> Result<()> {
+    let mut failed = 0;
+    for scenario in shipped_scenarios()? {
+        if !args.is_empty() && !args.contains(&scenario.name) {
+            continue;
+        }
+        let outcome = scenario.run()?;
+        if !outcome.passed() {
+            failed += 1;
+        }
+        println!("{}: {}", scenario.name, outcome);
+    }
+    if failed != 0 {
+        return Err(format!("scenarios failed: {} scenarios differ from their reference", failed).into());
+    }
+    Ok(())
+}
+
+fn recommend_calculation(args: &[String]) -> Result<()> {
+    let [precision_raw, seconds_raw, community_cards_raw, hero_hand_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let precision: f64 = precision_raw.parse()?;
+    let time_budget = Duration::from_secs_f64(seconds_raw.parse()?);
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let villain_ranges = args[4..].iter()
+        .map(|raw_range| RangeTable::parse(raw_range))
+        .collect::<Result<Vec<_>>>()?;
+    let throughput = Throughput::measure(Duration::from_millis(200))?;
+    println!("{}", throughput);
+    let recommendation = recommend(
+        community_cards,
+        hero_hand,
+        &villain_ranges,
+        precision / 100.0,
+        time_budget,
+        &throughput,
+    )?;
+    println!("{}", recommendation);
+    Ok(())
+}
+
+fn board_size(args: &[String]) -> Result<()> {
+    let [board_size_raw, community_cards_raw, hero_hand_raw, rounds_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let board_size: u8 = board_size_raw.parse()?;
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let result = if rounds_raw == "exact" {
+        let villain_ranges = args[4..].iter()
+            .map(|raw_range| RangeTable::parse(raw_range))
+            .collect::<Result<Vec<_>>>()?;
+        Equity::enumerate_board_size(community_cards, hero_hand, &villain_ranges, board_size).ok_or_else(|| {
+            equity::board_size_enumeration_error("boardsize", community_cards, hero_hand, &villain_ranges, board_size)
+        })
+    } else {
+        let [_, _, _, _, villain_count_raw] = args else {
+            return Err(Error::usage(INVALID_COMMAND_ERROR));
+        };
+        let rounds: u64 = rounds_raw.parse()?;
+        let villain_count: usize = villain_count_raw.parse()?;
+        let mut rng = SmallRng::from_entropy();
+        Equity::simulate_board_size(community_cards, hero_hand, villain_count, rounds, board_size, &mut rng)
+            .ok_or_else(|| Error::usage("boardsize failed: invalid input"))
+    };
+    let result = result?;
+    print_equities(&result.equities)?;
+    println!("work: {}", result.work);
+    Ok(())
+}
+
+fn export(args: &[String]) -> Result<()> {
+    let [path, community_cards_raw, hero_hand_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let villain_ranges = args[3..].iter()
+        .map(|raw_range| RangeTable::parse(raw_range))
+        .collect::<Result<Vec<_>>>()?;
+    let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let result = Equity::enumerate_outcomes(community_cards, hero_hand, &villain_ranges, &mut out)?;
+    print_equities(&result.equities)?;
+    println!("work: {}", result.work);
+    Ok(())
+}
+
+fn histories(args: &[String]) -> Result<()> {
+    let [path, community_cards_raw, hero_hand_raw, rounds_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let rounds: u64 = rounds_raw.parse()?;
+    let villain_ranges = args[4..].iter()
+        .map(|raw_range| RangeTable::parse(raw_range))
+        .collect::<Result<Vec<_>>>()?;
+    let mut rng = SmallRng::from_entropy();
+    let runouts = sample_runouts(community_cards, hero_hand, &villain_ranges, rounds, &mut rng)?;
+    let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+    write_hand_histories(&mut out, &runouts, 100)?;
+    out.flush()?;
+    Ok(())
+}
+
+fn plan(args: &[String]) -> Result<()> {
+    let [community_cards_raw, hero_hand_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let villain_ranges = args[2..].iter()
+        .map(|raw_range| RangeTable::parse(raw_range))
+        .collect::<Result<Vec<_>>>()?;
+    let Some(plan) = EnumerationPlan::new(community_cards, hero_hand, &villain_ranges) else {
+        return Err("plan failed: invalid input".into());
+    };
+    println!("{}", plan);
+    Ok(())
+}
+
+fn split(args: &[String]) -> Result<()> {
+    let [community_cards_raw, hero_hand_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let villain_ranges = args[2..].iter()
+        .map(|raw_range| RangeTable::parse(raw_range))
+        .map(|r| r.map(Arc::new))
+        .collect::<Result<Vec<_>>>()?;
+    let Some((result, split)) = Equity::enumerate_split(
+        community_cards,
+        hero_hand,
+        &villain_ranges,
+    ) else {
+        return Err(enumeration_error("split", community_cards, hero_hand, &villain_ranges));
+    };
+    print_equities(&result.equities)?;
+    println!("hero split: {}", split);
+    println!("work: {}", result.work);
+    Ok(())
+}
+
+fn chops(args: &[String]) -> Result<()> {
+    let [community_cards_raw, hero_hand_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let villain_ranges = args[2..].iter()
+        .map(|raw_range| RangeTable::parse(raw_range))
+        .map(|r| r.map(Arc::new))
+        .collect::<Result<Vec<_>>>()?;
+    let Some((result, chops)) = Equity::enumerate_chops(
+        community_cards,
+        hero_hand,
+        &villain_ranges,
+    ) else {
+        return Err(enumeration_error("chops", community_cards, hero_hand, &villain_ranges));
+    };
+    print_equities(&result.equities)?;
+    println!("{}", chops);
+    println!("work: {}", result.work);
+    Ok(())
+}
+
+fn side_pots(args: &[String]) -> Result<()> {
+    let [community_cards_raw, hero_hand_raw, stacks_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let stacks = stacks_raw.split(',')
+        .map(|stack| stack.parse::<u64>())
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let villain_ranges = args[3..].iter()
+        .map(|raw_range| RangeTable::parse(raw_range))
+        .map(|r| r.map(Arc::new))
+        .collect::<Result<Vec<_>>>()?;
+    if stacks.len() != villain_ranges.len() + 1 || stacks.contains(&0) {
+        return Err(Error::usage("side pots failed: expected one stack above zero per player"));
+    }
+    let Some((result, side_pots)) = Equity::enumerate_side_pots(
+        community_cards,
+        hero_hand,
+        &villain_ranges,
+        &stacks,
+    ) else {
+        return Err(enumeration_error("side pots", community_cards, hero_hand, &villain_ranges));
+    };
+    print_equities(&result.equities)?;
+    println!("{}", side_pots);
+    println!("work: {}", result.work);
+    Ok(())
+}
+
+fn improve(args: &[String]) -> Result<()> {
     let [community_cards_raw, hero_hand_raw, ..] = args else {
-        return Err(INVALID_COMMAND_ERROR.into());
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
     };
-    let community_cards = Cards::from_str(community_cards_raw)?;
-    let hero_hand = Hand::from_str(hero_hand_raw)?;
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
     let villain_ranges = args[2..].iter()
-        .map(|raw_range| RangeTable::parse(&raw_range))
+        .map(|raw_range| RangeTable::parse(raw_range))
         .map(|r| r.map(Arc::new))
         .collect::<Result<Vec<_>>>()?;
-    let Some(equities) = Equity::enumerate(community_cards, hero_hand, &villain_ranges) else {
-        return Err("enumerate failed: invalid input or expected sample to large".into());
+    let Some((result, improve)) = Equity::enumerate_improve(
+        community_cards,
+        hero_hand,
+        &villain_ranges,
+    ) else {
+        return Err(enumeration_error("improve", community_cards, hero_hand, &villain_ranges));
     };
-    print_equities(&equities);
+    print_equities(&result.equities)?;
+    print_equities(&improve)?;
+    println!("work: {}", result.work);
+    Ok(())
+}
+
+fn risk(args: &[String]) -> Result<()> {
+    let [community_cards_raw, hero_hand_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let villain_ranges = args[2..].iter()
+        .map(|raw_range| RangeTable::parse(raw_range))
+        .map(|r| r.map(Arc::new))
+        .collect::<Result<Vec<_>>>()?;
+    let Some((result, risk)) = Equity::enumerate_risk(
+        community_cards,
+        hero_hand,
+        &villain_ranges,
+    ) else {
+        return Err(enumeration_error("risk", community_cards, hero_hand, &villain_ranges));
+    };
+    print_equities(&result.equities)?;
+    println!("{}", risk);
+    println!("work: {}", result.work);
+    Ok(())
+}
+
+fn made_hands(args: &[String]) -> Result<()> {
+    let [community_cards_raw, hero_hand_raw] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let odds = made_hand_odds(community_cards, hero_hand)?;
+    println!("{}", odds);
+    println!("boards: {}", odds.boards);
+    Ok(())
+}
+
+fn board_odds_command(args: &[String]) -> Result<()> {
+    let (community_cards_raw, hero_hand_raw) = match args {
+        [community_cards_raw] => (community_cards_raw, None),
+        [community_cards_raw, hero_hand_raw] => (community_cards_raw, Some(hero_hand_raw)),
+        _ => return Err(Error::usage(INVALID_COMMAND_ERROR)),
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_cards = match hero_hand_raw {
+        Some(hero_hand_raw) => hero_hand_raw.parse::<Hand>()?.to_cards(),
+        None => Cards::EMPTY,
+    };
+    let odds = board_odds(community_cards, hero_cards)?;
+    println!("{}", odds);
+    println!("boards: {}", odds.boards);
+    Ok(())
+}
+
+fn best(args: &[String]) -> Result<()> {
+    let [cards_raw] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let cards = cards_raw.parse::<Cards>()?;
+    if cards.count() == 0 || cards.count() > 7 {
+        return Err("best failed: expected 1 to 7 cards".into());
+    }
+    let best = cards.best_five();
+    println!(
+        "{} cards={} unused={}",
+        HandRanking::CATEGORY_NAMES[best.ranking.category()],
+        best.cards,
+        best.unused,
+    );
+    Ok(())
+}
+
+fn flops(args: &[String]) -> Result<()> {
+    let boards: Vec<_> = match args {
+        [] => canonical_flops().collect(),
+        [board_raw] => {
+            let board = board_raw.parse::<Cards>()?;
+            if !(3..=4).contains(&board.count()) {
+                return Err("flops failed: expected a flop or turn".into());
+            }
+            canonical_extensions(board)
+        },
+        _ => return Err(Error::usage(INVALID_COMMAND_ERROR)),
+    };
+    for board in &boards {
+        println!("{}", board);
+    }
+    let total: u32 = boards.iter().map(|board| board.weight).sum();
+    println!("boards: {} weight: {}", boards.len(), total);
+    Ok(())
+}
+
+fn flop_metrics_csv(args: &[String]) -> Result<()> {
+    let combos = args.iter().any(|arg| arg == "--combos");
+    let args: Vec<_> = args.iter().filter(|arg| *arg != "--combos").cloned().collect();
+    let [range_a_raw, range_b_raw, runouts_raw, nut_percent_raw, flops_raw @ ..] = args.as_slice() else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let range_a = RangeTable::parse(range_a_raw)?;
+    let range_b = RangeTable::parse(range_b_raw)?;
+    let max_runouts: usize = runouts_raw.parse()?;
+    let nut_percent: f64 = nut_percent_raw.parse()?;
+    let boards = if flops_raw.is_empty() {
+        canonical_flops().collect()
+    } else {
+        flops_raw.iter()
+            .map(|flop_raw| Ok(CanonicalBoard { cards: flop_raw.parse::<Cards>()?, weight: 1 }))
+            .collect::<Result<Vec<_>>>()?
+    };
+    let mut rng = SmallRng::from_entropy();
+    // Every row is written as soon as its flop is done, so all flops
+    // with every combo never have to fit in memory.
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    writeln!(out, "{}", if combos { COMBO_CSV_HEADER } else { CSV_HEADER })?;
+    for_each_flop_metrics(&boards, &range_a, &range_b, max_runouts, nut_percent / 100.0, &mut rng, |flop, equities| {
+        if combos {
+            for combo in equities {
+                writeln!(out, "{}", to_combo_csv_row(&flop.board, combo))?;
+            }
+        } else {
+            writeln!(out, "{}", to_csv_row(&flop))?;
+        }
+        Ok(())
+    })?;
+    out.flush()?;
+    Ok(())
+}
+
+fn curve(args: &[String]) -> Result<()> {
+    let (community_cards_raw, hero_hand_raw, max_opponents_raw, rounds_raw, range_raw) = match args {
+        [a, b, c, d] => (a, b, c, d, None),
+        [a, b, c, d, range_raw] => (a, b, c, d, Some(range_raw)),
+        _ => return Err(Error::usage(INVALID_COMMAND_ERROR)),
+    };
+    let range = match range_raw {
+        Some(range_raw) if range_raw != "random" => Some(RangeTable::parse(range_raw)?),
+        _ => None,
+    };
+    let curve = equity_curve(
+        community_cards_raw.parse::<Cards>()?,
+        hero_hand_raw.parse::<Hand>()?,
+        range.as_ref(),
+        max_opponents_raw.parse()?,
+        rounds_raw.parse()?,
+        &mut SmallRng::from_entropy(),
+    )?;
+    for (opponents, equity) in curve.iter().enumerate() {
+        println!("{}: {}", opponents + 1, equity);
+    }
+    Ok(())
+}
+
+fn sensitivity(args: &[String]) -> Result<()> {
+    let [community_cards_raw, hero_hand_raw, percent_raw, step_raw, steps_raw] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let sensitivity = Sensitivity::new(
+        community_cards_raw.parse::<Cards>()?,
+        hero_hand_raw.parse::<Hand>()?,
+        percent_raw.parse()?,
+        step_raw.parse()?,
+        steps_raw.parse()?,
+    )?;
+    for point in &sensitivity.points {
+        println!(
+            "{} change={:+.2}",
+            point,
+            (point.equity - sensitivity.base.equity) * 100.0,
+        );
+    }
+    println!("max-change: {:.2}", sensitivity.max_change() * 100.0);
+    Ok(())
+}
+
+fn compare(args: &[String]) -> Result<()> {
+    let [community_cards_raw, hero_hand_raw, rounds_raw, range_a_raw, range_b_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let rounds = if rounds_raw == "exact" {
+        None
+    } else {
+        Some(rounds_raw.parse()?)
+    };
+    let range_a = RangeTable::parse(range_a_raw)?;
+    let range_b = RangeTable::parse(range_b_raw)?;
+    let other_ranges = args[5..].iter()
+        .map(|raw_range| RangeTable::parse(raw_range))
+        .collect::<Result<Vec<_>>>()?;
+    let comparison = compare_ranges(community_cards, hero_hand, &range_a, &range_b, &other_ranges, rounds)?;
+    println!("{}", comparison);
+    Ok(())
+}
+
+fn significance(args: &[String]) -> Result<()> {
+    let [path_a, path_b] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    // The last line, e.g. the latest journal entry.
+    let read_run = |path: &String| -> Result<RunSummary> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| format!("significance failed: {}: {}", path, err))?;
+        let line = content.lines().rev().find(|line| !line.trim().is_empty())
+            .ok_or_else(|| format!("significance failed: {}: expected a result", path))?;
+        RunSummary::parse_json(line)
+    };
+    let (a, b) = (read_run(path_a)?, read_run(path_b)?);
+    println!("samples a={} b={}", a.samples, b.samples);
+    println!("{}", compare_runs(a, b));
+    Ok(())
+}
+
+fn swing(args: &[String]) -> Result<()> {
+    let [community_cards_raw, hero_hand_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let villain_ranges = args[2..].iter()
+        .map(|raw_range| RangeTable::parse(raw_range))
+        .collect::<Result<Vec<_>>>()?;
+    let swing = equity_swing(community_cards, hero_hand, &villain_ranges)?;
+    println!("equity: {:2.2}", swing.start_equity * 100.0);
+    let names = ["flop-turn", "turn-river"];
+    for (name, street) in names[names.len()-swing.streets.len()..].iter().zip(swing.streets.iter()) {
+        println!("{}: {}", name, street);
+    }
+    Ok(())
+}
+
+fn hot_cold_cards(args: &[String]) -> Result<()> {
+    let [community_cards_raw, hero_hand_raw, threshold_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let threshold: f64 = threshold_raw.parse()?;
+    let villain_ranges = args[3..].iter()
+        .map(|raw_range| RangeTable::parse(raw_range))
+        .collect::<Result<Vec<_>>>()?;
+    let hot_cold = hot_cold(community_cards, hero_hand, &villain_ranges, threshold / 100.0)?;
+    println!("{}", hot_cold);
     Ok(())
 }
 
 fn simulate(args: &[String]) -> Result<()> {
-    let [community_cards_raw, hero_hand_raw, villain_count_raw, rounds_raw] = args else {
-        return Err(INVALID_COMMAND_ERROR.into());
+    let antithetic = args.iter().any(|arg| arg == "--antithetic");
+    let mut args: Vec<_> = args.iter().filter(|arg| *arg != "--antithetic").collect();
+    let trace_interval: u64 = match args.iter().position(|arg| *arg == "--trace") {
+        Some(index) if index + 1 < args.len() => {
+            let interval = args[index + 1].parse()?;
+            args.drain(index..index + 2);
+            interval
+        },
+        Some(_) => return Err(Error::usage("expected a number of rounds after --trace")),
+        None => 0,
+    };
+    let mut rng = match args.iter().position(|arg| *arg == "--seed") {
+        Some(index) if index + 1 < args.len() => {
+            let seed = args[index + 1].parse()?;
+            args.drain(index..index + 2);
+            SmallRng::seed_from_u64(seed)
+        },
+        Some(_) => return Err(Error::usage("expected a number after --seed")),
+        None => SmallRng::from_entropy(),
     };
-    let community_cards = Cards::from_str(community_cards_raw)?;
-    let hero_hand = Hand::from_str(hero_hand_raw)?;
+    let sampling = match args.iter().position(|arg| *arg == "--sampling") {
+        Some(index) if index + 1 < args.len() => {
+            let sampling = SamplingStrategy::parse(args[index + 1])?;
+            args.drain(index..index + 2);
+            Some(sampling)
+        },
+        Some(_) => return Err(Error::usage("expected exact or sequential after --sampling")),
+        None => None,
+    };
+    let [community_cards_raw, hero_hand_raw, villain_count_raw, rounds_raw, ..] = args.as_slice() else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
     let villain_count: usize = villain_count_raw.parse()?;
     let rounds: u64 = rounds_raw.parse()?;
-    let Some(equities) = Equity::simulate(
+    let villain_models = args[4..].iter()
+        .map(|raw| parse_villain_model(raw))
+        .collect::<Result<Vec<_>>>()?;
+    if !villain_models.is_empty() && antithetic {
+        return Err(Error::usage("simulate failed: antithetic sampling needs random villains"));
+    }
+    if !villain_models.is_empty() && trace_interval != 0 {
+        return Err(Error::usage("simulate failed: the convergence trace needs random villains"));
+    }
+    if villain_models.is_empty() && sampling.is_some() {
+        return Err(Error::usage("simulate failed: the sampling applies to villain ranges"));
+    }
+    let (result, convergence) = if villain_models.is_empty() {
+        match Equity::simulate_convergence(
+            community_cards,
+            hero_hand,
+            villain_count,
+            rounds,
+            antithetic,
+            trace_interval,
+            &mut rng,
+        ) {
+            Some((result, convergence)) => (Some(result), Some(convergence)),
+            None => (None, None),
+        }
+    } else if villain_models.len() == villain_count {
+        let sampling = sampling.unwrap_or(SamplingStrategy::ExactRejection);
+        let result = RangeSimulator::new(&villain_models, sampling).and_then(|simulator| {
+            Equity::simulate_ranges(community_cards, hero_hand, Cards::EMPTY, simulator, rounds, &mut rng)
+        });
+        (result, None)
+    } else {
+        return Err(format!(
+            "simulate failed: expected {} villain models, got {}",
+            villain_count,
+            villain_models.len(),
+        ).into());
+    };
+    let Some(result) = result else {
+        return Err("simulate failed: invalid input".into());
+    };
+    let method = if villain_models.is_empty() && antithetic {
+        "simulate-antithetic"
+    } else if villain_models.is_empty() {
+        "simulate"
+    } else {
+        "simulate-weighted"
+    };
+    let inputs: Vec<_> = args.iter().map(|arg| arg.to_string()).collect();
+    journal_result("simulate", &inputs, method, &result)?;
+    if JSON_OUTPUT.get().copied().unwrap_or(false) {
+        let labels = PLAYER_LABELS.get_or_init(PlayerLabels::default);
+        let json = match &convergence {
+            Some(convergence) => simulation_json(&result, convergence, labels)?,
+            None => result_json(&result, labels)?,
+        };
+        println!("{{\"result\":{}}}", json);
+        return Ok(());
+    }
+    print_equities(&result.equities)?;
+    if let Some(convergence) = convergence {
+        println!("convergence: {}", convergence);
+        for point in convergence.trace {
+            println!("trace: {}", point);
+        }
+    }
+    println!("work: {}", result.work);
+    Ok(())
+}
+
+fn combos(args: &[String]) -> Result<()> {
+    let [community_cards_raw, hero_hand_raw, range_raw] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let range = WeightedRange::parse(range_raw)?;
+    let Some(live) = range.live(community_cards | hero_hand.to_cards()) else {
+        return Err("combos failed: every hand of the range is blocked".into());
+    };
+    for (hand, weight) in live.hands() {
+        println!("{}: {:.4}", hand, weight * 100.0);
+    }
+    println!("combos: {}/{}", live.count(), range.count());
+    Ok(())
+}
+
+fn filter(args: &[String]) -> Result<()> {
+    let [community_cards_raw, range_raw, opponent_raw, min_equity_raw] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let range = WeightedRange::parse(range_raw)?;
+    let opponent = Opponent::parse(opponent_raw)?;
+    let min_equity: f64 = min_equity_raw.parse()?;
+    let filtered = range.filter_by_equity(community_cards, &opponent, min_equity / 100.0);
+    println!("combos: {}/{}", filtered.count(), range.count());
+    println!("{}", filtered);
+    Ok(())
+}
+
+fn showdown(args: &[String]) -> Result<()> {
+    let [range_raw, opponent_raw, showdowns_raw @ ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    if showdowns_raw.is_empty() {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    }
+    let range = WeightedRange::parse(range_raw)?;
+    let opponent = Opponent::parse(opponent_raw)?;
+    let mut session = SessionReads::default();
+    for showdown_raw in showdowns_raw {
+        let Some((community_cards_raw, shown_hand_raw)) = showdown_raw.split_once(':') else {
+            return Err(Error::usage(format!(
+                "showdown failed: expected <community>:<hand>, got '{}'",
+                showdown_raw,
+            )));
+        };
+        let community_cards = community_cards_raw.parse::<Cards>()?;
+        let shown_hand = shown_hand_raw.parse::<Hand>()?;
+        let read = read_showdown(community_cards, &range, &opponent, shown_hand)?;
+        println!("{}", read);
+        session.add(read);
+    }
+    println!("{}", session);
+    Ok(())
+}
+
+fn distribution(args: &[String]) -> Result<()> {
+    let [community_cards_raw, range_raw] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let range = RangeTable::parse(range_raw)?;
+    for street in range_distribution(community_cards, &range)? {
+        println!("{}", street);
+    }
+    Ok(())
+}
+
+fn shove(args: &[String]) -> Result<()> {
+    let (icm, args) = match args {
+        [args @ .., raw_stacks, raw_payouts] if args.len() == 5 => {
+            (Some(Icm::parse(raw_stacks, raw_payouts)?), args)
+        },
+        args => (None, args),
+    };
+    let [community_cards_raw, hero_hand_raw, pot_raw, stack_raw, villain_range_raw] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let pot: f64 = pot_raw.parse()?;
+    let stack: f64 = stack_raw.parse()?;
+    let villain_range = RangeTable::parse(villain_range_raw)?;
+    let call = ShoveCall::new(
         community_cards,
         hero_hand,
-        villain_count,
+        pot,
+        stack,
+        &villain_range,
+        icm.as_ref(),
+    )?;
+    print!("call: ev={:.2} equity={:2.2}", call.ev, call.equity * 100.0);
+    if let Some(icm_ev) = call.icm_ev {
+        print!(" icm-ev={:.4}", icm_ev);
+    }
+    println!();
+    for combo in &call.combos {
+        println!("{}", combo);
+    }
+    Ok(())
+}
+
+fn bluff_catch(args: &[String]) -> Result<()> {
+    let [community_cards_raw, pot_raw, bet_raw, hero_range_raw, value_range_raw, bluff_range_raw] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let pot: f64 = pot_raw.parse()?;
+    let bet: f64 = bet_raw.parse()?;
+    let hero_range = Opponent::parse(hero_range_raw)?;
+    let value_range = RangeTable::parse(value_range_raw)?;
+    let bluff_range = RangeTable::parse(bluff_range_raw)?;
+    let bluff_catch = BluffCatch::new(
+        community_cards,
+        pot,
+        bet,
+        &hero_range,
+        &value_range,
+        &bluff_range,
+    )?;
+    println!(
+        "mdf={:2.2} required-equity={:2.2} profitable-calls={}/{}",
+        bluff_catch.minimum_defense_frequency() * 100.0,
+        bluff_catch.required_equity() * 100.0,
+        bluff_catch.profitable_calls(),
+        bluff_catch.candidates.len(),
+    );
+    for candidate in &bluff_catch.candidates {
+        println!("{}", candidate);
+    }
+    Ok(())
+}
+
+fn heatmap(args: &[String]) -> Result<()> {
+    let [community_cards_raw, hero_range_raw, villain_range_raw, format] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_range = RangeTable::parse(hero_range_raw)?;
+    let villain_range = RangeTable::parse(villain_range_raw)?;
+    let heatmap = EquityHeatmap::new(community_cards, &hero_range, &villain_range)?;
+    match format.as_str() {
+        "csv" => print!("{}", heatmap.to_csv()),
+        "json" => print!("{}", heatmap.to_json()),
+        _ => return Err(format!("heatmap failed: unknown format '{}'", format).into()),
+    }
+    Ok(())
+}
+
+fn grid(args: &[String]) -> Result<()> {
+    let (community_cards_raw, hero_range_raw, villain_range_raw) = match args {
+        [community_cards_raw, hero_range_raw] => (community_cards_raw, hero_range_raw, None),
+        [community_cards_raw, hero_range_raw, villain_range_raw] => {
+            (community_cards_raw, hero_range_raw, Some(villain_range_raw))
+        },
+        _ => return Err(Error::usage(INVALID_COMMAND_ERROR)),
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_range = RangeTable::parse(hero_range_raw)?;
+    let equities = match villain_range_raw {
+        Some(villain_range_raw) => {
+            let villain_range = RangeTable::parse(villain_range_raw)?;
+            Some(class_equities(community_cards, &hero_range, &villain_range)?)
+        },
+        None => None,
+    };
+    print!("{}", render_grid(&hero_range, equities.as_deref()));
+    Ok(())
+}
+
+#[cfg(feature = "svg")]
+fn svg_image(args: &[String]) -> Result<()> {
+    let [path, community_cards_raw, hands_raw @ ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let image = if hands_raw.is_empty() {
+        svg::cards_svg(community_cards)
+    } else {
+        let hands = hands_raw.iter()
+            .map(|hand_raw| hand_raw.parse::<Cards>())
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let labeled = PLAYER_LABELS.get_or_init(PlayerLabels::default).labeled(&hands)?;
+        let players: Vec<_> = labeled.into_iter().map(|(label, cards)| (label, *cards)).collect();
+        svg::spot_svg(community_cards, &players)
+    };
+    std::fs::write(path, image)?;
+    Ok(())
+}
+
+#[cfg(not(feature = "svg"))]
+fn svg_image(_args: &[String]) -> Result<()> {
+    Err(Error::usage("svg failed: built without the svg feature, build with --features svg"))
+}
+
+fn duplicate_match(args: &[String]) -> Result<()> {
+    let [stack_raw, deals_raw, range_a_raw, range_b_raw] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    // Blinds of 1 and 2 chips, the stack is in big blinds.
+    let stack: u64 = stack_raw.parse()?;
+    let deals: u64 = deals_raw.parse()?;
+    let mut a = RangePlayer::new(RangeTable::parse(range_a_raw)?);
+    let mut b = RangePlayer::new(RangeTable::parse(range_b_raw)?);
+    let result = duplicate(&mut a, &mut b, stack * 2, 1, 2, deals, &mut SmallRng::from_entropy())?;
+    println!("{}", result);
+    Ok(())
+}
+
+fn coolers(args: &[String]) -> Result<()> {
+    let [community_cards_raw, range_a_raw, range_b_raw] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let range_a = RangeTable::parse(range_a_raw)?;
+    let range_b = RangeTable::parse(range_b_raw)?;
+    let stats = CoolerStats::enumerate(community_cards, &range_a, &range_b)?;
+    print!("{}", stats);
+    Ok(())
+}
+
+fn stud(args: &[String]) -> Result<()> {
+    let [dead_cards_raw, rounds_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let dead_cards = dead_cards_raw.parse::<Cards>()?;
+    let players = args[2..].iter()
+        .map(|raw_player| StudPlayer::parse(raw_player))
+        .collect::<Result<Vec<_>>>()?;
+    let result = if rounds_raw == "exact" {
+        stud_enumerate(&players, dead_cards)
+            .ok_or_else(|| stud_enumeration_error("stud", &players, dead_cards))
+    } else {
+        let rounds: u64 = rounds_raw.parse()?;
+        stud_simulate(&players, dead_cards, rounds, &mut SmallRng::from_entropy())
+            .ok_or_else(|| Error::usage("stud failed: invalid input"))
+    };
+    let result = result?;
+    for (player, equity) in players.iter().zip(result.equities.iter()) {
+        println!("{}: {}", player, equity);
+    }
+    println!("work: {}", result.work);
+    Ok(())
+}
+
+fn bring_in(args: &[String]) -> Result<()> {
+    let [hero_raw, opponents_raw, folded_raw, rounds_raw] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let street = ThirdStreet::new(
+        StudPlayer::parse(hero_raw)?,
+        opponents_raw.parse::<Cards>()?,
+        folded_raw.parse::<Cards>()?,
+    )?;
+    let rounds = if rounds_raw == "exact" {
+        None
+    } else {
+        Some(rounds_raw.parse()?)
+    };
+    let result = street.equity(rounds, &mut SmallRng::from_entropy())?;
+    println!("bring-in: {}", street.bring_in());
+    println!("{}", street.live_cards());
+    for (player, equity) in street.players().iter().zip(result.equities.iter()) {
+        println!("{}: {}", player, equity);
+    }
+    println!("work: {}", result.work);
+    Ok(())
+}
+
+fn mixture(args: &[String]) -> Result<()> {
+    let [community_cards_raw, hero_hand_raw, rounds_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let villains = args[3..].iter()
+        .map(|raw| VillainMixture::parse(raw))
+        .collect::<Result<Vec<_>>>()?;
+    if rounds_raw == "exact" {
+        let Some((equities, work)) = mixture_enumerate(community_cards, hero_hand, &villains) else {
+            return Err(mixture_enumeration_error(community_cards, hero_hand, &villains));
+        };
+        print_equities(&equities)?;
+        println!("work: {}", work);
+    } else {
+        let rounds: u64 = rounds_raw.parse()?;
+        let mut rng = SmallRng::from_entropy();
+        let Some(result) = mixture_simulate(community_cards, hero_hand, &villains, rounds, &mut rng) else {
+            return Err("mixture failed: invalid input".into());
+        };
+        print_equities(&result.equities)?;
+        println!("work: {}", result.work);
+    }
+    Ok(())
+}
+
+fn omaha(args: &[String]) -> Result<()> {
+    let [community_cards_raw, rounds_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    // Double board: both boards separated by a slash.
+    let boards = community_cards_raw.split('/')
+        .map(str::parse::<Cards>)
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let hands = args[2..].iter()
+        .map(|raw_hand| OmahaHand::parse(raw_hand))
+        .collect::<Result<Vec<_>>>()?;
+    let result = if rounds_raw == "exact" {
+        omaha_enumerate_boards(&boards, &hands)
+    } else {
+        let rounds: u64 = rounds_raw.parse()?;
+        omaha_simulate_boards(&boards, &hands, rounds, &mut SmallRng::from_entropy())
+    };
+    let Some(result) = result else {
+        return Err("omaha failed: invalid input".into());
+    };
+    for (hand, equity) in hands.iter().zip(result.equities.iter()) {
+        println!("{}: {}", hand, equity);
+    }
+    println!("work: {}", result.work);
+    Ok(())
+}
+
+fn game(args: &[String]) -> Result<()> {
+    let [rules_raw, community_cards_raw, rounds_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let Some(rules) = parse_game_rules(rules_raw) else {
+        return Err(format!("game failed: unknown game '{}'", rules_raw).into());
+    };
+    let boards = community_cards_raw.split('/')
+        .map(str::parse::<Cards>)
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let hands = args[3..].iter()
+        .map(|raw_hand| raw_hand.parse::<Cards>())
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let result = if rounds_raw == "exact" {
+        rules_enumerate(rules.as_ref(), &boards, &hands)
+    } else {
+        let rounds: u64 = rounds_raw.parse()?;
+        rules_simulate(rules.as_ref(), &boards, &hands, rounds, &mut SmallRng::from_entropy())
+    };
+    let Some(result) = result else {
+        return Err(format!("game failed: invalid input for {}", rules.name()).into());
+    };
+    for (hand, equity) in args[3..].iter().zip(result.equities.iter()) {
+        println!("{}: {}", hand, equity);
+    }
+    println!("work: {}", result.work);
+    Ok(())
+}
+
+fn ofc(args: &[String]) -> Result<()> {
+    let [a_raw, b_raw] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let hands = [OfcHand::parse(a_raw)?, OfcHand::parse(b_raw)?];
+    if hands.iter().any(|hand| !hand.is_complete()) {
+        return Err(Error::usage("ofc failed: expected two complete hands"));
+    }
+    if let Some(card) = (hands[0].cards() & hands[1].cards()).first() {
+        return Err(Error::DuplicateCard(card));
+    }
+    for hand in &hands {
+        let [front, middle, back] = hand.row_royalties();
+        println!(
+            "{}: fouled={} royalties={} (front={} middle={} back={}) fantasyland={}",
+            hand,
+            hand.is_fouled(),
+            hand.royalties(),
+            front,
+            middle,
+            back,
+            hand.qualifies_for_fantasyland(),
+        );
+    }
+    println!("points: {:+}", head_to_head(&hands[0], &hands[1]));
+    Ok(())
+}
+
+fn fantasyland(args: &[String]) -> Result<()> {
+    let [placement_raw, dead_cards_raw, rounds_raw] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let placement = OfcHand::parse(placement_raw)?;
+    let dead_cards = dead_cards_raw.parse::<Cards>()?;
+    let rounds: u64 = rounds_raw.parse()?;
+    let Some(probability) = fantasyland_probability(
+        &placement,
+        dead_cards,
         rounds,
+        &mut SmallRng::from_entropy(),
     ) else {
-        return Err("simulate failed: invalid input".into());
+        return Err("fantasyland failed: invalid input".into());
     };
-    print_equities(&equities);
+    println!("fantasyland: {:2.2}", probability * 100.0);
     Ok(())
 }
 
-fn print_equities(equities: &[Equity]) {
+fn push_fold(args: &[String]) -> Result<()> {
+    let (stack_raw, iterations) = match args {
+        [stack_raw] => (stack_raw, 1000),
+        [stack_raw, iterations_raw] => (stack_raw, iterations_raw.parse()?),
+        _ => return Err(Error::usage(INVALID_COMMAND_ERROR)),
+    };
+    println!("{}", PushFold::solve(stack_raw.parse()?, iterations)?);
+    Ok(())
+}
+
+fn bankroll(args: &[String]) -> Result<()> {
+    let [ev_raw, std_dev_raw, bankroll_raw, hands_raw, trials_raw] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let bankroll = Bankroll::new(ev_raw.parse()?, std_dev_raw.parse()?, bankroll_raw.parse()?)?;
+    let hands: u64 = hands_raw.parse()?;
+    let trials: u64 = trials_raw.parse()?;
+    println!("risk-of-ruin (unlimited hands): {:2.2}", bankroll.risk_of_ruin() * 100.0);
+    for risk in [0.05, 0.01] {
+        if let Some(required) = bankroll.required_bankroll(risk) {
+            println!("bankroll for {:.0}% risk: {:.2}", risk * 100.0, required);
+        }
+    }
+    let Some(simulation) = bankroll.simulate(hands, trials, &mut SmallRng::from_entropy()) else {
+        return Err("bankroll failed: invalid hands or trials".into());
+    };
+    println!("simulated ({} hands): {}", hands, simulation);
+    Ok(())
+}
+
+fn draw(args: &[String]) -> Result<()> {
+    let [rounds_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let rounds: u64 = rounds_raw.parse()?;
+    let players = args[1..].iter()
+        .map(|raw_player| DrawPlayer::parse(raw_player))
+        .collect::<Result<Vec<_>>>()?;
+    let Some(result) = draw_simulate(&players, rounds, &mut SmallRng::from_entropy()) else {
+        return Err("draw failed: invalid input".into());
+    };
+    for ((player, pre_draw), post_draw) in players.iter()
+        .zip(result.pre_draw.iter())
+        .zip(result.post_draw.equities.iter()) {
+        println!(
+            "{}: pre-draw={:2.2} post-draw: {}",
+            player,
+            pre_draw.equity_percent() * 100.0,
+            post_draw,
+        );
+    }
+    println!("work: {}", result.post_draw.work);
+    Ok(())
+}
+
+fn import(args: &[String]) -> Result<()> {
+    let [path] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let actions = parse_solver_csv(&std::fs::read_to_string(path)?)?;
+    for action in actions {
+        println!(
+            "{}: combos={} weight={:.2}",
+            action.name,
+            action.range.count(),
+            action.range.total_weight(),
+        );
+        println!("{}", action.range);
+    }
+    Ok(())
+}
+
+fn preflop(args: &[String]) -> Result<()> {
+    let [hero_hand_raw, villain_raw] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let table = PreflopTable::active();
+    let equity = if let Ok(villain_count) = villain_raw.parse::<usize>() {
+        if !(1..=MAX_OPPONENTS).contains(&villain_count) {
+            return Err(format!("preflop failed: expected 1 to {MAX_OPPONENTS} villains").into());
+        }
+        table.vs_random(hero_hand, villain_count)
+    } else {
+        table.heads_up(hero_hand, villain_raw.parse::<Hand>()?)
+    };
+    println!("hero:      equity={:2.2}", equity * 100.0);
+    Ok(())
+}
+
+fn buckets(args: &[String]) -> Result<()> {
+    let [bucket_count_raw, field_raw] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let field = match field_raw.parse::<usize>() {
+        Ok(opponents) => BucketField::Random(opponents),
+        Err(_) => BucketField::Range(RangeTable::parse(field_raw)?),
+    };
+    let buckets = PreflopBuckets::new(&field, bucket_count_raw.parse()?)?;
+    print!("{}", buckets);
+    Ok(())
+}
+
+fn generate_preflop(args: &[String]) -> Result<()> {
+    let (heads_up_rounds_raw, vs_random_rounds_raw, path) = match args {
+        [heads_up_rounds_raw, vs_random_rounds_raw] => {
+            (heads_up_rounds_raw, vs_random_rounds_raw, dirs::data_file(TABLE_FILE)?)
+        },
+        [heads_up_rounds_raw, vs_random_rounds_raw, path] => {
+            (heads_up_rounds_raw, vs_random_rounds_raw, PathBuf::from(path))
+        },
+        _ => return Err(Error::usage(INVALID_COMMAND_ERROR)),
+    };
+    let table = PreflopTable::generate(
+        heads_up_rounds_raw.parse()?,
+        vs_random_rounds_raw.parse()?,
+    );
+    std::fs::write(&path, table.to_bytes())?;
+    println!("written: {}", path.display());
+    Ok(())
+}
+
+fn print_dirs(args: &[String]) -> Result<()> {
+    let [] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    println!("data: {}", dirs::data_dir()?.display());
+    println!("config: {}", dirs::config_dir()?.display());
+    Ok(())
+}
+
+fn print_memory(args: &[String]) -> Result<()> {
+    let [] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    println!("{}", MemoryReport::loaded());
+    Ok(())
+}
+
+fn shard(args: &[String]) -> Result<()> {
+    let [community_cards_raw, hero_hand_raw, start_raw, end_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let villain_ranges = args[4..].iter()
+        .map(|raw_range| RangeTable::parse(raw_range))
+        .collect::<Result<Vec<_>>>()?;
+    let Some(enumeration) = Enumeration::new(community_cards, hero_hand, &villain_ranges) else {
+        return Err("shard failed: invalid input".into());
+    };
+    let board_count = enumeration.board_count();
+    let start = start_raw.parse::<usize>()?.min(board_count);
+    let end = end_raw.parse::<usize>()?.min(board_count);
+    let Some(mut enumeration) = enumeration.shard(start, end) else {
+        return Err("shard failed: invalid board range".into());
+    };
+    enumeration.run_until(end);
+    println!("{}", enumeration.checkpoint());
+    Ok(())
+}
+
+fn resume(args: &[String]) -> Result<()> {
+    let [path, time_limit_raw, community_cards_raw, hero_hand_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let time_limit = Duration::from_millis(time_limit_raw.parse()?);
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let villain_ranges = args[4..].iter()
+        .map(|raw_range| RangeTable::parse(raw_range))
+        .collect::<Result<Vec<_>>>()?;
+    let Some(mut enumeration) = Enumeration::new(community_cards, hero_hand, &villain_ranges) else {
+        return Err("resume failed: invalid input".into());
+    };
+    if let Ok(raw_checkpoint) = std::fs::read_to_string(path) {
+        enumeration = enumeration.resume(&Checkpoint::parse(&raw_checkpoint)?)?;
+    }
+    enumeration.run_for(time_limit);
+    let checkpoint = enumeration.checkpoint();
+    std::fs::write(path, format!("{}\n", checkpoint))?;
+    println!("boards: {}/{}", enumeration.cursor(), enumeration.board_count());
+    if checkpoint.is_done() {
+        let Some(equities) = checkpoint.equities() else {
+            return Err("resume failed: no showdowns".into());
+        };
+        print_equities(&equities)?;
+    }
+    Ok(())
+}
+
+fn merge(args: &[String]) -> Result<()> {
+    let mut checkpoints = args.iter()
+        .map(|path| Checkpoint::parse(&std::fs::read_to_string(path)?))
+        .collect::<Result<Vec<_>>>()?;
+    checkpoints.sort_by_key(|checkpoint| checkpoint.to_string());
+    let Some(mut merged) = checkpoints.pop() else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    while !checkpoints.is_empty() {
+        let Some(index) = checkpoints.iter().position(|checkpoint| merged.merge(checkpoint).is_ok()) else {
+            return Err("merge failed: checkpoints are not adjacent".into());
+        };
+        merged = merged.merge(&checkpoints.remove(index))?;
+    }
+    println!("{}", merged);
+    let Some(equities) = merged.equities() else {
+        return Err("merge failed: no showdowns".into());
+    };
+    print_equities(&equities)?;
+    Ok(())
+}
+
+fn parse_villain_model(raw: &str) -> Result<WeightedRange> {
+    if let Some((card, rest)) = split_exposed_card(raw)? {
+        let model = if rest.is_empty() {
+            WeightedRange::full()
+        } else {
+            parse_villain_model(rest)?
+        };
+        Ok(model.containing(card))
+    } else if raw == "random" {
+        Ok(WeightedRange::full())
+    } else if let Some((path, action_name)) = raw.split_once(".csv#") {
+        let actions = parse_solver_csv(&std::fs::read_to_string(format!("{path}.csv"))?)?;
+        actions.into_iter()
+            .find(|action| action.name == action_name)
+            .map(|action| action.range)
+            .ok_or_else(|| format!("invalid villain model '{raw}': unknown action").into())
+    } else if raw.contains('-') && !raw.contains(',') && !raw.contains(':') {
+        parse_preset(raw)
+    } else {
+        WeightedRange::parse(raw)
+    }
+}
+
+fn hybrid(args: &[String]) -> Result<()> {
+    let [community_cards_raw, hero_hand_raw, time_limit_raw, ..] = args else {
+        return Err(Error::usage(INVALID_COMMAND_ERROR));
+    };
+    let community_cards = community_cards_raw.parse::<Cards>()?;
+    let hero_hand = hero_hand_raw.parse::<Hand>()?;
+    let time_limit = Duration::from_millis(time_limit_raw.parse()?);
+    let villain_ranges = args[3..].iter()
+        .map(|raw_range| RangeTable::parse(raw_range))
+        .map(|r| r.map(Arc::new))
+        .collect::<Result<Vec<_>>>()?;
+    let Some(hybrid) = Equity::hybrid(
+        community_cards,
+        hero_hand,
+        &villain_ranges,
+        time_limit,
+        &mut SmallRng::from_entropy(),
+    ) else {
+        return Err("hybrid failed: invalid input".into());
+    };
+    println!(
+        "boards: total={} exact={} sampled={}",
+        hybrid.total_boards,
+        hybrid.exact_boards,
+        hybrid.sampled_boards,
+    );
+    print_equities(&hybrid.equities)?;
+    println!("work: {}", hybrid.work);
+    Ok(())
+}
+
+fn daemon(args: &[String]) -> Result<()> {
+    let mut args = args.to_vec();
+    let ranges_path = match args.iter().position(|arg| arg == "--ranges") {
+        Some(index) if index + 1 < args.len() => {
+            let path = args.remove(index + 1);
+            args.remove(index);
+            PathBuf::from(path)
+        },
+        Some(_) => return Err(Error::usage("expected a path after --ranges")),
+        None => dirs::config_dir()?.join(RANGES_FILE),
+    };
+    let (address, max_concurrent) = match args.as_slice() {
+        [address] => (address, 4),
+        [address, max_concurrent_raw] => (address, max_concurrent_raw.parse()?),
+        _ => return Err(Error::usage(INVALID_COMMAND_ERROR)),
+    };
+    Daemon::new(max_concurrent, ranges_path)?.serve(address)
+}
+
+fn print_equities(equities: &[impl fmt::Display]) -> Result<()> {
     assert!(equities.len() >= 2);
-    println!("hero:      {}", equities[0]);
-    for (i, equity) in equities[1..].iter().enumerate() {
-        println!("villain {}: {}", i+1, equity);
+    let labeled = PLAYER_LABELS.get_or_init(PlayerLabels::default).labeled(equities)?;
+    let width = labeled.iter().map(|(label, _)| label.len() + 1).max().unwrap();
+    for (label, equity) in labeled {
+        println!("{:<width$} {}", format!("{}:", label), equity);
     }
+    Ok(())
 }
@@ -0,0 +1,94 @@
+use core::fmt;
+
+use crate::canonical;
+use crate::cards::{Cards, ScoreBackend};
+use crate::equivalence_class;
+use crate::error::Error;
+use crate::preflop;
+use crate::result::Result;
+
+// Bytes with an optional K, M or G suffix (powers of 1024), e.g. "512K" or "64M".
+pub fn parse_size(s: &str) -> Result<usize> {
+    let (digits, factor) = match s.char_indices().last() {
+        Some((index, 'K' | 'k')) => (&s[..index], 1 << 10),
+        Some((index, 'M' | 'm')) => (&s[..index], 1 << 20),
+        Some((index, 'G' | 'g')) => (&s[..index], 1 << 30),
+        _ => (s, 1),
+    };
+    digits.parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_mul(factor))
+        .ok_or_else(|| format!("invalid size '{}': expected bytes with an optional K, M or G suffix", s).into())
+}
+
+pub fn format_size(bytes: usize) -> String {
+    if bytes < 1 << 10 {
+        format!("{} B", bytes)
+    } else if bytes < 1 << 20 {
+        format!("{:.1} KiB", bytes as f64 / f64::from(1 << 10))
+    } else {
+        format!("{:.1} MiB", bytes as f64 / f64::from(1 << 20))
+    }
+}
+
+// --memory-limit <size> caps the evaluation tables, above it the slower
+// table-free score backend is used.
+pub fn limit_from_args(args: &mut Vec<String>) -> Result<Option<usize>> {
+    let Some(index) = args.iter().position(|arg| arg == "--memory-limit") else {
+        return Ok(None);
+    };
+    if index + 1 >= args.len() {
+        return Err(Error::usage("expected a size after --memory-limit"));
+    }
+    let limit = parse_size(&args[index + 1])?;
+    args.drain(index..index + 2);
+    Ok(Some(limit))
+}
+
+// Heap memory of the loaded evaluation tables and caches in bytes.
+pub struct MemoryReport {
+    pub backend: ScoreBackend,
+    pub parts: Vec<(&'static str, usize)>,
+}
+
+impl fmt::Display for MemoryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "score-backend: {}", self.backend.name())?;
+        if self.backend == ScoreBackend::Direct {
+            write!(f, " (tables need {})", format_size(Cards::score_tables_memory()))?;
+        }
+        writeln!(f)?;
+        for (name, bytes) in &self.parts {
+            writeln!(f, "{}: {}", name, format_size(*bytes))?;
+        }
+        write!(f, "total: {}", format_size(self.total()))
+    }
+}
+
+impl MemoryReport {
+    pub fn loaded() -> Self {
+        let backend = Cards::score_backend();
+        let score_tables = match backend {
+            ScoreBackend::Tables => Cards::score_tables_memory(),
+            ScoreBackend::Direct => 0,
+        };
+        Self {
+            backend,
+            parts: vec![
+                ("score-tables", score_tables),
+                ("preflop", preflop::loaded_memory()),
+                ("canonical-flops", canonical::loaded_memory()),
+                ("equivalence-classes", equivalence_class::loaded_memory()),
+            ],
+        }
+    }
+
+    pub fn with(mut self, name: &'static str, bytes: usize) -> Self {
+        self.parts.push((name, bytes));
+        self
+    }
+
+    pub fn total(&self) -> usize {
+        self.parts.iter().map(|(_, bytes)| bytes).sum()
+    }
+}
@@ -0,0 +1,246 @@
+use core::fmt;
+use std::time::Instant;
+
+use rand::Rng;
+
+use crate::card::Card;
+use crate::cards::{Cards, Score};
+use crate::equity::{enumeration_error, showdown, Equity, EquityResult, Work};
+use crate::error::Error;
+use crate::hand::Hand;
+use crate::range::RangeTable;
+use crate::result::Result;
+
+// Deals per round before a simulation is given up, see mixture_simulate.
+const MAX_DEAL_ATTEMPTS: u32 = 100_000;
+
+// A villain whose type is unknown: with the given probability
+// the villain plays the range of the type, e.g. 60% tight, 40% loose.
+#[derive(Clone)]
+pub struct VillainMixture {
+    types: Vec<(RangeTable, f64)>,
+}
+
+impl VillainMixture {
+    // Types separated by |, each a range with an optional relative weight after @,
+    // e.g. "QQ+,AKs@60|22+,A2s+,KTs+,QJs,AJo+@40". Weights default to 1
+    // and are normalized to probabilities.
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut types = Vec::new();
+        for raw_type in s.split('|') {
+            let (raw_range, weight) = match raw_type.rsplit_once('@') {
+                Some((raw_range, raw_weight)) => match raw_weight.parse::<f64>() {
+                    Ok(weight) if weight.is_finite() && weight > 0.0 => (raw_range, weight),
+                    _ => return Err(format!("invalid villain mixture '{s}': invalid weight").into()),
+                },
+                None => (raw_type, 1.0),
+            };
+            types.push((RangeTable::parse(raw_range)?, weight));
+        }
+        let total: f64 = types.iter().map(|(_, weight)| weight).sum();
+        for (_, weight) in &mut types {
+            *weight /= total;
+        }
+        Ok(Self { types })
+    }
+
+    pub fn types(&self) -> &[(RangeTable, f64)] {
+        &self.types
+    }
+}
+
+impl fmt::Display for VillainMixture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (range, probability)) in self.types.iter().enumerate() {
+            if index != 0 {
+                write!(f, "|")?;
+            }
+            write!(f, "{}@{:.2}", range, probability * 100.0)?;
+        }
+        Ok(())
+    }
+}
+
+// Equity against villain mixtures, averaged over the villain types.
+#[derive(Debug, Clone, Copy)]
+pub struct MixtureEquity {
+    pub equity: f64,
+    pub win: f64,
+    pub tie: f64,
+}
+
+impl fmt::Display for MixtureEquity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "equity={:2.2} win={:2.2} tie={:2.2}",
+            self.equity * 100.0,
+            self.win * 100.0,
+            self.tie * 100.0,
+        )
+    }
+}
+
+// Enumerates every combination of villain types and weights the exact equities
+// with the probability of the combination. Combinations impossible
+// with the known cards (every hand of a type is blocked) are left out
+// and the remaining probabilities renormalized.
+pub fn mixture_enumerate(
+    community_cards: Cards,
+    hero_hand: Hand,
+    villains: &[VillainMixture],
+) -> Option<(Vec<MixtureEquity>, Work)> {
+    let start = Instant::now();
+    let player_count = villains.len() + 1;
+    let mut equities = vec![MixtureEquity { equity: 0.0, win: 0.0, tie: 0.0 }; player_count];
+    let mut work = Work::default();
+    let mut total_probability = 0.0;
+    let mut type_indices = vec![0; villains.len()];
+    loop {
+        let probability: f64 = villains.iter()
+            .zip(&type_indices)
+            .map(|(villain, index)| villain.types[*index].1)
+            .product();
+        let ranges: Vec<_> = villains.iter()
+            .zip(&type_indices)
+            .map(|(villain, index)| &villain.types[*index].0)
+            .collect();
+        if let Some(result) = Equity::enumerate(community_cards, hero_hand, &ranges) {
+            total_probability += probability;
+            for (mixture, equity) in equities.iter_mut().zip(&result.equities) {
+                mixture.equity += probability * equity.equity_percent();
+                mixture.win += probability * equity.win_percent();
+                mixture.tie += probability * equity.tie_percent();
+            }
+            work.boards += result.work.boards;
+            work.villain_combos += result.work.villain_combos;
+            work.showdowns += result.work.showdowns;
+            work.locks += result.work.locks;
+            work.drawing_dead += result.work.drawing_dead;
+        }
+
+        // Next combination, the first villain changes fastest.
+        let mut villain_index = 0;
+        while villain_index < villains.len() {
+            type_indices[villain_index] += 1;
+            if type_indices[villain_index] < villains[villain_index].types.len() {
+                break;
+            }
+            type_indices[villain_index] = 0;
+            villain_index += 1;
+        }
+        if villain_index == villains.len() {
+            break;
+        }
+    }
+    if total_probability == 0.0 {
+        return None;
+    }
+    for mixture in &mut equities {
+        mixture.equity /= total_probability;
+        mixture.win /= total_probability;
+        mixture.tie /= total_probability;
+    }
+    work.elapsed = start.elapsed();
+    Some((equities, work))
+}
+
+// Why mixture_enumerate failed, every combination of types did, the first one says why.
+pub fn mixture_enumeration_error(community_cards: Cards, hero_hand: Hand, villains: &[VillainMixture]) -> Error {
+    let ranges: Vec<_> = villains.iter().map(|villain| &villain.types[0].0).collect();
+    enumeration_error("mixture", community_cards, hero_hand, &ranges)
+}
+
+// Every round first draws the type of every villain, then a live hand of the type.
+// Deals with overlapping villain hands are redrawn as a whole,
+// the same distribution as enumerating the combinations of types.
+pub fn mixture_simulate(
+    community_cards: Cards,
+    hero_hand: Hand,
+    villains: &[VillainMixture],
+    rounds: u64,
+    rng: &mut impl Rng,
+) -> Option<EquityResult> {
+    let hero_cards = hero_hand.to_cards();
+    let known_cards = community_cards | hero_cards;
+    if villains.is_empty()
+        || rounds == 0
+        || community_cards.count() > 5
+        || (community_cards & hero_cards) != Cards::EMPTY
+    {
+        return None;
+    }
+    let start = Instant::now();
+    let live_hands: Vec<Vec<(Vec<Hand>, f64)>> = villains.iter()
+        .map(|villain| {
+            villain.types.iter()
+                .map(|(range, probability)| {
+                    let mut hands = Vec::new();
+                    range.for_each_hand(|hand| if (hand.to_cards() & known_cards) == Cards::EMPTY {
+                        hands.push(hand);
+                    });
+                    (hands, *probability)
+                })
+                .filter(|(hands, _)| !hands.is_empty())
+                .collect()
+        })
+        .collect();
+    if live_hands.iter().any(|types| types.is_empty()) {
+        return None;
+    }
+
+    let player_count = villains.len() + 1;
+    let mut hands = vec![Hand::MIN; villains.len()];
+    let mut scores = vec![Score::ZERO; player_count];
+    let mut wins = vec![0u64; player_count];
+    let mut ties = vec![0; player_count];
+    for _ in 0..rounds {
+        let mut attempts = 0;
+        let mut dealt_cards = 'deal: loop {
+            attempts += 1;
+            if attempts > MAX_DEAL_ATTEMPTS {
+                return None;
+            }
+            let mut dealt_cards = known_cards;
+            for (types, hand) in live_hands.iter().zip(hands.iter_mut()) {
+                let total: f64 = types.iter().map(|(_, probability)| probability).sum();
+                let mut target = rng.gen_range(0.0..total);
+                let mut type_hands = &types[types.len() - 1].0;
+                for (candidates, probability) in types {
+                    if target < *probability {
+                        type_hands = candidates;
+                        break;
+                    }
+                    target -= probability;
+                }
+                *hand = type_hands[rng.gen_range(0..type_hands.len())];
+                if (hand.to_cards() & dealt_cards) != Cards::EMPTY {
+                    continue 'deal;
+                }
+                dealt_cards |= hand.to_cards();
+            }
+            break dealt_cards;
+        };
+
+        let mut board = community_cards;
+        while board.count() < 5 {
+            let card: Card = rng.r#gen();
+            if dealt_cards.try_add(card) {
+                board.add(card);
+            }
+        }
+        scores[0] = (board | hero_cards).score_fast();
+        for (score, hand) in scores[1..].iter_mut().zip(&hands) {
+            *score = (board | hand.to_cards()).score_fast();
+        }
+        showdown(&scores, &mut wins, &mut ties);
+    }
+    let work = Work {
+        boards: rounds,
+        villain_combos: rounds * villains.len() as u64,
+        showdowns: rounds,
+        elapsed: start.elapsed(),
+        ..Work::default()
+    };
+    Some(EquityResult { equities: Equity::from_total_wins_ties(rounds, &wins, &ties), work })
+}
@@ -0,0 +1,243 @@
+use core::fmt;
+use std::cmp::Ordering;
+
+use rand::Rng;
+
+use crate::card::Card;
+use crate::cards::{Cards, HandRanking, Score};
+use crate::equity::Deck;
+use crate::rank::Rank;
+use crate::result::Result;
+
+pub const FRONT_SIZE: u8 = 3;
+
+pub const ROW_SIZE: u8 = 5;
+
+const SCOOP_BONUS: i32 = 3;
+
+// Open Face Chinese rows, the front row holds three cards,
+// the middle and the back row five cards each.
+#[derive(Debug, Clone, Copy)]
+pub struct OfcHand {
+    pub front: Cards,
+    pub middle: Cards,
+    pub back: Cards,
+}
+
+impl fmt::Display for OfcHand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, row) in self.rows().iter().enumerate() {
+            if index != 0 {
+                write!(f, "|")?;
+            }
+            for card in row.iter() {
+                write!(f, "{}", card)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl OfcHand {
+    // Front, middle and back separated by '|', e.g. "QhQd2c|...|...".
+    // Rows may be incomplete (or empty) for a starting placement.
+    pub fn parse(s: &str) -> Result<Self> {
+        let rows: Vec<_> = s.split('|').collect();
+        let [raw_front, raw_middle, raw_back] = rows.as_slice() else {
+            return Err(format!("invalid ofc hand '{}': expected three rows", s).into());
+        };
+        let parse = |raw: &str| if raw.is_empty() {
+            Ok(Cards::EMPTY)
+        } else {
+            raw.parse::<Cards>()
+        };
+        let hand = Self { front: parse(raw_front)?, middle: parse(raw_middle)?, back: parse(raw_back)? };
+        let distinct = (hand.front & hand.middle) == Cards::EMPTY
+            && ((hand.front | hand.middle) & hand.back) == Cards::EMPTY;
+        if !distinct
+            || hand.front.count() > FRONT_SIZE
+            || hand.middle.count() > ROW_SIZE
+            || hand.back.count() > ROW_SIZE {
+            return Err(format!("invalid ofc hand '{}'", s).into());
+        }
+        Ok(hand)
+    }
+
+    pub fn rows(&self) -> [Cards; 3] {
+        [self.front, self.middle, self.back]
+    }
+
+    pub fn cards(&self) -> Cards {
+        self.front | self.middle | self.back
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.front.count() == FRONT_SIZE
+            && self.middle.count() == ROW_SIZE
+            && self.back.count() == ROW_SIZE
+    }
+
+    // Scores of the complete rows, the front score only has the three card ranks,
+    // so it compares correctly against the five card rows.
+    pub fn scores(&self) -> [Score; 3] {
+        assert!(self.is_complete());
+        [self.front.top5().to_score(), self.middle.score_five(), self.back.score_five()]
+    }
+
+    // The back has to be at least as strong as the middle
+    // and the middle at least as strong as the front.
+    pub fn is_fouled(&self) -> bool {
+        let [front, middle, back] = self.scores();
+        front > middle || middle > back
+    }
+
+    pub fn row_royalties(&self) -> [u32; 3] {
+        if self.is_fouled() {
+            return [0; 3];
+        }
+        let [front, middle, back] = self.scores();
+        [
+            front_royalty(front.to_hand_ranking()),
+            match middle.to_hand_ranking() {
+                HandRanking::ThreeOfAKind(_) => 2,
+                ranking => 2 * back_royalty(ranking),
+            },
+            back_royalty(back.to_hand_ranking()),
+        ]
+    }
+
+    pub fn royalties(&self) -> u32 {
+        self.row_royalties().iter().sum()
+    }
+
+    // Queens or better in the front without fouling.
+    pub fn qualifies_for_fantasyland(&self) -> bool {
+        !self.is_fouled() && front_qualifies(self.front)
+    }
+}
+
+fn front_qualifies(front: Cards) -> bool {
+    match front.top5().to_score().to_hand_ranking() {
+        HandRanking::OnePair(rank) => rank >= Rank::Queen,
+        HandRanking::ThreeOfAKind(_) => true,
+        _ => false,
+    }
+}
+
+fn front_royalty(ranking: HandRanking) -> u32 {
+    match ranking {
+        HandRanking::OnePair(rank) if rank >= Rank::Six => rank.to_u32() - Rank::Five.to_u32(),
+        HandRanking::ThreeOfAKind(rank) => 10 + rank.to_u32(),
+        _ => 0,
+    }
+}
+
+fn back_royalty(ranking: HandRanking) -> u32 {
+    match ranking {
+        HandRanking::Straight => 2,
+        HandRanking::Flush => 4,
+        HandRanking::FullHouse { .. } => 6,
+        HandRanking::FourOfAKind(_) => 10,
+        HandRanking::StraightFlush => 15,
+        HandRanking::RoyalFlush => 25,
+        _ => 0,
+    }
+}
+
+// Points of player a against player b: one per row won, a scoop bonus for
+// winning all rows and the royalty difference. A fouled hand loses every row
+// and scores no royalties.
+pub fn head_to_head(a: &OfcHand, b: &OfcHand) -> i32 {
+    let royalties = a.royalties() as i32 - b.royalties() as i32;
+    let rows = match (a.is_fouled(), b.is_fouled()) {
+        (true, true) => return 0,
+        (true, false) => -(3 + SCOOP_BONUS),
+        (false, true) => 3 + SCOOP_BONUS,
+        (false, false) => {
+            let rows: i32 = a.scores().iter().zip(b.scores().iter())
+                .map(|(a, b)| match a.cmp(b) {
+                    Ordering::Greater => 1,
+                    Ordering::Equal => 0,
+                    Ordering::Less => -1,
+                })
+                .sum();
+            match rows {
+                3 => 3 + SCOOP_BONUS,
+                -3 => -(3 + SCOOP_BONUS),
+                rows => rows,
+            }
+        },
+    };
+    rows + royalties
+}
+
+// Chance to reach fantasyland from the starting placement, with the remaining
+// cards dealt at random and placed in the best possible way.
+// Placing with knowledge of all future cards makes this an upper bound.
+pub fn fantasyland_probability(
+    placement: &OfcHand,
+    dead_cards: Cards,
+    rounds: u64,
+    rng: &mut impl Rng,
+) -> Option<f64> {
+    let known = placement.cards() | dead_cards;
+    if (placement.cards() & dead_cards) != Cards::EMPTY || rounds == 0 {
+        return None;
+    }
+    let missing = FRONT_SIZE + 2 * ROW_SIZE - placement.cards().count();
+    if missing > Card::COUNT as u8 - known.count() {
+        return None;
+    }
+    let mut deck = Deck::from_cards(rng, known);
+    let mut hits = 0u64;
+    for _ in 0..rounds {
+        deck.reset();
+        let mut dealt = Vec::with_capacity(missing.into());
+        for _ in 0..missing {
+            dealt.push(deck.draw(rng).unwrap());
+        }
+        if can_reach_fantasyland(placement, &dealt) {
+            hits += 1;
+        }
+    }
+    Some(hits as f64 / rounds as f64)
+}
+
+fn can_reach_fantasyland(placement: &OfcHand, dealt: &[Card]) -> bool {
+    let front_open = usize::from(FRONT_SIZE - placement.front.count());
+    let middle_open = usize::from(ROW_SIZE - placement.middle.count());
+    for front in subsets(dealt, front_open) {
+        let front = placement.front | front;
+        if !front_qualifies(front) {
+            continue;
+        }
+        let rest: Vec<_> = dealt.iter().copied().filter(|card| !front.has(*card)).collect();
+        let rest_cards = Cards::from_slice(&rest).unwrap();
+        for middle in subsets(&rest, middle_open) {
+            let hand = OfcHand {
+                front,
+                middle: placement.middle | middle,
+                back: placement.back | (rest_cards & !middle),
+            };
+            if hand.qualifies_for_fantasyland() {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+fn subsets(cards: &[Card], size: usize) -> Vec<Cards> {
+    fn recurse(subsets: &mut Vec<Cards>, cards: &[Card], subset: Cards, size: usize) {
+        if size == 0 {
+            subsets.push(subset);
+            return;
+        }
+        for (index, card) in cards.iter().enumerate() {
+            recurse(subsets, &cards[index+1..], subset.with(*card), size - 1);
+        }
+    }
+    let mut subsets = Vec::new();
+    recurse(&mut subsets, cards, Cards::EMPTY, size);
+    subsets
+}
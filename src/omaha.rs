@@ -0,0 +1,81 @@
+use core::fmt;
+
+use rand::Rng;
+
+use crate::cards::Cards;
+use crate::equity::EquityResult;
+use crate::game_rules::{rules_enumerate, rules_simulate, Omaha};
+use crate::result::Result;
+
+// 4 for Omaha, 5 for Big O and 6 for 6-card Omaha.
+pub const MIN_HOLE_CARDS: u8 = 4;
+
+pub const MAX_HOLE_CARDS: u8 = 6;
+
+// The hole cards of an Omaha player, at showdown exactly two of them are used.
+#[derive(Debug, Clone)]
+pub struct OmahaHand {
+    cards: Cards,
+}
+
+impl fmt::Display for OmahaHand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for card in self.cards.iter() {
+            write!(f, "{}", card)?;
+        }
+        Ok(())
+    }
+}
+
+impl OmahaHand {
+    pub fn parse(s: &str) -> Result<Self> {
+        let cards = s.parse::<Cards>()?;
+        Self::from_cards(cards).ok_or_else(|| {
+            format!("invalid omaha hand '{}': expected 4 to 6 cards", s).into()
+        })
+    }
+
+    pub fn from_cards(cards: Cards) -> Option<Self> {
+        if !(MIN_HOLE_CARDS..=MAX_HOLE_CARDS).contains(&cards.count()) {
+            return None;
+        }
+        Some(Self { cards })
+    }
+
+    pub fn cards(&self) -> Cards {
+        self.cards
+    }
+}
+
+fn hand_cards(hands: &[OmahaHand]) -> Vec<Cards> {
+    hands.iter().map(|hand| hand.cards).collect()
+}
+
+// Every remaining board, all players must use exactly two hole cards.
+pub fn omaha_enumerate(community_cards: Cards, hands: &[OmahaHand]) -> Option<EquityResult> {
+    omaha_enumerate_boards(&[community_cards], hands)
+}
+
+// One or two boards (double board, e.g. bomb pots), each board wins half the pot.
+// Enumerating two boards is only possible from about the flop.
+pub fn omaha_enumerate_boards(boards: &[Cards], hands: &[OmahaHand]) -> Option<EquityResult> {
+    rules_enumerate(&Omaha { hi_lo: false }, boards, &hand_cards(hands))
+}
+
+pub fn omaha_simulate(
+    community_cards: Cards,
+    hands: &[OmahaHand],
+    rounds: u64,
+    rng: &mut impl Rng,
+) -> Option<EquityResult> {
+    omaha_simulate_boards(&[community_cards], hands, rounds, rng)
+}
+
+pub fn omaha_simulate_boards(
+    boards: &[Cards],
+    hands: &[OmahaHand],
+    rounds: u64,
+    rng: &mut impl Rng,
+) -> Option<EquityResult> {
+    rules_simulate(&Omaha { hi_lo: false }, boards, &hand_cards(hands), rounds, rng)
+}
@@ -0,0 +1,121 @@
+use core::fmt;
+
+use crate::card::Card;
+
+// A parse error pointing at the offending token of the input,
+// offset is the byte offset of the token in the input.
+// Returned as Error::Parse in the usual Result,
+// or as Error::DuplicateCard if a card is given twice.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: &'static str,
+    pub input: String,
+    pub offset: usize,
+    pub token: String,
+    pub message: String,
+    // A str instead of a String keeps the error below the size clippy warns about.
+    pub suggestion: Option<Box<str>>,
+    pub duplicate: Option<Card>,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid {} '{}': {} at offset {} ('{}')",
+            self.kind,
+            self.input,
+            self.message,
+            self.offset,
+            self.token,
+        )?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean '{}'?", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_string(), f)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    pub fn new(
+        kind: &'static str,
+        input: &str,
+        offset: usize,
+        token: &str,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind,
+            input: input.to_string(),
+            offset,
+            token: token.to_string(),
+            message: message.into(),
+            suggestion: None,
+            duplicate: None,
+        }
+    }
+
+    // The token is the second occurrence of the card.
+    pub fn duplicate(kind: &'static str, input: &str, offset: usize, token: &str, card: Card) -> Self {
+        Self {
+            duplicate: Some(card),
+            ..Self::new(kind, input, offset, token, format!("duplicate card {card}"))
+        }
+    }
+
+    // Moves the error into an enclosing input, where the parsed part starts at offset.
+    pub fn within(self, kind: &'static str, input: &str, offset: usize) -> Self {
+        Self {
+            kind,
+            input: input.to_string(),
+            offset: offset + self.offset,
+            token: self.token,
+            message: self.message,
+            suggestion: None,
+            duplicate: self.duplicate,
+        }
+    }
+
+    // Only keeps suggestions which differ from the input and parse successfully.
+    pub fn suggest(mut self, suggestion: String, valid: impl FnOnce(&str) -> bool) -> Self {
+        if suggestion != self.input && valid(&suggestion) {
+            self.suggestion = Some(suggestion.into_boxed_str());
+        }
+        self
+    }
+}
+
+// Fixes the common mistakes when writing cards:
+// '10' instead of 'T', lowercase ranks and uppercase suites.
+pub fn normalize_cards(s: &str) -> String {
+    s.replace("10", "T")
+        .chars()
+        .enumerate()
+        .map(|(index, ch)| if index % 2 == 0 {
+            ch.to_ascii_uppercase()
+        } else {
+            ch.to_ascii_lowercase()
+        })
+        .collect()
+}
+
+// Same as normalize_cards for range entries like "AKs" or "TT+".
+pub fn normalize_range_entry(s: &str) -> String {
+    s.replace("10", "T")
+        .chars()
+        .enumerate()
+        .map(|(index, ch)| if index < 2 {
+            ch.to_ascii_uppercase()
+        } else {
+            ch.to_ascii_lowercase()
+        })
+        .collect()
+}
@@ -0,0 +1,262 @@
+use std::cmp::Ordering;
+use std::sync::OnceLock;
+use std::thread;
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::card::Card;
+use crate::cards::{Cards, Score};
+use crate::dirs;
+use crate::equity::Equity;
+use crate::hand::Hand;
+use crate::hand_class::HandClass;
+use crate::result::Result;
+
+pub const CLASS_COUNT: usize = HandClass::COUNT;
+
+pub const MAX_OPPONENTS: usize = 8;
+
+const MAGIC: &[u8; 4] = b"PEQT";
+
+const VERSION: u8 = 1;
+
+const BLOB_SIZE: usize = MAGIC.len() + 1 + (CLASS_COUNT * CLASS_COUNT + MAX_OPPONENTS * CLASS_COUNT) * 2;
+
+static SHIPPED_BLOB: &[u8] = include_bytes!("../data/preflop.bin");
+
+static SHIPPED_TABLE: OnceLock<PreflopTable> = OnceLock::new();
+
+static ACTIVE_TABLE: OnceLock<PreflopTable> = OnceLock::new();
+
+// A generated table in the data directory replaces the shipped one.
+pub const TABLE_FILE: &str = "preflop.bin";
+
+static RANKINGS: [OnceLock<Vec<(Hand, f64)>>; MAX_OPPONENTS] = [const { OnceLock::new() }; MAX_OPPONENTS];
+
+// One representative hand for each of the 169 starting hand classes,
+// ordered by class_index.
+pub fn class_representatives() -> impl Iterator<Item = Hand> {
+    HandClass::all().map(HandClass::representative)
+}
+
+// AA = 0, AKs = 1, AKo = 2, ..., 22 = 168
+pub fn class_index(hand: Hand) -> usize {
+    HandClass::of_hand(hand).index()
+}
+
+// E.g. "AA", "AKs" or "AKo".
+pub fn class_name(hand: Hand) -> String {
+    HandClass::of_hand(hand).to_string()
+}
+
+pub fn class_combos(hand: Hand) -> u32 {
+    HandClass::of_hand(hand).combos()
+}
+
+pub fn class_hands(representative: Hand) -> Vec<Hand> {
+    HandClass::of_hand(representative).hands()
+}
+
+// Preflop equities of every starting hand class heads-up against every other class
+// and against 1 to 8 random hands. Stored as u16 fractions of u16::MAX.
+pub struct PreflopTable {
+    heads_up: Vec<u16>,
+    vs_random: Vec<u16>,
+}
+
+fn equity_to_u16(equity: f64) -> u16 {
+    (equity.clamp(0.0, 1.0) * f64::from(u16::MAX)).round() as u16
+}
+
+fn u16_to_equity(n: u16) -> f64 {
+    f64::from(n) / f64::from(u16::MAX)
+}
+
+impl PreflopTable {
+    pub fn shipped() -> &'static Self {
+        SHIPPED_TABLE.get_or_init(|| Self::load(SHIPPED_BLOB).unwrap())
+    }
+
+    // The generated table in the data directory (see dirs::data_dir) if there is one,
+    // otherwise the shipped table.
+    pub fn active() -> &'static Self {
+        ACTIVE_TABLE.get_or_init(|| Self::installed().unwrap_or_else(|| Self::load(SHIPPED_BLOB).unwrap()))
+    }
+
+    fn installed() -> Option<Self> {
+        let path = dirs::data_dir().ok()?.join(TABLE_FILE);
+        let blob = std::fs::read(&path).ok()?;
+        match Self::load(&blob) {
+            Ok(table) => Some(table),
+            Err(err) => {
+                eprintln!("warning: ignoring {}: {}", path.display(), err);
+                None
+            },
+        }
+    }
+
+    pub fn load(blob: &[u8]) -> Result<Self> {
+        if blob.len() != BLOB_SIZE {
+            return Err(format!(
+                "invalid preflop table: expected {} bytes, got {}",
+                BLOB_SIZE,
+                blob.len(),
+            ).into());
+        }
+        let (magic, rest) = blob.split_at(MAGIC.len());
+        if magic != MAGIC {
+            return Err("invalid preflop table: bad magic".into());
+        }
+        let (version, rest) = rest.split_at(1);
+        if version[0] != VERSION {
+            return Err(format!("invalid preflop table: unsupported version {}", version[0]).into());
+        }
+        let values: Vec<_> = rest.chunks_exact(2)
+            .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+            .collect();
+        let (heads_up, vs_random) = values.split_at(CLASS_COUNT * CLASS_COUNT);
+        Ok(Self { heads_up: heads_up.to_vec(), vs_random: vs_random.to_vec() })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut blob = Vec::with_capacity(BLOB_SIZE);
+        blob.extend_from_slice(MAGIC);
+        blob.push(VERSION);
+        for n in self.heads_up.iter().chain(self.vs_random.iter()) {
+            blob.extend_from_slice(&n.to_le_bytes());
+        }
+        assert_eq!(blob.len(), BLOB_SIZE);
+        blob
+    }
+
+    // Simulates every matchup with the given number of rounds,
+    // using all available threads.
+    pub fn generate(heads_up_rounds: u64, vs_random_rounds: u64) -> Self {
+        let representatives: Vec<_> = class_representatives().collect();
+        let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+
+        let rows: Vec<(usize, Vec<f64>)> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads).map(|thread_index| {
+                let representatives = &representatives;
+                scope.spawn(move || {
+                    let mut rng = SmallRng::seed_from_u64(thread_index as u64);
+                    (thread_index..CLASS_COUNT).step_by(threads).map(|hero| {
+                        let row = (hero..CLASS_COUNT).map(|villain| simulate_classes(
+                            representatives[hero],
+                            representatives[villain],
+                            heads_up_rounds,
+                            &mut rng,
+                        )).collect();
+                        (hero, row)
+                    }).collect::<Vec<_>>()
+                })
+            }).collect();
+            handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+        });
+        let mut heads_up = vec![0; CLASS_COUNT * CLASS_COUNT];
+        for (hero, row) in rows {
+            for (offset, equity) in row.into_iter().enumerate() {
+                let villain = hero + offset;
+                heads_up[hero*CLASS_COUNT + villain] = equity_to_u16(equity);
+                heads_up[villain*CLASS_COUNT + hero] = equity_to_u16(1.0 - equity);
+            }
+        }
+
+        let columns: Vec<(usize, Vec<f64>)> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads).map(|thread_index| {
+                let representatives = &representatives;
+                scope.spawn(move || {
+                    let mut rng = SmallRng::seed_from_u64((threads + thread_index) as u64);
+                    (thread_index..MAX_OPPONENTS).step_by(threads).map(|opponents_index| {
+                        let column = representatives.iter().map(|hand| {
+                            Equity::simulate(
+                                Cards::EMPTY,
+                                *hand,
+                                opponents_index + 1,
+                                vs_random_rounds,
+                                &mut rng,
+                            ).unwrap().equities[0].equity_percent()
+                        }).collect();
+                        (opponents_index, column)
+                    }).collect::<Vec<_>>()
+                })
+            }).collect();
+            handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+        });
+        let mut vs_random = vec![0; MAX_OPPONENTS * CLASS_COUNT];
+        for (opponents_index, column) in columns {
+            for (class, equity) in column.into_iter().enumerate() {
+                vs_random[opponents_index*CLASS_COUNT + class] = equity_to_u16(equity);
+            }
+        }
+
+        Self { heads_up, vs_random }
+    }
+
+    pub fn heads_up(&self, hero: Hand, villain: Hand) -> f64 {
+        u16_to_equity(self.heads_up[class_index(hero)*CLASS_COUNT + class_index(villain)])
+    }
+
+    pub fn vs_random(&self, hero: Hand, opponents: usize) -> f64 {
+        assert!((1..=MAX_OPPONENTS).contains(&opponents));
+        u16_to_equity(self.vs_random[(opponents-1)*CLASS_COUNT + class_index(hero)])
+    }
+}
+
+fn simulate_classes(hero: Hand, villain: Hand, rounds: u64, rng: &mut impl Rng) -> f64 {
+    let hero_hands = class_hands(hero);
+    let villain_hands = class_hands(villain);
+    let mut pot_share = 0.0;
+    for _ in 0..rounds {
+        let (hero_cards, villain_cards) = loop {
+            let hero_cards = hero_hands[rng.gen_range(0..hero_hands.len())].to_cards();
+            let villain_cards = villain_hands[rng.gen_range(0..villain_hands.len())].to_cards();
+            if (hero_cards & villain_cards) == Cards::EMPTY {
+                break (hero_cards, villain_cards);
+            }
+        };
+        let mut dealt_cards = hero_cards | villain_cards;
+        let mut community_cards = Cards::EMPTY;
+        while community_cards.count() < 5 {
+            let card: Card = rng.r#gen();
+            if dealt_cards.try_add(card) {
+                community_cards.add(card);
+            }
+        }
+        let hero_score: Score = (community_cards | hero_cards).score_fast();
+        let villain_score = (community_cards | villain_cards).score_fast();
+        pot_share += match hero_score.cmp(&villain_score) {
+            Ordering::Greater => 1.0,
+            Ordering::Equal => 0.5,
+            Ordering::Less => 0.0,
+        };
+    }
+    pot_share / rounds as f64
+}
+
+// Heap memory in bytes of the tables and rankings loaded so far.
+pub fn loaded_memory() -> usize {
+    let tables: usize = [&SHIPPED_TABLE, &ACTIVE_TABLE].into_iter()
+        .filter_map(OnceLock::get)
+        .map(|table| (table.heads_up.capacity() + table.vs_random.capacity()) * size_of::<u16>())
+        .sum();
+    let rankings: usize = RANKINGS.iter()
+        .filter_map(OnceLock::get)
+        .map(|ranking| ranking.capacity() * size_of::<(Hand, f64)>())
+        .sum();
+    tables + rankings
+}
+
+// The starting hand classes ordered by their preflop equity
+// against the given number of random opponents, best first.
+pub fn ranking(opponents: usize) -> &'static [(Hand, f64)] {
+    assert!((1..=MAX_OPPONENTS).contains(&opponents));
+    RANKINGS[opponents - 1].get_or_init(|| {
+        let table = PreflopTable::active();
+        let mut ranking: Vec<_> = class_representatives()
+            .map(|hand| (hand, table.vs_random(hand, opponents)))
+            .collect();
+        ranking.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+        ranking
+    })
+}
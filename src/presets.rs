@@ -0,0 +1,186 @@
+use core::fmt;
+
+use crate::error::Error;
+use crate::result::Result;
+use crate::weighted_range::WeightedRange;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Position {
+    UnderTheGun,
+    Hijack,
+    Cutoff,
+    Button,
+    SmallBlind,
+    BigBlind,
+}
+
+use Position::*;
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let position = match *self {
+            UnderTheGun => "utg",
+            Hijack => "hj",
+            Cutoff => "co",
+            Button => "btn",
+            SmallBlind => "sb",
+            BigBlind => "bb",
+        };
+        write!(f, "{}", position)
+    }
+}
+
+impl Position {
+    pub const POSITIONS: [Position; 6] = [
+        UnderTheGun,
+        Hijack,
+        Cutoff,
+        Button,
+        SmallBlind,
+        BigBlind,
+    ];
+}
+
+impl std::str::FromStr for Position {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::POSITIONS.iter()
+            .copied()
+            .find(|position| position.to_string() == s)
+            .ok_or_else(|| format!("invalid position '{s}'").into())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Open,
+    Flat,
+    ThreeBet,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match *self {
+            Action::Open => "open",
+            Action::Flat => "flat",
+            Action::ThreeBet => "3bet",
+        };
+        write!(f, "{}", action)
+    }
+}
+
+impl Action {
+    pub const ACTIONS: [Action; 3] = [Action::Open, Action::Flat, Action::ThreeBet];
+}
+
+impl std::str::FromStr for Action {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ACTIONS.iter()
+            .copied()
+            .find(|action| action.to_string() == s)
+            .ok_or_else(|| format!("invalid action '{s}'").into())
+    }
+}
+
+// Approximations of common 6-max 100bb charts.
+// Flat and 3-bet ranges are versus a single open from an earlier position.
+const PRESETS: &[(Position, Action, &str)] = &[
+    (
+        UnderTheGun,
+        Action::Open,
+        "55+,55:0.5,A2s+,KTs+,QTs+,JTs,T9s,98s:0.5,87s:0.25,AJo+,KQo",
+    ),
+    (
+        Hijack,
+        Action::Open,
+        "33+,33:0.5,A2s+,K8s+,Q9s+,J9s+,T9s,98s,87s:0.5,76s:0.5,ATo+,KJo+,QJo:0.5",
+    ),
+    (
+        Cutoff,
+        Action::Open,
+        "22+,A2s+,K6s+,Q8s+,J8s+,T8s+,97s+,86s+,75s+,65s,54s:0.5,A7o+,A7o:0.5,K9o+,K9o:0.5,QTo+,JTo",
+    ),
+    (
+        Button,
+        Action::Open,
+        "22+,A2s+,K2s+,Q4s+,J6s+,T6s+,96s+,85s+,74s+,63s+,53s+,43s,A2o+,K7o+,K7o:0.5,Q8o+,Q8o:0.5,J9o+,T8o+,98o,87o:0.5",
+    ),
+    (
+        SmallBlind,
+        Action::Open,
+        "22+,A2s+,K2s+,Q2s+,J4s+,T6s+,96s+,85s+,74s+,64s+,53s+,43s,A2o+,K7o+,Q8o+,J8o+,T8o+,98o,87o:0.5,76o:0.25",
+    ),
+    (
+        Hijack,
+        Action::Flat,
+        "88+,88:0.5,JJ:0.5,QQ:0,KK:0,AA:0,AJs,AQs:0.5,KQs:0.75,QJs:0.5,JTs:0.5",
+    ),
+    (
+        Hijack,
+        Action::ThreeBet,
+        "JJ+,JJ:0.5,AKs,AKo,AQs:0.5,A5s:0.5,A4s:0.5,KQs:0.25",
+    ),
+    (
+        Cutoff,
+        Action::Flat,
+        "77+,77:0.5,JJ:0,QQ:0,KK:0,AA:0,AJs,ATs:0.5,AQs:0.5,KJs+,KJs:0.5,QJs,JTs,T9s:0.5,AQo:0.5",
+    ),
+    (
+        Cutoff,
+        Action::ThreeBet,
+        "TT+,TT:0.25,JJ:0.5,AKs,AKo,AQs:0.5,A5s,A4s:0.5,KQs:0.25,AQo:0.25",
+    ),
+    (
+        Button,
+        Action::Flat,
+        "55+,55:0.5,66:0.5,JJ:0.5,QQ:0,KK:0,AA:0,A9s+,A9s:0.5,AQs:0.5,AKs:0,KTs+,KTs:0.5,KQs:0.5,\
+            QTs+,QTs:0.5,JTs,T9s,98s,87s:0.5,AJo+,AJo:0.5,AQo:0.5,AKo:0,KQo:0.5",
+    ),
+    (
+        Button,
+        Action::ThreeBet,
+        "TT+,TT:0.5,JJ:0.5,AKs,AKo,AQs:0.5,A3s+,A3s:0.5,A6s:0,A7s:0,A8s:0,A9s:0,ATs:0,AJs:0,\
+            KQs:0.5,K9s:0.25,76s:0.25,AQo:0.5,AJo:0.25",
+    ),
+    (
+        SmallBlind,
+        Action::Flat,
+        "77+,77:0.5,88:0.5,99:0.5,TT:0,JJ:0,QQ:0,KK:0,AA:0,AJs:0.5,KQs:0.5,QJs:0.5,JTs:0.5,T9s:0.5",
+    ),
+    (
+        SmallBlind,
+        Action::ThreeBet,
+        "99+,99:0.5,AQs+,AKo,AJs:0.5,ATs:0.5,A5s,A4s,KJs+,KJs:0.5,KQs:0.5,AQo:0.5",
+    ),
+    (
+        BigBlind,
+        Action::Flat,
+        "22+,JJ:0.75,QQ:0.25,KK:0,AA:0,A2s+,AQs:0.75,AKs:0.25,K2s+,Q4s+,J6s+,T6s+,96s+,85s+,74s+,63s+,\
+            53s+,43s,A2o+,A2o:0.5,A3o:0.5,A4o:0.5,A5o:0.5,A6o:0.5,AKo:0.25,K9o+,Q9o+,J9o+,T9o,98o:0.5",
+    ),
+    (
+        BigBlind,
+        Action::ThreeBet,
+        "QQ+,JJ:0.25,AKs,AKo:0.75,AQs:0.25,A5s:0.5,A4s:0.5,KQs:0.25,K9s:0.25,76s:0.25,65s:0.25",
+    ),
+];
+
+pub fn preset(position: Position, action: Action) -> Option<WeightedRange> {
+    PRESETS.iter()
+        .find(|(p, a, _)| *p == position && *a == action)
+        .map(|(_, _, raw)| WeightedRange::parse(raw).unwrap())
+}
+
+// Parses preset names like "btn-open" or "bb-3bet".
+pub fn parse_preset(name: &str) -> Result<WeightedRange> {
+    let Some((raw_position, raw_action)) = name.split_once('-') else {
+        return Err(format!("invalid preset '{name}': expected <position>-<action>").into());
+    };
+    let position = raw_position.parse::<Position>()?;
+    let action = raw_action.parse::<Action>()?;
+    preset(position, action)
+        .ok_or_else(|| format!("invalid preset '{name}': no range for {position} {action}").into())
+}
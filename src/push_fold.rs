@@ -0,0 +1,124 @@
+use core::fmt;
+
+use crate::hand::Hand;
+use crate::preflop::{
+    class_combos, class_hands, class_name, class_representatives, PreflopTable, CLASS_COUNT,
+};
+use crate::result::Result;
+
+const SMALL_BLIND: f64 = 0.5;
+
+const BIG_BLIND: f64 = 1.0;
+
+// Heads-up (or blind versus blind) push/fold: the small blind shoves or folds,
+// the big blind calls or folds. Frequencies are per starting hand class,
+// ordered by preflop::class_index.
+pub struct PushFold {
+    pub stack: f64,
+    pub iterations: u32,
+    pub push: Vec<f64>,
+    pub call: Vec<f64>,
+}
+
+impl fmt::Display for PushFold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "push: {:2.2}% {}", self.push_percent() * 100.0, range_string(&self.push))?;
+        write!(f, "call: {:2.2}% {}", self.call_percent() * 100.0, range_string(&self.call))
+    }
+}
+
+fn range_string(frequencies: &[f64]) -> String {
+    class_representatives()
+        .zip(frequencies)
+        .filter(|(_, frequency)| **frequency >= 0.5)
+        .map(|(hand, _)| class_name(hand))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn combo_share(frequencies: &[f64]) -> f64 {
+    let combos: f64 = class_representatives()
+        .zip(frequencies)
+        .map(|(hand, frequency)| f64::from(class_combos(hand)) * frequency)
+        .sum();
+    combos / 1326.0
+}
+
+impl PushFold {
+    // Fictitious play: both players repeatedly best respond to the average strategy
+    // of the other, which converges to a Nash equilibrium of this zero-sum game.
+    // The equities come from the shipped preflop table, card removal
+    // between the two hands is respected by weighting the class matchups
+    // with their number of possible combo pairs.
+    pub fn solve(stack: f64, iterations: u32) -> Result<Self> {
+        if !(stack.is_finite() && stack >= BIG_BLIND) || iterations == 0 {
+            return Err("push fold failed: expected stack of at least one big blind".into());
+        }
+        let table = PreflopTable::active();
+        let classes: Vec<Hand> = class_representatives().collect();
+        let hands: Vec<_> = classes.iter().map(|hand| class_hands(*hand)).collect();
+        let mut weights = vec![0.0; CLASS_COUNT * CLASS_COUNT];
+        let mut equities = vec![0.0; CLASS_COUNT * CLASS_COUNT];
+        for (a, a_hands) in hands.iter().enumerate() {
+            for (b, b_hands) in hands.iter().enumerate() {
+                let pairs = a_hands.iter()
+                    .flat_map(|a| b_hands.iter().map(move |b| (a, b)))
+                    .filter(|(a, b)| (a.to_cards() & b.to_cards()).count() == 0)
+                    .count();
+                weights[a * CLASS_COUNT + b] = pairs as f64;
+                equities[a * CLASS_COUNT + b] = table.heads_up(classes[a], classes[b]);
+            }
+        }
+
+        // Result of a player in big blinds when the pot goes to showdown.
+        let showdown = |equity: f64| equity * 2.0 * stack - stack;
+        let mut push = vec![1.0; CLASS_COUNT];
+        let mut call = vec![1.0; CLASS_COUNT];
+        for iteration in 1..=iterations {
+            let step = 1.0 / f64::from(iteration + 1);
+            let mut call_response = vec![0.0; CLASS_COUNT];
+            for (b, response) in call_response.iter_mut().enumerate() {
+                let mut weight = 0.0;
+                let mut ev = 0.0;
+                for a in 0..CLASS_COUNT {
+                    let w = weights[a * CLASS_COUNT + b] * push[a];
+                    weight += w;
+                    ev += w * showdown(equities[b * CLASS_COUNT + a]);
+                }
+                if weight > 0.0 && ev / weight > -BIG_BLIND {
+                    *response = 1.0;
+                }
+            }
+            let mut push_response = vec![0.0; CLASS_COUNT];
+            for (a, response) in push_response.iter_mut().enumerate() {
+                let mut weight = 0.0;
+                let mut ev = 0.0;
+                for b in 0..CLASS_COUNT {
+                    let w = weights[a * CLASS_COUNT + b];
+                    let called = showdown(equities[a * CLASS_COUNT + b]);
+                    weight += w;
+                    ev += w * (call[b] * called + (1.0 - call[b]) * BIG_BLIND);
+                }
+                if ev / weight > -SMALL_BLIND {
+                    *response = 1.0;
+                }
+            }
+            for (frequency, response) in push.iter_mut().zip(push_response) {
+                *frequency += step * (response - *frequency);
+            }
+            for (frequency, response) in call.iter_mut().zip(call_response) {
+                *frequency += step * (response - *frequency);
+            }
+        }
+        Ok(Self { stack, iterations, push, call })
+    }
+
+    // Share of all 1326 combos.
+    pub fn push_percent(&self) -> f64 {
+        combo_share(&self.push)
+    }
+
+    pub fn call_percent(&self) -> f64 {
+        combo_share(&self.call)
+    }
+}
@@ -1,10 +1,15 @@
 use core::fmt;
 use std::cmp::{max, min};
 use std::collections::HashSet;
+use std::sync::Arc;
 
 use crate::card::Card;
 use crate::cards::{Cards, CardsByRank};
+use crate::error::Error;
 use crate::hand::Hand;
+use crate::hand_class::HandClass;
+use crate::parse_error::{normalize_range_entry, ParseError};
+use crate::preflop;
 use crate::rank::Rank;
 use crate::result::Result;
 use crate::suite::Suite;
@@ -29,14 +34,26 @@ impl fmt::Display for RangeEntry {
     }
 }
 
-impl RangeEntry {
-    fn from_hand(hand: Hand) -> Self {
+impl From<HandClass> for RangeEntry {
+    fn from(class: HandClass) -> Self {
         RangeEntry {
-            high: hand.high().rank(),
-            low: hand.low().rank(),
-            suited: hand.suited(),
+            high: class.high(),
+            low: class.low(),
+            suited: class.suited(),
         }
     }
+}
+
+impl From<RangeEntry> for HandClass {
+    fn from(entry: RangeEntry) -> Self {
+        HandClass::try_new(entry.high, entry.low, entry.suited).unwrap()
+    }
+}
+
+impl RangeEntry {
+    fn from_hand(hand: Hand) -> Self {
+        HandClass::of_hand(hand).into()
+    }
 
     fn first_second(self) -> (Rank, Rank) {
         debug_assert!(self.high >= self.low);
@@ -48,6 +65,153 @@ impl RangeEntry {
     }
 }
 
+pub trait HandRange {
+    fn for_each_hand(&self, f: impl FnMut(Hand));
+
+    fn is_empty(&self) -> bool;
+
+    fn count_cards(&self) -> u32 {
+        let mut count = 0u32;
+        self.for_each_hand(|_| count += 2);
+        count
+    }
+}
+
+impl HandRange for RangeTable {
+    fn for_each_hand(&self, f: impl FnMut(Hand)) {
+        RangeTable::for_each_hand(self, f)
+    }
+
+    fn is_empty(&self) -> bool {
+        RangeTable::is_empty(self)
+    }
+}
+
+impl HandRange for Hand {
+    fn for_each_hand(&self, mut f: impl FnMut(Hand)) {
+        f(*self)
+    }
+
+    fn is_empty(&self) -> bool {
+        false
+    }
+}
+
+// Combos expanded once, e.g. see EquitySession.
+impl HandRange for [Hand] {
+    fn for_each_hand(&self, f: impl FnMut(Hand)) {
+        self.iter().copied().for_each(f)
+    }
+
+    fn is_empty(&self) -> bool {
+        <[Hand]>::is_empty(self)
+    }
+
+    fn count_cards(&self) -> u32 {
+        2 * u32::try_from(self.len()).unwrap()
+    }
+}
+
+impl HandRange for Vec<Hand> {
+    fn for_each_hand(&self, f: impl FnMut(Hand)) {
+        self.as_slice().for_each_hand(f)
+    }
+
+    fn is_empty(&self) -> bool {
+        Vec::is_empty(self)
+    }
+
+    fn count_cards(&self) -> u32 {
+        self.as_slice().count_cards()
+    }
+}
+
+impl <T: HandRange + ?Sized> HandRange for &T {
+    fn for_each_hand(&self, f: impl FnMut(Hand)) {
+        (**self).for_each_hand(f)
+    }
+
+    fn is_empty(&self) -> bool {
+        (**self).is_empty()
+    }
+}
+
+impl <T: HandRange + ?Sized> HandRange for Arc<T> {
+    fn for_each_hand(&self, f: impl FnMut(Hand)) {
+        (**self).for_each_hand(f)
+    }
+
+    fn is_empty(&self) -> bool {
+        (**self).is_empty()
+    }
+}
+
+// A player whose hand is fully known, only known to be in a range,
+// or partially known: one card exposed, the other from the range (any card without one).
+#[derive(Clone)]
+pub enum Opponent {
+    Hand(Hand),
+    Range(RangeTable),
+    Partial(Card, Option<RangeTable>),
+}
+
+impl Opponent {
+    // A hand, a range, or an exposed card followed by '?' and an optional range, e.g. Kh? or Kh?AK,KQs.
+    pub fn parse(s: &str) -> Result<Self> {
+        if let Some((card, rest)) = split_exposed_card(s)? {
+            let range = if rest.is_empty() {
+                None
+            } else {
+                Some(RangeTable::parse(rest)?)
+            };
+            return Ok(Opponent::Partial(card, range));
+        }
+        match s.parse::<Hand>() {
+            Ok(hand) => Ok(Opponent::Hand(hand)),
+            Err(_) => Ok(Opponent::Range(RangeTable::parse(s)?)),
+        }
+    }
+}
+
+// Splits an exposed card prefix (e.g. "Kh?") from the rest, None without one.
+pub fn split_exposed_card(s: &str) -> Result<Option<(Card, &str)>> {
+    match s.split_once('?') {
+        Some((raw_card, rest)) => Ok(Some((raw_card.parse::<Card>()?, rest))),
+        None => Ok(None),
+    }
+}
+
+impl HandRange for Opponent {
+    fn for_each_hand(&self, f: impl FnMut(Hand)) {
+        match self {
+            Opponent::Hand(hand) => HandRange::for_each_hand(hand, f),
+            Opponent::Range(range) => HandRange::for_each_hand(range, f),
+            Opponent::Partial(card, range) => for_each_partial_hand(*card, range.as_ref(), f),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            Opponent::Hand(hand) => HandRange::is_empty(hand),
+            Opponent::Range(range) => HandRange::is_empty(range),
+            Opponent::Partial(card, range) => {
+                let mut empty = true;
+                for_each_partial_hand(*card, range.as_ref(), |_| empty = false);
+                empty
+            },
+        }
+    }
+}
+
+fn for_each_partial_hand(card: Card, range: Option<&RangeTable>, mut f: impl FnMut(Hand)) {
+    for other in Card::all().filter(|other| *other != card) {
+        let hand = Hand::of_two_cards(card, other);
+        if range.is_none_or(|range| range.contains(hand)) {
+            f(hand);
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct RangeTable {
     table: [CardsByRank; Rank::COUNT],
@@ -55,8 +219,8 @@ pub struct RangeTable {
 
 impl fmt::Display for RangeTable {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for row in Rank::RANKS.iter().rev().copied() {
-            let mut iter = Rank::RANKS.iter().rev().copied().peekable();
+        for row in Rank::descending() {
+            let mut iter = Rank::descending().peekable();
             while let Some(column) = iter.next() {
                 let entry = RangeEntry {
                     high: max(row, column),
@@ -73,12 +237,20 @@ impl fmt::Display for RangeTable {
                     write!(f, " ")?;
                 }
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
         Ok(())
     }
 }
 
+impl std::str::FromStr for RangeTable {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
 impl RangeTable {
     pub fn empty() -> Self {
         Self { table: [CardsByRank::EMPTY; Rank::COUNT] }
@@ -86,8 +258,8 @@ impl RangeTable {
 
     pub fn full() -> Self {
         let mut range = Self::empty();
-        for row in Rank::RANKS.iter().rev().copied() {
-            for column in Rank::RANKS.iter().rev().copied() {
+        for row in Rank::descending() {
+            for column in Rank::descending() {
                 let high = max(row, column);
                 let low = min(row, column);
                 let suited = column < row;
@@ -97,13 +269,54 @@ impl RangeTable {
         range
     }
 
+    // The best percent of all starting hands by preflop equity heads-up.
+    pub fn top_percent(percent: f64) -> Self {
+        Self::top_percent_vs(percent, 1)
+    }
+
+    pub fn top_percent_vs(percent: f64, opponents: usize) -> Self {
+        let total_combos = 52 * 51 / 2;
+        let target = percent.clamp(0.0, 100.0) / 100.0 * f64::from(total_combos);
+        let mut range = Self::empty();
+        let mut combos = 0;
+        for (hand, _) in preflop::ranking(opponents) {
+            if f64::from(combos) >= target {
+                break;
+            }
+            range.add(RangeEntry::from_hand(*hand));
+            combos += preflop::class_combos(*hand);
+        }
+        range
+    }
+
+    // Errors are a boxed ParseError pointing at the invalid entry.
     pub fn parse(range_str: &str) -> Result<Self> {
+        let offset = range_str.len() - range_str.trim_start().len();
         let range_str = range_str.trim();
+        Self::parse_entries(range_str)
+            .map_err(|err| {
+                let suggestion = range_str.split(',')
+                    .map(normalize_range_entry)
+                    .collect::<Vec<_>>()
+                    .join(",");
+                err.suggest(suggestion, |suggestion| Self::parse_entries(suggestion).is_ok())
+            })
+            .map_err(|err| ParseError { offset: offset + err.offset, ..err }.into())
+    }
+
+    fn parse_entries(range_str: &str) -> std::result::Result<Self, ParseError> {
         if range_str == "full" {
             return Ok(Self::full());
         }
+        if let Some(raw_percent) = range_str.strip_suffix('%') {
+            return match raw_percent.parse::<f64>() {
+                Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(Self::top_percent(percent)),
+                _ => Err(ParseError::new("range", range_str, 0, range_str, "invalid percentage")),
+            };
+        }
 
         let mut range = Self::empty();
+        let mut offset = 0;
         for def in range_str.split(',') {
             let result = match def.as_bytes() {
                 [pair_a, pair_b] if pair_a == pair_b => range.parse_pair(*pair_a),
@@ -116,13 +329,15 @@ impl RangeTable {
             };
 
             if let Err(err) = result {
-                return Err(format!(
-                    "invalid range '{}': invalid entry '{}': {}",
+                return Err(ParseError::new(
+                    "range",
                     range_str,
+                    offset,
                     def,
-                    err,
-                ).into())
+                    format!("invalid entry: {}", err),
+                ));
             }
+            offset += def.len() + 1;
         }
 
         Ok(range)
@@ -195,6 +410,27 @@ impl RangeTable {
         self.contains_entry(RangeEntry::from_hand(hand))
     }
 
+    pub fn contains_class(&self, class: HandClass) -> bool {
+        self.contains_entry(class.into())
+    }
+
+    pub fn add_class(&mut self, class: HandClass) {
+        self.add(class.into())
+    }
+
+    // The classes of the range ordered by index.
+    pub fn classes(&self) -> impl Iterator<Item = HandClass> + '_ {
+        HandClass::all().filter(|class| self.contains_class(*class))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut range = self.clone();
+        for (row, other_row) in range.table.iter_mut().zip(other.table) {
+            *row |= other_row;
+        }
+        range
+    }
+
     pub fn is_empty(&self) -> bool {
         self.table.iter().all(|row| *row == CardsByRank::EMPTY)
     }
@@ -220,7 +456,7 @@ impl RangeTable {
 
     pub fn to_set(&self) -> HashSet<Hand> {
         let mut hands = HashSet::new();
-        for high in Rank::RANKS.iter().rev().copied() {
+        for high in Rank::descending() {
             for low in Rank::RANKS[..=high.to_usize()].iter().rev().copied() {
                 for suite_a in Suite::SUITES {
                     for suite_b in Suite::SUITES {
@@ -0,0 +1,157 @@
+use core::fmt;
+
+use rand::{rngs::SmallRng, SeedableRng};
+
+use crate::cards::Cards;
+use crate::daemon::check_schema_version;
+use crate::equity::{enumeration_error, Equity, EquityResult};
+use crate::error::Error;
+use crate::hand::Hand;
+use crate::json::JsonValue;
+use crate::range::RangeTable;
+use crate::result::Result;
+use crate::weighted_range::WeightedRange;
+
+// Two-sided 95% level.
+const SIGNIFICANT_Z: f64 = 1.96;
+
+// Hero equity with the first villain playing range a or range b,
+// the other villains unchanged, or hero equity of two separate runs (see compare_runs).
+#[derive(Debug, Clone, Copy)]
+pub struct RangeComparison {
+    pub equity_a: f64,
+    pub equity_b: f64,
+    // Standard error of the difference, None if enumerated exactly.
+    pub std_error: Option<f64>,
+}
+
+impl fmt::Display for RangeComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "equity a={:2.2} b={:2.2} difference={:+.2}",
+            self.equity_a * 100.0,
+            self.equity_b * 100.0,
+            self.difference() * 100.0,
+        )?;
+        if let (Some(std_error), Some(z)) = (self.std_error, self.z()) {
+            write!(
+                f,
+                " std-error={:.2} z={:.2} significant={}",
+                std_error * 100.0,
+                z,
+                self.is_significant(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl RangeComparison {
+    // Equity b minus equity a.
+    pub fn difference(&self) -> f64 {
+        self.equity_b - self.equity_a
+    }
+
+    pub fn z(&self) -> Option<f64> {
+        let std_error = self.std_error?;
+        if std_error == 0.0 {
+            None
+        } else {
+            Some(self.difference() / std_error)
+        }
+    }
+
+    // Exact differences are always significant if not zero.
+    pub fn is_significant(&self) -> bool {
+        match self.std_error {
+            None => self.difference() != 0.0,
+            Some(_) => self.z().is_some_and(|z| z.abs() >= SIGNIFICANT_Z),
+        }
+    }
+}
+
+// Enumerates both assumptions exactly or simulates each with the given rounds.
+// The simulations are independent, the standard error of a single simulation
+// is bounded by the one of a coin flip with the equity as probability
+// (a round's share of the pot is between 0 and 1).
+pub fn compare_ranges(
+    community_cards: Cards,
+    hero_hand: Hand,
+    range_a: &RangeTable,
+    range_b: &RangeTable,
+    other_ranges: &[RangeTable],
+    rounds: Option<u64>,
+) -> Result<RangeComparison> {
+    let run = |range: &RangeTable| -> Result<EquityResult> {
+        let ranges: Vec<_> = std::iter::once(range).chain(other_ranges).collect();
+        match rounds {
+            None => Equity::enumerate(community_cards, hero_hand, &ranges)
+                .ok_or_else(|| enumeration_error("compare", community_cards, hero_hand, &ranges)),
+            Some(rounds) => {
+                let weighted: Vec<_> = ranges.iter().map(|range| WeightedRange::from_range(range)).collect();
+                Equity::simulate_weighted(community_cards, hero_hand, &weighted, rounds, &mut SmallRng::from_entropy())
+                    .ok_or_else(|| Error::usage("compare failed: invalid input"))
+            },
+        }
+    };
+    let (a, b) = (run(range_a)?, run(range_b)?);
+    let equity_a = a.equities[0].equity_percent();
+    let equity_b = b.equities[0].equity_percent();
+    let std_error = rounds.map(|_| {
+        (a.equities[0].std_error().powi(2) + b.equities[0].std_error().powi(2)).sqrt()
+    });
+    Ok(RangeComparison { equity_a, equity_b, std_error })
+}
+
+// Hero equity of a finished run, read back from its JSON result.
+#[derive(Debug, Clone, Copy)]
+pub struct RunSummary {
+    pub equity: f64,
+    pub samples: u64,
+    // None if enumerated exactly.
+    pub std_error: Option<f64>,
+}
+
+impl RunSummary {
+    // A result printed by simulate --json, a daemon response or a journal line.
+    // Simulations with convergence use its standard error, other simulations
+    // the bound of compare_ranges with every board a sample.
+    pub fn parse_json(s: &str) -> Result<Self> {
+        let value = JsonValue::parse(s)?;
+        let result = value.get("result")?;
+        check_schema_version(result)?;
+        let equity = result.get("equities")?.as_array()?
+            .first()
+            .ok_or("run failed: expected equities")?
+            .get("equity")?
+            .as_f64()? / 100.0;
+        let boards = result.get("boards")?.as_f64()? as u64;
+        let exact = value.get("method").and_then(|method| method.as_str())
+            .is_ok_and(|method| method.starts_with("enumerate"));
+        if exact {
+            return Ok(Self { equity, samples: boards, std_error: None });
+        }
+        match result.get("convergence") {
+            Ok(convergence) => Ok(Self {
+                equity,
+                samples: convergence.get("samples")?.as_f64()? as u64,
+                std_error: Some(convergence.get("std_error")?.as_f64()? / 100.0),
+            }),
+            Err(_) => Ok(Self {
+                equity,
+                samples: boards,
+                std_error: Some((equity * (1.0 - equity) / boards.max(1) as f64).sqrt()),
+            }),
+        }
+    }
+}
+
+// z-test of two independent runs, e.g. of two bet lines or two versions of the engine.
+pub fn compare_runs(a: RunSummary, b: RunSummary) -> RangeComparison {
+    let std_error = match (a.std_error, b.std_error) {
+        (None, None) => None,
+        (a, b) => Some((a.unwrap_or(0.0).powi(2) + b.unwrap_or(0.0).powi(2)).sqrt()),
+    };
+    RangeComparison { equity_a: a.equity, equity_b: b.equity, std_error }
+}
@@ -0,0 +1,119 @@
+use core::fmt;
+
+use crate::card::Card;
+use crate::cards::{Cards, HandRanking, Score};
+use crate::equity::boards_of_size;
+use crate::hand::Hand;
+use crate::range::HandRange;
+use crate::result::Result;
+
+// Composition of a range on one street, averaged over all runouts
+// and counted per live combo:
+// nuts: no other hand beats it on the board,
+// strong: two pair or better,
+// marginal: one pair,
+// air: everything else.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StreetDistribution {
+    pub community_cards_count: u8,
+    pub total: u64,
+    pub nuts: u64,
+    pub strong: u64,
+    pub marginal: u64,
+    pub air: u64,
+}
+
+impl fmt::Display for StreetDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let street = match self.community_cards_count {
+            3 => "flop",
+            4 => "turn",
+            _ => "river",
+        };
+        write!(
+            f,
+            "{}: nuts={:2.2} strong={:2.2} marginal={:2.2} air={:2.2}",
+            street,
+            self.percent(self.nuts),
+            self.percent(self.strong),
+            self.percent(self.marginal),
+            self.percent(self.air),
+        )
+    }
+}
+
+impl StreetDistribution {
+    pub fn percent(&self, count: u64) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            count as f64 / self.total as f64 * 100.0
+        }
+    }
+
+    fn add(&mut self, score: Score, nut_score: Score) {
+        self.total += 1;
+        if score == nut_score {
+            self.nuts += 1;
+            return;
+        }
+        match score.to_hand_ranking() {
+            HandRanking::HighCard => self.air += 1,
+            HandRanking::OnePair(_) => self.marginal += 1,
+            _ => self.strong += 1,
+        }
+    }
+}
+
+// How the composition of a range evolves from the given community cards
+// to the river, one entry per street.
+pub fn range_distribution(
+    community_cards: Cards,
+    range: &impl HandRange,
+) -> Result<Vec<StreetDistribution>> {
+    if !(3..=5).contains(&community_cards.count()) {
+        return Err("range distribution failed: expected 3 to 5 community cards".into());
+    }
+    let mut hands = Vec::new();
+    range.for_each_hand(|hand| if (hand.to_cards() & community_cards) == Cards::EMPTY {
+        hands.push(hand);
+    });
+    if hands.is_empty() {
+        return Err("range distribution failed: no live combos".into());
+    }
+
+    let mut streets = Vec::new();
+    for count in community_cards.count()..=5 {
+        let mut street = StreetDistribution {
+            community_cards_count: count,
+            ..StreetDistribution::default()
+        };
+        for board in boards_of_size(community_cards, Cards::EMPTY, count) {
+            let nut_score = nut_score(board);
+            for hand in hands.iter().copied() {
+                if (hand.to_cards() & board) != Cards::EMPTY {
+                    continue;
+                }
+                street.add(score(board, hand), nut_score);
+            }
+        }
+        streets.push(street);
+    }
+    Ok(streets)
+}
+
+fn score(board: Cards, hand: Hand) -> Score {
+    (board | hand.to_cards()).score_fast()
+}
+
+// The best score any two cards can make on the board.
+fn nut_score(board: Cards) -> Score {
+    let available: Vec<_> = Card::all().filter(|card| !board.has(*card)).collect();
+    let mut nut_score = Score::ZERO;
+    for (index, a) in available.iter().copied().enumerate() {
+        for b in available[index+1..].iter().copied() {
+            nut_score = nut_score.max(score(board, Hand::of_two_cards(a, b)));
+        }
+    }
+    nut_score
+}
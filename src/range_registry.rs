@@ -0,0 +1,141 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::SystemTime;
+
+use crate::range::RangeTable;
+use crate::result::Result;
+
+// Named ranges (aliases), one per line: <name> = <range>, e.g. "btn-open = 22+,A2s+,K9s+".
+// Empty lines and lines starting with # are ignored.
+#[derive(Clone, Default)]
+pub struct RangeRegistry {
+    ranges: Vec<(String, String, RangeTable)>,
+}
+
+impl RangeRegistry {
+    // Either every line is valid or the whole file is rejected.
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut registry = Self::default();
+        for (index, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |message: String| format!("invalid range registry: line {}: {}", index + 1, message);
+            let Some((name, raw_range)) = line.split_once('=') else {
+                return Err(invalid("expected <name> = <range>".to_string()).into());
+            };
+            let (name, raw_range) = (name.trim(), raw_range.trim());
+            let valid_name = !name.is_empty()
+                && name.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_');
+            if !valid_name {
+                return Err(invalid(format!("invalid name '{}'", name)).into());
+            }
+            if registry.get(name).is_some() {
+                return Err(invalid(format!("duplicate name '{}'", name)).into());
+            }
+            let range = RangeTable::parse(raw_range).map_err(|err| invalid(err.to_string()))?;
+            registry.ranges.push((name.to_string(), raw_range.to_string(), range));
+        }
+        Ok(registry)
+    }
+
+    // A missing file is an empty registry.
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(raw) => Self::parse(&raw),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(format!("invalid range registry {}: {}", path.display(), err).into()),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&RangeTable> {
+        self.ranges.iter()
+            .find(|(range_name, _, _)| range_name == name)
+            .map(|(_, _, range)| range)
+    }
+
+    // A registered name or otherwise a range.
+    pub fn resolve(&self, raw: &str) -> Result<RangeTable> {
+        match self.get(raw) {
+            Some(range) => Ok(range.clone()),
+            None => RangeTable::parse(raw),
+        }
+    }
+
+    // Name, range as written and the range in registry order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str, &RangeTable)> {
+        self.ranges.iter().map(|(name, raw_range, range)| (name.as_str(), raw_range.as_str(), range))
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+// A registry file reloaded on change. Readers always see a complete registry,
+// an invalid file keeps the previous registry and is reported by last_error.
+pub struct WatchedRegistry {
+    path: PathBuf,
+    current: RwLock<Arc<RangeRegistry>>,
+    modified: Mutex<Option<SystemTime>>,
+    last_error: Mutex<Option<String>>,
+}
+
+impl WatchedRegistry {
+    // Fails if the file exists but is invalid.
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let modified = modified_time(&path);
+        let registry = RangeRegistry::load(&path)?;
+        Ok(Self {
+            path,
+            current: RwLock::new(Arc::new(registry)),
+            modified: Mutex::new(modified),
+            last_error: Mutex::new(None),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn current(&self) -> Arc<RangeRegistry> {
+        Arc::clone(&self.current.read().unwrap())
+    }
+
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().unwrap().clone()
+    }
+
+    // Reloads if the modification time changed, returns true if a new registry was loaded.
+    pub fn reload_if_changed(&self) -> bool {
+        let modified = modified_time(&self.path);
+        {
+            let mut last_modified = self.modified.lock().unwrap();
+            if *last_modified == modified {
+                return false;
+            }
+            *last_modified = modified;
+        }
+        match RangeRegistry::load(&self.path) {
+            Ok(registry) => {
+                *self.current.write().unwrap() = Arc::new(registry);
+                *self.last_error.lock().unwrap() = None;
+                true
+            },
+            Err(err) => {
+                *self.last_error.lock().unwrap() = Some(err.to_string());
+                false
+            },
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
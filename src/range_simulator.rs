@@ -0,0 +1,222 @@
+use rand::Rng;
+
+use crate::cards::Cards;
+use crate::error::Error;
+use crate::hand::Hand;
+use crate::result::Result;
+use crate::weighted_range::{WeightedRange, WeightedRangeSampler};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SamplingStrategy {
+    // Every villain hand is drawn from the full weighted range,
+    // a deal with any overlapping cards is discarded and redrawn as a whole.
+    // The accepted deals follow exactly the joint distribution
+    // P(h1, ..., hn) ~ w1(h1) * ... * wn(hn) over all non-overlapping deals.
+    ExactRejection,
+    // Every villain hand is drawn from the live part of its range,
+    // conditioned on the dead cards and the previously drawn hands.
+    // Drawing this way alone would skew the distribution, the earlier
+    // players would ignore that their hands block the later players.
+    // Therefore a deal is accepted with probability
+    // (L1 / L1') * ... * (Ln / Ln'), where Li is the live weight of player i
+    // given the previous hands and Li' the live weight given only the dead cards.
+    // The accepted deals again follow exactly the joint distribution,
+    // with far fewer rejections if the ranges overlap heavily.
+    Sequential,
+}
+
+impl SamplingStrategy {
+    // "exact" (ExactRejection) or "sequential".
+    pub fn parse(s: &str) -> Result<Self> {
+        match s {
+            "exact" => Ok(SamplingStrategy::ExactRejection),
+            "sequential" => Ok(SamplingStrategy::Sequential),
+            _ => Err(Error::usage(format!("invalid sampling '{}': expected exact or sequential", s))),
+        }
+    }
+}
+
+pub struct RangeSimulator {
+    samplers: Vec<WeightedRangeSampler>,
+    strategy: SamplingStrategy,
+    max_attempts: u64,
+    attempts: u64,
+    deals: u64,
+}
+
+impl RangeSimulator {
+    pub const DEFAULT_MAX_ATTEMPTS: u64 = 1_000_000;
+
+    pub fn new(ranges: &[WeightedRange], strategy: SamplingStrategy) -> Option<Self> {
+        if ranges.iter().any(|range| range.total_weight() <= 0.0) {
+            return None;
+        }
+        Some(Self {
+            samplers: ranges.iter().map(|range| range.sampler()).collect(),
+            strategy,
+            max_attempts: Self::DEFAULT_MAX_ATTEMPTS,
+            attempts: 0,
+            deals: 0,
+        })
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u64) -> Self {
+        assert_ne!(max_attempts, 0);
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn strategy(&self) -> SamplingStrategy {
+        self.strategy
+    }
+
+    pub fn player_count(&self) -> usize {
+        self.samplers.len()
+    }
+
+    // Share of the attempted deals which were accepted.
+    // A low rate means the ranges (and dead cards) overlap heavily.
+    pub fn acceptance_rate(&self) -> f64 {
+        if self.attempts == 0 {
+            1.0
+        } else {
+            self.deals as f64 / self.attempts as f64
+        }
+    }
+
+    // Deals one hand per range into hands, avoiding the dead cards.
+    // Returns the dead cards together with the dealt cards,
+    // or None if no valid deal was found within the attempt limit.
+    pub fn deal(
+        &mut self,
+        rng: &mut impl Rng,
+        dead_cards: Cards,
+        hands: &mut [Hand],
+    ) -> Option<Cards> {
+        assert_eq!(hands.len(), self.samplers.len());
+        match self.strategy {
+            SamplingStrategy::ExactRejection => self.deal_exact_rejection(rng, dead_cards, hands),
+            SamplingStrategy::Sequential => self.deal_sequential(rng, dead_cards, hands),
+        }
+    }
+
+    fn deal_sequential(
+        &mut self,
+        rng: &mut impl Rng,
+        dead_cards: Cards,
+        hands: &mut [Hand],
+    ) -> Option<Cards> {
+        let unblocked_weights: Vec<_> = self.samplers.iter()
+            .map(|sampler| sampler.live_weight(dead_cards))
+            .collect();
+        if unblocked_weights.iter().any(|weight| *weight <= 0.0) {
+            return None;
+        }
+        'deal: for _ in 0..self.max_attempts {
+            self.attempts += 1;
+            let mut dealt_cards = dead_cards;
+            let mut acceptance = 1.0;
+            let players = hands.iter_mut().zip(self.samplers.iter()).zip(unblocked_weights.iter());
+            for ((hand, sampler), unblocked_weight) in players {
+                let Some((sampled, live_weight)) = sampler.sample_live(rng, dealt_cards) else {
+                    continue 'deal;
+                };
+                acceptance *= live_weight / unblocked_weight;
+                dealt_cards |= sampled.to_cards();
+                *hand = sampled;
+            }
+            if rng.gen_range(0.0..1.0) >= acceptance {
+                continue;
+            }
+            self.deals += 1;
+            return Some(dealt_cards);
+        }
+        None
+    }
+
+    fn deal_exact_rejection(
+        &mut self,
+        rng: &mut impl Rng,
+        dead_cards: Cards,
+        hands: &mut [Hand],
+    ) -> Option<Cards> {
+        'deal: for _ in 0..self.max_attempts {
+            self.attempts += 1;
+            let mut dealt_cards = dead_cards;
+            for (hand, sampler) in hands.iter_mut().zip(self.samplers.iter()) {
+                let sampled = sampler.sample(rng).unwrap();
+                let sampled_cards = sampled.to_cards();
+                if (dealt_cards & sampled_cards) != Cards::EMPTY {
+                    continue 'deal;
+                }
+                dealt_cards |= sampled_cards;
+                *hand = sampled;
+            }
+            self.deals += 1;
+            return Some(dealt_cards);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    const DEALS: u64 = 400_000;
+
+    // The probability of every pair of hands without a shared or dead card.
+    fn exact_frequencies(ranges: &[WeightedRange], dead_cards: Cards) -> HashMap<(Hand, Hand), f64> {
+        let mut weights = HashMap::new();
+        for (a, weight_a) in ranges[0].hands() {
+            for (b, weight_b) in ranges[1].hands() {
+                let (cards_a, cards_b) = (a.to_cards(), b.to_cards());
+                if (dead_cards & (cards_a | cards_b)) == Cards::EMPTY && (cards_a & cards_b) == Cards::EMPTY {
+                    weights.insert((a, b), weight_a * weight_b);
+                }
+            }
+        }
+        let total: f64 = weights.values().sum();
+        weights.values_mut().for_each(|weight| *weight /= total);
+        weights
+    }
+
+    fn check_distribution(strategy: SamplingStrategy) {
+        let ranges = [
+            WeightedRange::parse("AA,AKs").unwrap(),
+            WeightedRange::parse("AA,KK").unwrap(),
+        ];
+        let dead_cards: Cards = "AhKd".parse().unwrap();
+        let exact = exact_frequencies(&ranges, dead_cards);
+        let mut simulator = RangeSimulator::new(&ranges, strategy).unwrap();
+        let mut rng = SmallRng::seed_from_u64(1);
+        let mut hands = [Hand::MIN; 2];
+        let mut counts: HashMap<(Hand, Hand), u64> = HashMap::new();
+        for _ in 0..DEALS {
+            let dealt_cards = simulator.deal(&mut rng, dead_cards, &mut hands).unwrap();
+            assert_eq!(dealt_cards, dead_cards | hands[0].to_cards() | hands[1].to_cards());
+            let pair = (hands[0], hands[1]);
+            assert!(exact.contains_key(&pair), "illegal deal {} {}", hands[0], hands[1]);
+            *counts.entry(pair).or_default() += 1;
+        }
+        for (pair, frequency) in exact {
+            let dealt = counts.get(&pair).copied().unwrap_or(0) as f64 / DEALS as f64;
+            assert!((dealt - frequency).abs() < 0.002, "{:?}: dealt {} expected {}", pair, dealt, frequency);
+        }
+    }
+
+    #[test]
+    fn exact_rejection_matches_enumeration() {
+        check_distribution(SamplingStrategy::ExactRejection);
+    }
+
+    #[test]
+    fn sequential_matches_enumeration() {
+        check_distribution(SamplingStrategy::Sequential);
+    }
+}
@@ -52,6 +52,17 @@ impl fmt::Display for Rank {
     }
 }
 
+impl TryFrom<char> for Rank {
+    type Error = crate::error::Error;
+
+    fn try_from(ch: char) -> Result<Self> {
+        match u8::try_from(ch) {
+            Ok(ch) => Self::from_ascii(ch),
+            Err(_) => Err(format!("invalid rank char '{}'", ch).into()),
+        }
+    }
+}
+
 impl TryFrom<i8> for Rank {
     type Error = ();
 
@@ -138,6 +149,58 @@ impl Rank {
         Ok(rank)
     }
 
+    // Ace first.
+    pub fn descending() -> impl Iterator<Item = Rank> {
+        Self::RANKS.iter().rev().copied()
+    }
+
+    pub fn distance(self, other: Rank) -> u8 {
+        self.to_u8().abs_diff(other.to_u8())
+    }
+
+    // Same as distance, but the ace also counts as one below the two,
+    // e.g. A and 3 are two ranks apart.
+    pub fn straight_distance(self, other: Rank) -> u8 {
+        let low = |rank: Rank| if rank == Ace { -1 } else { rank.to_i8() };
+        let low_distance = low(self).abs_diff(low(other));
+        self.distance(other).min(low_distance)
+    }
+
+    // The ten straights from the wheel (A2345) to broadway (TJQKA),
+    // each lowest rank first.
+    pub fn straights() -> impl Iterator<Item = [Rank; 5]> {
+        let wheel = [Ace, Two, Three, Four, Five];
+        let others = (0..=Self::COUNT-5).map(|low| {
+            std::array::from_fn(|i| Self::RANKS[low + i])
+        });
+        std::iter::once(wheel).chain(others)
+    }
+
+    // The fewest ranks missing from ranks to any straight, 0 if there is one.
+    pub fn straight_gap(ranks: &[Rank]) -> u8 {
+        Self::straights()
+            .map(|straight| straight.iter().filter(|rank| !ranks.contains(rank)).count() as u8)
+            .min()
+            .unwrap()
+    }
+
+    // The ranks that make a straight together with ranks, highest first.
+    // Two of them with four consecutive ranks is an open-ended draw.
+    pub fn straight_completions(ranks: &[Rank]) -> Vec<Rank> {
+        let mut completions: Vec<_> = Self::straights()
+            .filter_map(|straight| {
+                let mut missing = straight.iter().filter(|rank| !ranks.contains(rank));
+                match (missing.next(), missing.next()) {
+                    (Some(rank), None) => Some(*rank),
+                    _ => None,
+                }
+            })
+            .collect();
+        completions.sort_unstable_by(|a, b| b.cmp(a));
+        completions.dedup();
+        completions
+    }
+
     pub fn range(from: Rank, to: Rank) -> impl Iterator<Item = Rank> {
         (from.to_i8()..=to.to_i8()).map(|item| item.try_into().unwrap())
     }
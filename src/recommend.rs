@@ -0,0 +1,137 @@
+use core::fmt;
+use std::time::{Duration, Instant};
+
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+use crate::cards::Cards;
+use crate::equity::{EnumerationPlan, Equity};
+use crate::hand::Hand;
+use crate::range::{HandRange, RangeTable};
+use crate::result::Result;
+
+const BENCH_ROUNDS: u64 = 100_000;
+const BENCH_VILLAINS: usize = 2;
+
+// Speed of this machine, measured by running reference calculations.
+// Enumeration speed is in showdowns of the plan upper bound per second
+// (the same measure the recommendation estimates with), simulation speed
+// in rounds times players per second.
+#[derive(Debug, Clone, Copy)]
+pub struct Throughput {
+    pub plan_showdowns_per_second: f64,
+    pub player_rounds_per_second: f64,
+}
+
+impl fmt::Display for Throughput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "enumerate: {:.0} showdowns/s simulate: {:.0} player-rounds/s",
+            self.plan_showdowns_per_second,
+            self.player_rounds_per_second,
+        )
+    }
+}
+
+impl Throughput {
+    // Runs every reference calculation repeatedly for at least the given duration.
+    pub fn measure(duration: Duration) -> Result<Self> {
+        let community_cards = "AsTd3h2c".parse::<Cards>()?;
+        let hero_hand = "AhTh".parse::<Hand>()?;
+        let villain_ranges = [RangeTable::parse("AKo,AKs,TT+,33")?, RangeTable::parse("full")?];
+        let plan = EnumerationPlan::new(community_cards, hero_hand, &villain_ranges).unwrap();
+
+        Cards::warm_up();
+        let start = Instant::now();
+        let mut showdowns = 0.0;
+        while start.elapsed() < duration {
+            Equity::enumerate(community_cards, hero_hand, &villain_ranges).unwrap();
+            showdowns += plan.showdowns_upper_bound as f64;
+        }
+        let plan_showdowns_per_second = showdowns / start.elapsed().as_secs_f64();
+
+        let mut rng = SmallRng::from_entropy();
+        let start = Instant::now();
+        let mut rounds = 0;
+        while start.elapsed() < duration {
+            Equity::simulate(community_cards, hero_hand, BENCH_VILLAINS, BENCH_ROUNDS, &mut rng)
+                .unwrap();
+            rounds += BENCH_ROUNDS;
+        }
+        let player_rounds = (rounds * (BENCH_VILLAINS as u64 + 1)) as f64;
+        let player_rounds_per_second = player_rounds / start.elapsed().as_secs_f64();
+
+        Ok(Self { plan_showdowns_per_second, player_rounds_per_second })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Recommendation {
+    Enumerate {
+        expected_time: Duration,
+    },
+    Simulate {
+        rounds: u64,
+        expected_time: Duration,
+        // Worst case (50% equity) standard error of the equities after the rounds,
+        // above the precision if the time budget is too small.
+        std_error: f64,
+    },
+}
+
+impl fmt::Display for Recommendation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recommendation::Enumerate { expected_time } => write!(
+                f,
+                "enumerate: expected-time={:.1}s",
+                expected_time.as_secs_f64(),
+            ),
+            Recommendation::Simulate { rounds, expected_time, std_error } => write!(
+                f,
+                "simulate: rounds={} expected-time={:.1}s std-error<={:.3}",
+                rounds,
+                expected_time.as_secs_f64(),
+                std_error * 100.0,
+            ),
+        }
+    }
+}
+
+// Enumerate if the upper bound of the showdowns fits into the time budget,
+// otherwise simulate as many rounds as needed for the precision
+// (a standard error of the equities as a fraction), but at most as many
+// as fit into the time budget.
+pub fn recommend(
+    community_cards: Cards,
+    hero_hand: Hand,
+    villain_ranges: &[impl HandRange],
+    precision: f64,
+    time_budget: Duration,
+    throughput: &Throughput,
+) -> Result<Recommendation> {
+    if !(precision.is_finite() && precision > 0.0) {
+        return Err("recommend failed: expected a positive precision".into());
+    }
+    let Some(plan) = EnumerationPlan::new(community_cards, hero_hand, villain_ranges) else {
+        return Err("recommend failed: invalid input".into());
+    };
+    let enumerate_seconds = plan.showdowns_upper_bound as f64 / throughput.plan_showdowns_per_second;
+    if plan.feasible && enumerate_seconds <= time_budget.as_secs_f64() {
+        return Ok(Recommendation::Enumerate {
+            expected_time: Duration::from_secs_f64(enumerate_seconds),
+        });
+    }
+
+    let player_count = (villain_ranges.len() + 1) as f64;
+    let rounds_per_second = throughput.player_rounds_per_second / player_count;
+    let needed_rounds = (0.25 / (precision * precision)).ceil();
+    let budget_rounds = (rounds_per_second * time_budget.as_secs_f64()).floor();
+    let rounds = f64::max(1.0, f64::min(needed_rounds, budget_rounds)) as u64;
+    Ok(Recommendation::Simulate {
+        rounds,
+        expected_time: Duration::from_secs_f64(rounds as f64 / rounds_per_second),
+        std_error: (0.25 / rounds as f64).sqrt(),
+    })
+}
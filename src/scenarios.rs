@@ -0,0 +1,165 @@
+use core::fmt;
+
+use rand::{rngs::SmallRng, SeedableRng};
+
+use crate::cards::Cards;
+use crate::equity::{Equity, EquityResult};
+use crate::hand::Hand;
+use crate::omaha::{omaha_enumerate, OmahaHand};
+use crate::range::{Opponent, RangeTable};
+use crate::result::Result;
+use crate::weighted_range::WeightedRange;
+
+static SHIPPED_SCENARIOS: &str = include_str!("../data/scenarios.txt");
+
+static REFERENCE_SCENARIOS: &str = include_str!("../data/references.txt");
+
+// Stored references are rounded to two decimals in percent,
+// exact results may differ by up to one rounding step.
+const ROUNDING_TOLERANCE: f64 = 0.0001;
+
+// Simulations pass within this many (worst case) standard errors.
+const SIMULATION_STD_ERRORS: f64 = 4.0;
+
+pub enum ScenarioKind {
+    Enumerate { community_cards: Cards, hero_hand: Hand, villain_ranges: Vec<Opponent> },
+    Simulate { rounds: u64, community_cards: Cards, hero_hand: Hand, villain_ranges: Vec<RangeTable> },
+    Omaha { community_cards: Cards, hands: Vec<OmahaHand> },
+}
+
+// A calculation with the reference equity (as a fraction) of every player.
+pub struct Scenario {
+    pub name: String,
+    pub kind: ScenarioKind,
+    pub reference: Vec<f64>,
+}
+
+pub struct ScenarioOutcome {
+    pub result: EquityResult,
+    // Largest difference to the reference of any player.
+    pub deviation: f64,
+    pub tolerance: f64,
+}
+
+impl fmt::Display for ScenarioOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} deviation={:.3} tolerance={:.3} time={:.1}ms",
+            if self.passed() { "ok" } else { "FAILED" },
+            self.deviation * 100.0,
+            self.tolerance * 100.0,
+            self.result.work.elapsed.as_secs_f64() * 1000.0,
+        )
+    }
+}
+
+impl ScenarioOutcome {
+    pub fn passed(&self) -> bool {
+        self.deviation <= self.tolerance
+    }
+}
+
+impl Scenario {
+    // <name> <kind> <arguments> = <reference equities in percent>
+    pub fn parse(line: &str) -> Result<Self> {
+        let invalid = || format!("invalid scenario '{}'", line);
+        let Some((raw_scenario, raw_reference)) = line.split_once('=') else {
+            return Err(invalid().into());
+        };
+        let tokens: Vec<_> = raw_scenario.split_whitespace().collect();
+        let [name, kind, args @ ..] = tokens.as_slice() else {
+            return Err(invalid().into());
+        };
+        let parse_ranges = |raw: &[&str]| raw.iter()
+            .map(|raw_range| RangeTable::parse(raw_range))
+            .collect::<Result<Vec<_>>>();
+        let kind = match (*kind, args) {
+            ("enumerate", [community_cards, hero_hand, villain_ranges @ ..]) => ScenarioKind::Enumerate {
+                community_cards: community_cards.parse::<Cards>()?,
+                hero_hand: hero_hand.parse::<Hand>()?,
+                villain_ranges: villain_ranges.iter()
+                    .map(|raw_range| Opponent::parse(raw_range))
+                    .collect::<Result<Vec<_>>>()?,
+            },
+            ("simulate", [rounds, community_cards, hero_hand, villain_ranges @ ..]) => ScenarioKind::Simulate {
+                rounds: rounds.parse()?,
+                community_cards: community_cards.parse::<Cards>()?,
+                hero_hand: hero_hand.parse::<Hand>()?,
+                villain_ranges: parse_ranges(villain_ranges)?,
+            },
+            ("omaha", [community_cards, hands @ ..]) => ScenarioKind::Omaha {
+                community_cards: community_cards.parse::<Cards>()?,
+                hands: hands.iter()
+                    .map(|raw_hand| OmahaHand::parse(raw_hand))
+                    .collect::<Result<Vec<_>>>()?,
+            },
+            _ => return Err(invalid().into()),
+        };
+        let reference = raw_reference.split_whitespace()
+            .map(|raw| raw.parse::<f64>().map(|equity| equity / 100.0))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(Self { name: name.to_string(), kind, reference })
+    }
+
+    pub fn run(&self) -> Result<ScenarioOutcome> {
+        let (result, tolerance) = match &self.kind {
+            ScenarioKind::Enumerate { community_cards, hero_hand, villain_ranges } => (
+                Equity::enumerate(*community_cards, *hero_hand, villain_ranges),
+                ROUNDING_TOLERANCE,
+            ),
+            ScenarioKind::Simulate { rounds, community_cards, hero_hand, villain_ranges } => {
+                let villain_ranges: Vec<_> = villain_ranges.iter()
+                    .map(WeightedRange::from_range)
+                    .collect();
+                (
+                    Equity::simulate_weighted(
+                        *community_cards,
+                        *hero_hand,
+                        &villain_ranges,
+                        *rounds,
+                        &mut SmallRng::from_entropy(),
+                    ),
+                    ROUNDING_TOLERANCE + SIMULATION_STD_ERRORS * (0.25 / *rounds as f64).sqrt(),
+                )
+            },
+            ScenarioKind::Omaha { community_cards, hands } => (
+                omaha_enumerate(*community_cards, hands),
+                ROUNDING_TOLERANCE,
+            ),
+        };
+        let Some(result) = result else {
+            return Err(format!("scenario {} failed: invalid input", self.name).into());
+        };
+        if result.equities.len() != self.reference.len() {
+            return Err(format!(
+                "scenario {} failed: expected {} reference equities",
+                self.name,
+                result.equities.len(),
+            ).into());
+        }
+        let deviation = result.equities.iter()
+            .zip(&self.reference)
+            .map(|(equity, reference)| (equity.equity_percent() - reference).abs())
+            .fold(0.0, f64::max);
+        Ok(ScenarioOutcome { result, deviation, tolerance })
+    }
+}
+
+// The scenarios shipped with the binary.
+pub fn shipped_scenarios() -> Result<Vec<Scenario>> {
+    parse_scenarios(SHIPPED_SCENARIOS)
+}
+
+// Exact calibration references of classic matchups, see verify.
+pub fn reference_scenarios() -> Result<Vec<Scenario>> {
+    parse_scenarios(REFERENCE_SCENARIOS)
+}
+
+fn parse_scenarios(raw: &str) -> Result<Vec<Scenario>> {
+    raw.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(Scenario::parse)
+        .collect()
+}
@@ -0,0 +1,90 @@
+use core::fmt;
+
+use crate::cards::Cards;
+use crate::equity::Equity;
+use crate::hand::Hand;
+use crate::range::RangeTable;
+use crate::result::Result;
+
+// Hero equity against the villain range of the best percent of starting hands.
+#[derive(Debug, Clone, Copy)]
+pub struct SensitivityPoint {
+    pub percent: f64,
+    pub live_combos: u32,
+    pub equity: f64,
+}
+
+impl fmt::Display for SensitivityPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "top {:.2}%: equity={:2.2} live-combos={}",
+            self.percent,
+            self.equity * 100.0,
+            self.live_combos,
+        )
+    }
+}
+
+pub struct Sensitivity {
+    pub base: SensitivityPoint,
+    // Ordered from the tightest to the widest range, including the base.
+    pub points: Vec<SensitivityPoint>,
+}
+
+impl Sensitivity {
+    // Widens and tightens the base range by steps times step percent,
+    // ranges outside (0, 100] are skipped.
+    pub fn new(
+        community_cards: Cards,
+        hero_hand: Hand,
+        base_percent: f64,
+        step: f64,
+        steps: u32,
+    ) -> Result<Self> {
+        if community_cards.count() < 3 {
+            return Err("sensitivity failed: expected at least 3 community cards".into());
+        }
+        if !(base_percent > 0.0 && base_percent <= 100.0 && step > 0.0 && step.is_finite()) {
+            return Err("sensitivity failed: invalid percent or step".into());
+        }
+        let steps = i64::from(steps);
+        let mut points = Vec::new();
+        let mut base = None;
+        for k in -steps..=steps {
+            let percent = base_percent + k as f64 * step;
+            if percent <= 0.0 || percent > 100.0 {
+                continue;
+            }
+            let range = RangeTable::top_percent(percent);
+            let dead_cards = community_cards | hero_hand.to_cards();
+            let mut live_combos = 0;
+            range.for_each_hand(|hand| if (hand.to_cards() & dead_cards) == Cards::EMPTY {
+                live_combos += 1;
+            });
+            let Some(result) = Equity::enumerate(community_cards, hero_hand, &[range]) else {
+                continue;
+            };
+            let point = SensitivityPoint {
+                percent,
+                live_combos,
+                equity: result.equities[0].equity_percent(),
+            };
+            if k == 0 {
+                base = Some(point);
+            }
+            points.push(point);
+        }
+        let Some(base) = base else {
+            return Err("sensitivity failed: invalid input".into());
+        };
+        Ok(Self { base, points })
+    }
+
+    // Largest absolute equity difference to the base.
+    pub fn max_change(&self) -> f64 {
+        self.points.iter()
+            .map(|point| (point.equity - self.base.equity).abs())
+            .fold(0.0, f64::max)
+    }
+}
@@ -0,0 +1,164 @@
+use core::fmt;
+use std::str::FromStr;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::card::Card;
+use crate::cards::Cards;
+use crate::equity::{tie_units_of_pots, Equity};
+use crate::equivalence_class::EquivalenceClass;
+use crate::hand::Hand;
+use crate::range::RangeTable;
+use crate::rank::Rank;
+use crate::suite::Suite;
+
+// Everything is (de)serialized in the notation of the command line:
+// "A", "h", "Ah", "AhKd2c" (any number of cards), "AhKd"
+// and ranges as their comma separated classes, e.g. "AA,AKs".
+
+fn deserialize_parsed<'de, D: Deserializer<'de>, T: FromStr<Err: fmt::Display>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    s.parse().map_err(D::Error::custom)
+}
+
+fn deserialize_str<'de, D: Deserializer<'de>, T>(
+    deserializer: D,
+    parse: impl FnOnce(&str) -> crate::result::Result<T>,
+) -> Result<T, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    parse(&s).map_err(D::Error::custom)
+}
+
+impl Serialize for Rank {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rank {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_str(deserializer, |s| match s.as_bytes() {
+            [ch] => Rank::from_ascii(*ch),
+            _ => Err(format!("invalid rank '{}'", s).into()),
+        })
+    }
+}
+
+impl Serialize for Suite {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Suite {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_str(deserializer, |s| {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => Suite::try_from(ch),
+                _ => Err(format!("invalid suite '{}'", s).into()),
+            }
+        })
+    }
+}
+
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_parsed(deserializer)
+    }
+}
+
+impl Serialize for Cards {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let cards: String = self.iter().map(|card| card.to_string()).collect();
+        serializer.serialize_str(&cards)
+    }
+}
+
+impl<'de> Deserialize<'de> for Cards {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_parsed(deserializer)
+    }
+}
+
+impl Serialize for Hand {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Hand {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_parsed(deserializer)
+    }
+}
+
+impl Serialize for RangeTable {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let classes: Vec<_> = self.classes().map(|class| class.to_string()).collect();
+        serializer.serialize_str(&classes.join(","))
+    }
+}
+
+// Also accepts everything the command line does, e.g. "QQ+,AKs" or "10%".
+impl<'de> Deserialize<'de> for RangeTable {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_str(deserializer, |s| {
+            if s.is_empty() {
+                Ok(RangeTable::empty())
+            } else {
+                RangeTable::parse(s)
+            }
+        })
+    }
+}
+
+// The index, 1 (royal flush) to 7462.
+impl Serialize for EquivalenceClass {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u16(self.index())
+    }
+}
+
+impl<'de> Deserialize<'de> for EquivalenceClass {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let index = u16::deserialize(deserializer)?;
+        EquivalenceClass::from_index(index)
+            .ok_or_else(|| D::Error::custom(format!("invalid equivalence class {}", index)))
+    }
+}
+
+// The ties as pot shares, e.g. {"wins":24076,"ties":5.0,"total":26730},
+// deserialized they are rounded to the nearest tie unit.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Equity")]
+struct EquityCounts {
+    wins: u64,
+    ties: f64,
+    total: u64,
+}
+
+impl Serialize for Equity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        EquityCounts { wins: self.wins(), ties: self.ties(), total: self.total() }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Equity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let counts = EquityCounts::deserialize(deserializer)?;
+        if !counts.ties.is_finite() || counts.ties < 0.0 {
+            return Err(D::Error::custom(format!("invalid ties {}", counts.ties)));
+        }
+        Ok(Equity::from_counts(counts.wins, tie_units_of_pots(counts.ties), counts.total))
+    }
+}
@@ -0,0 +1,251 @@
+use core::fmt;
+use std::collections::HashMap;
+
+use crate::card::Card;
+use crate::cards::Cards;
+use crate::equity::Equity;
+use crate::hand::Hand;
+use crate::range::HandRange;
+use crate::result::Result;
+use crate::suite::Suite;
+
+// Stacks behind of all players (hero first, villain second) and the tournament payouts.
+pub struct Icm {
+    pub stacks: Vec<f64>,
+    pub payouts: Vec<f64>,
+}
+
+impl Icm {
+    pub fn parse(raw_stacks: &str, raw_payouts: &str) -> Result<Self> {
+        let parse = |raw: &str| raw.split(',')
+            .map(|n| match n.trim().parse::<f64>() {
+                Ok(n) if n.is_finite() && n >= 0.0 => Ok(n),
+                _ => Err(format!("invalid icm value '{}'", n)),
+            })
+            .collect::<std::result::Result<Vec<_>, _>>();
+        let icm = Self { stacks: parse(raw_stacks)?, payouts: parse(raw_payouts)? };
+        if icm.stacks.len() < 2 {
+            return Err("invalid icm: expected at least hero and villain stack".into());
+        }
+        Ok(icm)
+    }
+
+    // Malmuth-Harville: the chance to finish first is proportional to the stack,
+    // the remaining places are distributed recursively the same way.
+    // Busted players share the payouts after all remaining players.
+    pub fn equities(stacks: &[f64], payouts: &[f64]) -> Vec<f64> {
+        let alive: Vec<_> = (0..stacks.len()).filter(|index| stacks[*index] > 0.0).collect();
+        let mut equities = vec![0.0; stacks.len()];
+        let mut used = vec![false; stacks.len()];
+        finish_places(stacks, payouts, &alive, &mut used, 0, 1.0, &mut equities);
+
+        let busted = stacks.len() - alive.len();
+        if busted != 0 {
+            let payout: f64 = payouts.iter().skip(alive.len()).take(busted).sum();
+            for (index, stack) in stacks.iter().enumerate() {
+                if *stack <= 0.0 {
+                    equities[index] = payout / busted as f64;
+                }
+            }
+        }
+        equities
+    }
+
+    fn hero_equity(&self, hero_stack: f64, villain_stack: f64) -> f64 {
+        let mut stacks = self.stacks.clone();
+        stacks[0] = hero_stack;
+        stacks[1] = villain_stack;
+        Self::equities(&stacks, &self.payouts)[0]
+    }
+}
+
+fn finish_places(
+    stacks: &[f64],
+    payouts: &[f64],
+    alive: &[usize],
+    used: &mut [bool],
+    place: usize,
+    probability: f64,
+    equities: &mut [f64],
+) {
+    if place >= payouts.len() || place >= alive.len() {
+        return;
+    }
+    let remaining: f64 = alive.iter().filter(|index| !used[**index]).map(|index| stacks[*index]).sum();
+    for index in alive.iter().copied() {
+        if used[index] {
+            continue;
+        }
+        let p = probability * stacks[index] / remaining;
+        equities[index] += p * payouts[place];
+        used[index] = true;
+        finish_places(stacks, payouts, alive, used, place + 1, p, equities);
+        used[index] = false;
+    }
+}
+
+// Result of calling an all-in with the given pot (before the shove)
+// and effective stack against every combo of the shoving range.
+// EVs are relative to folding, in chips and optionally in ICM payouts.
+pub struct ShoveCall {
+    pub pot: f64,
+    pub stack: f64,
+    pub equity: f64,
+    pub ev: f64,
+    pub icm_ev: Option<f64>,
+    pub combos: Vec<ComboCall>,
+}
+
+pub struct ComboCall {
+    pub hand: Hand,
+    pub win: f64,
+    pub tie: f64,
+    pub ev: f64,
+    pub icm_ev: Option<f64>,
+}
+
+impl fmt::Display for ComboCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: ev={:.2} equity={:2.2} win={:2.2} tie={:2.2}",
+            self.hand,
+            self.ev,
+            self.equity() * 100.0,
+            self.win * 100.0,
+            self.tie * 100.0,
+        )?;
+        if let Some(icm_ev) = self.icm_ev {
+            write!(f, " icm-ev={:.4}", icm_ev)?;
+        }
+        Ok(())
+    }
+}
+
+impl ComboCall {
+    // Tie is the chance of a chopped pot.
+    pub fn equity(&self) -> f64 {
+        self.win + self.tie / 2.0
+    }
+}
+
+impl ShoveCall {
+    pub fn new(
+        community_cards: Cards,
+        hero_hand: Hand,
+        pot: f64,
+        stack: f64,
+        villain_range: &impl HandRange,
+        icm: Option<&Icm>,
+    ) -> Result<Self> {
+        if !(pot >= 0.0 && stack > 0.0 && pot.is_finite() && stack.is_finite()) {
+            return Err("shove call failed: invalid pot or stack".into());
+        }
+        if let Some(icm) = icm {
+            if stack > icm.stacks[0].min(icm.stacks[1]) {
+                return Err("shove call failed: stack greater than hero or villain stack".into());
+            }
+        }
+        let dead_cards = community_cards | hero_hand.to_cards();
+        let symmetries = suit_symmetries(community_cards, hero_hand.to_cards());
+        let mut cache: HashMap<Hand, (f64, f64)> = HashMap::new();
+        let mut combos = Vec::new();
+        let mut villain_hands = Vec::new();
+        villain_range.for_each_hand(|hand| villain_hands.push(hand));
+        for hand in villain_hands {
+            if (hand.to_cards() & dead_cards) != Cards::EMPTY {
+                continue;
+            }
+            let canonical = symmetries.iter()
+                .map(|permutation| permute_hand(hand, permutation))
+                .min_by_key(|hand| (hand.high().to_usize(), hand.low().to_usize()))
+                .unwrap();
+            let (win, tie) = match cache.get(&canonical) {
+                Some(result) => *result,
+                None => {
+                    let Some(result) = Equity::enumerate(community_cards, hero_hand, &[hand]) else {
+                        return Err("shove call failed: invalid input".into());
+                    };
+                    let equity = result.equities[0];
+                    let win_tie = (equity.win_percent(), equity.tie_percent() * 2.0);
+                    cache.insert(canonical, win_tie);
+                    win_tie
+                },
+            };
+            let lose = 1.0 - win - tie;
+            let icm_ev = icm.map(|icm| {
+                let (hero, villain) = (icm.stacks[0], icm.stacks[1]);
+                win * icm.hero_equity(hero + pot + stack, villain - stack)
+                    + tie * icm.hero_equity(hero + pot / 2.0, villain + pot / 2.0)
+                    + lose * icm.hero_equity(hero - stack, villain + pot + stack)
+                    - icm.hero_equity(hero, villain + pot)
+            });
+            let equity = win + tie / 2.0;
+            combos.push(ComboCall {
+                hand,
+                win,
+                tie,
+                ev: equity * (pot + 2.0 * stack) - stack,
+                icm_ev,
+            });
+        }
+        if combos.is_empty() {
+            return Err("shove call failed: no live villain combos".into());
+        }
+
+        let count = combos.len() as f64;
+        let mean = |f: &dyn Fn(&ComboCall) -> f64| combos.iter().map(f).sum::<f64>() / count;
+        let equity = mean(&|combo| combo.equity());
+        let ev = mean(&|combo| combo.ev);
+        let icm_ev = icm.map(|_| mean(&|combo| combo.icm_ev.unwrap()));
+        Ok(Self { pot, stack, equity, ev, icm_ev, combos })
+    }
+}
+
+// All suit permutations mapping the community cards and the hero cards onto themselves,
+// villain hands mapped onto each other by them have the same equity.
+fn suit_symmetries(community_cards: Cards, hero_cards: Cards) -> Vec<[Suite; Suite::COUNT]> {
+    suit_permutations().into_iter()
+        .filter(|permutation| {
+            permute_cards(community_cards, permutation) == community_cards
+                && permute_cards(hero_cards, permutation) == hero_cards
+        })
+        .collect()
+}
+
+// All 24 ways to relabel the suits.
+pub fn suit_permutations() -> Vec<[Suite; Suite::COUNT]> {
+    let mut permutations = Vec::new();
+    for a in Suite::SUITES {
+        for b in Suite::SUITES {
+            for c in Suite::SUITES {
+                for d in Suite::SUITES {
+                    let permutation = [a, b, c, d];
+                    let distinct = (0..Suite::COUNT).all(|i| {
+                        (i+1..Suite::COUNT).all(|j| permutation[i] != permutation[j])
+                    });
+                    if distinct {
+                        permutations.push(permutation);
+                    }
+                }
+            }
+        }
+    }
+    permutations
+}
+
+pub fn permute_card(card: Card, permutation: &[Suite; Suite::COUNT]) -> Card {
+    Card::of(card.rank(), permutation[card.suite().to_usize()])
+}
+
+pub fn permute_cards(cards: Cards, permutation: &[Suite; Suite::COUNT]) -> Cards {
+    let mut permuted = Cards::EMPTY;
+    for card in cards.iter() {
+        permuted.add(permute_card(card, permutation));
+    }
+    permuted
+}
+
+pub fn permute_hand(hand: Hand, permutation: &[Suite; Suite::COUNT]) -> Hand {
+    Hand::of_two_cards(permute_card(hand.high(), permutation), permute_card(hand.low(), permutation))
+}
@@ -0,0 +1,144 @@
+use core::fmt;
+
+use crate::cards::Cards;
+use crate::equity::{enumeration_error, Equity};
+use crate::error::Error;
+use crate::hand::Hand;
+use crate::range::HandRange;
+use crate::result::Result;
+use crate::weighted_range::WeightedRange;
+
+// A live combo of a range with its equity against the opponent on the board.
+#[derive(Debug, Clone, Copy)]
+pub struct RankedCombo {
+    pub hand: Hand,
+    pub weight: f64,
+    pub equity: f64,
+}
+
+// The live combos of the range ordered by equity against the opponent, strongest first.
+pub fn rank_combos(
+    community_cards: Cards,
+    range: &WeightedRange,
+    opponent: &impl HandRange,
+) -> Vec<RankedCombo> {
+    let mut ranked = Vec::new();
+    for (hand, weight) in range.hands() {
+        if (hand.to_cards() & community_cards) != Cards::EMPTY {
+            continue;
+        }
+        let Some(result) = Equity::enumerate(community_cards, hand, &[opponent]) else {
+            continue;
+        };
+        ranked.push(RankedCombo { hand, weight, equity: result.equities[0].equity_percent() });
+    }
+    ranked.sort_by(|a, b| b.equity.total_cmp(&a.equity));
+    ranked
+}
+
+// Where a hand shown down sat in the range assumed for the player.
+#[derive(Debug, Clone, Copy)]
+pub struct ShowdownRead {
+    pub hand: Hand,
+    pub equity: f64,
+    // Weight of the hand in the range, 0 if the range didn't contain it.
+    pub weight: f64,
+    // Share of the live range weight the hand is stronger than, ties count half,
+    // e.g. 0.9 for a hand in the top 10% of the range.
+    pub percentile: f64,
+}
+
+impl fmt::Display for ShowdownRead {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: equity={:2.2} weight={} percentile={:2.2}",
+            self.hand,
+            self.equity * 100.0,
+            self.weight,
+            self.percentile * 100.0,
+        )?;
+        if !self.in_range() {
+            write!(f, " outside-range")?;
+        }
+        Ok(())
+    }
+}
+
+impl ShowdownRead {
+    pub fn in_range(&self) -> bool {
+        self.weight > 0.0
+    }
+}
+
+pub fn read_showdown(
+    community_cards: Cards,
+    range: &WeightedRange,
+    opponent: &impl HandRange,
+    shown_hand: Hand,
+) -> Result<ShowdownRead> {
+    if let Some(card) = (shown_hand.to_cards() & community_cards).first() {
+        return Err(Error::DuplicateCard(card));
+    }
+    let Some(result) = Equity::enumerate(community_cards, shown_hand, &[opponent]) else {
+        return Err(enumeration_error("showdown", community_cards, shown_hand, &[opponent]));
+    };
+    let equity = result.equities[0].equity_percent();
+    let ranked = rank_combos(community_cards, range, opponent);
+    let total_weight: f64 = ranked.iter().map(|combo| combo.weight).sum();
+    if total_weight == 0.0 {
+        return Err("showdown failed: no live combos in the range".into());
+    }
+    let below: f64 = ranked.iter()
+        .map(|combo| if combo.equity < equity {
+            combo.weight
+        } else if combo.equity == equity {
+            combo.weight / 2.0
+        } else {
+            0.0
+        })
+        .sum();
+    Ok(ShowdownRead {
+        hand: shown_hand,
+        equity,
+        weight: range.weight(shown_hand),
+        percentile: below / total_weight,
+    })
+}
+
+// Reads over a session. With a well calibrated range the shown hands are in the range
+// and spread over it, a mean percentile far from 50% means the range is too wide or too narrow.
+#[derive(Debug, Clone, Default)]
+pub struct SessionReads {
+    pub reads: Vec<ShowdownRead>,
+}
+
+impl fmt::Display for SessionReads {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "showdowns={} outside-range={} mean-percentile={:2.2}",
+            self.reads.len(),
+            self.outside_range(),
+            self.mean_percentile() * 100.0,
+        )
+    }
+}
+
+impl SessionReads {
+    pub fn add(&mut self, read: ShowdownRead) {
+        self.reads.push(read);
+    }
+
+    pub fn outside_range(&self) -> usize {
+        self.reads.iter().filter(|read| !read.in_range()).count()
+    }
+
+    pub fn mean_percentile(&self) -> f64 {
+        if self.reads.is_empty() {
+            0.0
+        } else {
+            self.reads.iter().map(|read| read.percentile).sum::<f64>() / self.reads.len() as f64
+        }
+    }
+}
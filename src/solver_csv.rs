@@ -0,0 +1,82 @@
+use crate::hand::Hand;
+use crate::range::RangeTable;
+use crate::result::Result;
+use crate::weighted_range::WeightedRange;
+
+#[derive(Debug, Clone)]
+pub struct SolverAction {
+    pub name: String,
+    pub range: WeightedRange,
+}
+
+// Parses per-combo (e.g. "AsKs") or per-class (e.g. "AKs") action frequencies:
+//
+// Hand,Fold,Call,Raise 2.5
+// AsKs,0,0.25,0.75
+// ...
+//
+// Frequencies are either fractions or percentages,
+// if any frequency is greater than 1 all of them are treated as percentages.
+pub fn parse_solver_csv(content: &str) -> Result<Vec<SolverAction>> {
+    let mut lines = content.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+
+    let Some((_, header)) = lines.next() else {
+        return Err("invalid solver csv: missing header".into());
+    };
+    let action_names: Vec<_> = header.split(',').skip(1).map(|name| name.trim()).collect();
+    if action_names.is_empty() {
+        return Err("invalid solver csv: no action columns".into());
+    }
+
+    let mut rows = Vec::new();
+    let mut percentages = false;
+    for (line_number, line) in lines {
+        let mut fields = line.split(',').map(|field| field.trim());
+        let raw_hand = fields.next().unwrap();
+        let hands = parse_hands(raw_hand).map_err(|err| {
+            format!("invalid solver csv: line {line_number}: {err}")
+        })?;
+        let frequencies = fields
+            .map(|field| match field.parse::<f64>() {
+                Ok(frequency) if frequency.is_finite() && frequency >= 0.0 => Ok(frequency),
+                _ => Err(format!(
+                    "invalid solver csv: line {line_number}: invalid frequency '{field}'",
+                )),
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        if frequencies.len() != action_names.len() {
+            return Err(format!(
+                "invalid solver csv: line {line_number}: expected {} frequencies, got {}",
+                action_names.len(),
+                frequencies.len(),
+            ).into());
+        }
+        percentages |= frequencies.iter().any(|frequency| *frequency > 1.0);
+        rows.push((hands, frequencies));
+    }
+
+    let scale = if percentages { 0.01 } else { 1.0 };
+    let mut actions: Vec<_> = action_names.iter()
+        .map(|name| SolverAction { name: name.to_string(), range: WeightedRange::empty() })
+        .collect();
+    for (hands, frequencies) in rows {
+        for (action, frequency) in actions.iter_mut().zip(frequencies) {
+            for hand in hands.iter().copied() {
+                action.range.set(hand, frequency * scale);
+            }
+        }
+    }
+    Ok(actions)
+}
+
+fn parse_hands(raw: &str) -> Result<Vec<Hand>> {
+    if let Ok(hand) = raw.parse::<Hand>() {
+        return Ok(vec![hand]);
+    }
+    let mut hands = Vec::new();
+    RangeTable::parse(raw)?.for_each_hand(|hand| hands.push(hand));
+    Ok(hands)
+}
@@ -0,0 +1,338 @@
+use core::fmt;
+use std::time::Instant;
+
+use rand::Rng;
+
+use crate::card::Card;
+use crate::cards::{Cards, Score};
+use crate::equity::{showdown, Deck, Equity, EquityResult, Work};
+use crate::error::Error;
+use crate::rank::Rank;
+use crate::result::Result;
+use crate::suite::Suite;
+
+const HAND_SIZE: u8 = 7;
+
+const MAX_PLAYERS: usize = 8;
+
+// Deals above this are simulated instead.
+pub const MAX_ENUMERATED_DEALS: u64 = 100_000_000;
+
+// The known cards of a seven card stud player,
+// every missing card up to seven is dealt from the deck.
+#[derive(Debug, Clone, Copy)]
+pub struct StudPlayer {
+    pub down: Cards,
+    pub up: Cards,
+}
+
+impl fmt::Display for StudPlayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for card in self.down.iter() {
+            write!(f, "{}", card)?;
+        }
+        write!(f, "|")?;
+        for card in self.up.iter() {
+            write!(f, "{}", card)?;
+        }
+        Ok(())
+    }
+}
+
+impl StudPlayer {
+    // Down cards and up cards separated by '|', e.g. "AsKs|Qh9d" or "|Qh9d"
+    // if the down cards are unknown.
+    pub fn parse(s: &str) -> Result<Self> {
+        let (raw_down, raw_up) = s.split_once('|').unwrap_or((s, ""));
+        let parse = |raw: &str| if raw.is_empty() {
+            Ok(Cards::EMPTY)
+        } else {
+            raw.parse::<Cards>()
+        };
+        let player = StudPlayer { down: parse(raw_down)?, up: parse(raw_up)? };
+        if let Some(card) = (player.down & player.up).first() {
+            return Err(Error::DuplicateCard(card));
+        }
+        if player.cards().count() > HAND_SIZE {
+            return Err(Error::usage(format!("invalid stud player '{}': more than {} cards", s, HAND_SIZE)));
+        }
+        Ok(player)
+    }
+
+    pub fn cards(self) -> Cards {
+        self.down | self.up
+    }
+
+    fn missing(self) -> u8 {
+        HAND_SIZE - self.cards().count()
+    }
+}
+
+fn known_cards(players: &[StudPlayer], dead_cards: Cards) -> Option<Cards> {
+    if !(2..=MAX_PLAYERS).contains(&players.len()) {
+        return None;
+    }
+    let mut known = dead_cards;
+    for player in players {
+        if (known & player.cards()) != Cards::EMPTY {
+            return None;
+        }
+        known |= player.cards();
+    }
+    let missing: u32 = players.iter().map(|player| u32::from(player.missing())).sum();
+    let available = Card::COUNT as u32 - u32::from(known.count());
+    (missing <= available).then_some(known)
+}
+
+// Number of ways to deal the missing cards, None on overflow.
+pub fn stud_deal_count(players: &[StudPlayer], dead_cards: Cards) -> Option<u64> {
+    let known = known_cards(players, dead_cards)?;
+    let mut available = Card::COUNT as u64 - u64::from(known.count());
+    let mut count = 1u64;
+    for player in players {
+        let missing = u64::from(player.missing());
+        let mut combinations = 1u64;
+        for i in 0..missing {
+            combinations = combinations.checked_mul(available - i)? / (i + 1);
+        }
+        count = count.checked_mul(combinations)?;
+        available -= missing;
+    }
+    Some(count)
+}
+
+// Why stud_enumerate failed: a card of two players (Error::DuplicateCard),
+// too many deals (Error::TooLarge) or any other invalid input (Error::Usage).
+pub fn stud_enumeration_error(command: &str, players: &[StudPlayer], dead_cards: Cards) -> Error {
+    let mut known = dead_cards;
+    for player in players {
+        if let Some(card) = (known & player.cards()).first() {
+            return Error::DuplicateCard(card);
+        }
+        known |= player.cards();
+    }
+    match known_cards(players, dead_cards) {
+        Some(_) => Error::too_large(format!("{} failed: expected sample to large", command)),
+        None => Error::usage(format!("{} failed: invalid input", command)),
+    }
+}
+
+// Every way to complete the hands of all players to seven cards.
+pub fn stud_enumerate(players: &[StudPlayer], dead_cards: Cards) -> Option<EquityResult> {
+    let known = known_cards(players, dead_cards)?;
+    if stud_deal_count(players, dead_cards)? > MAX_ENUMERATED_DEALS {
+        return None;
+    }
+    let start = Instant::now();
+    let mut deal = StudDeal {
+        players,
+        hands: players.iter().map(|player| player.cards()).collect(),
+        scores: vec![Score::ZERO; players.len()],
+        wins: vec![0; players.len()],
+        ties: vec![0; players.len()],
+        total: 0,
+    };
+    deal.player(0, !known, Card::MIN);
+    let work = Work {
+        boards: deal.total,
+        showdowns: deal.total,
+        elapsed: start.elapsed(),
+        ..Work::default()
+    };
+    Some(EquityResult {
+        equities: Equity::from_total_wins_ties(deal.total, &deal.wins, &deal.ties),
+        work,
+    })
+}
+
+pub fn stud_simulate(
+    players: &[StudPlayer],
+    dead_cards: Cards,
+    rounds: u64,
+    rng: &mut impl Rng,
+) -> Option<EquityResult> {
+    let known = known_cards(players, dead_cards)?;
+    if rounds == 0 {
+        return None;
+    }
+    let start = Instant::now();
+    let mut deck = Deck::from_cards(rng, known);
+    let mut scores = vec![Score::ZERO; players.len()];
+    let mut wins = vec![0; players.len()];
+    let mut ties = vec![0; players.len()];
+    for _ in 0..rounds {
+        deck.reset();
+        for (player, score) in players.iter().zip(scores.iter_mut()) {
+            let mut cards = player.cards();
+            for _ in 0..player.missing() {
+                cards.add(deck.draw(rng).unwrap());
+            }
+            *score = cards.score_fast();
+        }
+        showdown(&scores, &mut wins, &mut ties);
+    }
+    let work = Work {
+        boards: rounds,
+        showdowns: rounds,
+        elapsed: start.elapsed(),
+        ..Work::default()
+    };
+    Some(EquityResult { equities: Equity::from_total_wins_ties(rounds, &wins, &ties), work })
+}
+
+// Third street from hero's seat: hero's two down cards and upcard,
+// the upcards of the opponents still in the hand and of the folded players.
+// Every exposed upcard is dead for hero and is never dealt to anyone else.
+#[derive(Debug, Clone)]
+pub struct ThirdStreet {
+    pub hero: StudPlayer,
+    pub opponents: Cards,
+    pub folded: Cards,
+}
+
+// Unseen cards that still help hero: per rank and suit of hero's cards
+// the cards of it not in hero's hand and not exposed.
+#[derive(Debug, Clone)]
+pub struct LiveCards {
+    pub ranks: Vec<(Rank, u8)>,
+    pub suits: Vec<(Suite, u8)>,
+    pub unseen: u8,
+}
+
+impl fmt::Display for LiveCards {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "live:")?;
+        for (rank, count) in &self.ranks {
+            write!(f, " {}={}", rank, count)?;
+        }
+        for (suite, count) in &self.suits {
+            write!(f, " {}={}", suite, count)?;
+        }
+        write!(f, " unseen={}", self.unseen)
+    }
+}
+
+// Ties of the lowest rank are broken by suit, clubs lowest, then diamonds, hearts and spades.
+fn bring_in_order(card: Card) -> (Rank, u8) {
+    let suite = match card.suite() {
+        Suite::Clubs => 0,
+        Suite::Diamonds => 1,
+        Suite::Hearts => 2,
+        Suite::Spades => 3,
+    };
+    (card.rank(), suite)
+}
+
+impl ThirdStreet {
+    pub fn new(hero: StudPlayer, opponents: Cards, folded: Cards) -> Result<Self> {
+        let street = Self { hero, opponents, folded };
+        let hero_cards = hero.cards();
+        if hero.down.count() != 2
+            || hero.up.count() != 1
+            || opponents == Cards::EMPTY
+            || (hero_cards & opponents) != Cards::EMPTY
+            || (folded & (hero_cards | opponents)) != Cards::EMPTY
+        {
+            return Err("invalid third street: expected two down cards and one upcard for hero \
+                and different upcards for the opponents".into());
+        }
+        Ok(street)
+    }
+
+    // Exposed cards hero can't draw.
+    pub fn dead_cards(&self) -> Cards {
+        self.opponents | self.folded
+    }
+
+    // The lowest upcard of the players still in the hand brings in.
+    pub fn bring_in(&self) -> Card {
+        (self.hero.up | self.opponents).iter()
+            .min_by_key(|card| bring_in_order(*card))
+            .unwrap()
+    }
+
+    pub fn live_cards(&self) -> LiveCards {
+        let seen = self.hero.cards() | self.dead_cards();
+        let mut ranks: Vec<Rank> = self.hero.cards().iter().map(|card| card.rank()).collect();
+        ranks.sort_by(|a, b| b.cmp(a));
+        ranks.dedup();
+        let mut suits = Vec::new();
+        for card in self.hero.cards().iter() {
+            if !suits.contains(&card.suite()) {
+                suits.push(card.suite());
+            }
+        }
+        let count_unseen = |matches: &dyn Fn(Card) -> bool| {
+            Card::all().filter(|card| matches(*card) && !seen.has(*card)).count() as u8
+        };
+        LiveCards {
+            ranks: ranks.into_iter()
+                .map(|rank| (rank, count_unseen(&|card| card.rank() == rank)))
+                .collect(),
+            suits: suits.into_iter()
+                .map(|suite| (suite, count_unseen(&|card| card.suite() == suite)))
+                .collect(),
+            unseen: Card::COUNT as u8 - seen.count(),
+        }
+    }
+
+    // Hero first, then the opponents with unknown down cards in card order.
+    pub fn players(&self) -> Vec<StudPlayer> {
+        std::iter::once(self.hero)
+            .chain(self.opponents.iter().map(|card| StudPlayer {
+                down: Cards::EMPTY,
+                up: Cards::EMPTY.with(card),
+            }))
+            .collect()
+    }
+
+    // Equity of every player still in the hand if everyone goes to showdown,
+    // the folded upcards are dead. Enumerated if rounds is None.
+    pub fn equity(&self, rounds: Option<u64>, rng: &mut impl Rng) -> Result<EquityResult> {
+        let players = self.players();
+        match rounds {
+            None => stud_enumerate(&players, self.folded)
+                .ok_or_else(|| stud_enumeration_error("bringin", &players, self.folded)),
+            Some(rounds) => stud_simulate(&players, self.folded, rounds, rng)
+                .ok_or_else(|| Error::usage("bringin failed: invalid input")),
+        }
+    }
+}
+
+struct StudDeal<'a> {
+    players: &'a [StudPlayer],
+    hands: Vec<Cards>,
+    scores: Vec<Score>,
+    wins: Vec<u64>,
+    ties: Vec<u128>,
+    total: u64,
+}
+
+impl StudDeal<'_> {
+    // Deals the missing cards of the player in ascending order (above from),
+    // so every combination is dealt once.
+    fn player(&mut self, player_index: usize, available: Cards, from: Card) {
+        let Some(player) = self.players.get(player_index) else {
+            self.total += 1;
+            showdown(&self.scores, &mut self.wins, &mut self.ties);
+            return;
+        };
+        let hand = self.hands[player_index];
+        if hand.count() == HAND_SIZE {
+            self.scores[player_index] = hand.score_fast();
+            self.player(player_index + 1, available, Card::MIN);
+            return;
+        }
+        debug_assert!(player.missing() != 0);
+        for card in Card::all() {
+            if card.to_usize() < from.to_usize() || !available.has(card) {
+                continue;
+            }
+            self.hands[player_index] = hand.with(card);
+            let mut next_available = available;
+            next_available.remove(card);
+            self.player(player_index, next_available, card);
+        }
+        self.hands[player_index] = hand;
+    }
+}
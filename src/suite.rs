@@ -1,4 +1,5 @@
 use std::fmt;
+use std::sync::OnceLock;
 
 use rand::{distributions::{Distribution, Standard}, Rng};
 
@@ -15,6 +16,9 @@ pub enum Suite {
 
 use Suite::*;
 
+// Chosen with Suite::set_symbols before the first output, letters by default.
+static SYMBOLS: OnceLock<bool> = OnceLock::new();
+
 impl Distribution<Suite> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Suite {
         let n = rng.gen_range(0..i8::try_from(Suite::COUNT).unwrap());
@@ -24,6 +28,9 @@ impl Distribution<Suite> for Standard {
 
 impl fmt::Display for Suite {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if SYMBOLS.get().copied().unwrap_or(false) {
+            return write!(f, "{}", self.symbol());
+        }
         let suite = match *self {
             Diamonds => "d",
             Spades => "s",
@@ -34,6 +41,24 @@ impl fmt::Display for Suite {
     }
 }
 
+// The letter or the symbol, e.g. 's' or '♠'.
+impl TryFrom<char> for Suite {
+    type Error = crate::error::Error;
+
+    fn try_from(ch: char) -> Result<Self> {
+        match ch {
+            '♦' => Ok(Diamonds),
+            '♠' => Ok(Spades),
+            '♥' => Ok(Hearts),
+            '♣' => Ok(Clubs),
+            _ => match u8::try_from(ch) {
+                Ok(ch) => Self::from_ascii(ch),
+                Err(_) => Err(format!("invalid suite char '{}'", ch).into()),
+            },
+        }
+    }
+}
+
 impl TryFrom<i8> for Suite {
     type Error = ();
 
@@ -69,6 +94,20 @@ impl Suite {
         Ok(suite)
     }
 
+    pub fn symbol(self) -> char {
+        match self {
+            Diamonds => '♦',
+            Spades => '♠',
+            Hearts => '♥',
+            Clubs => '♣',
+        }
+    }
+
+    // Whether Display writes the symbols instead of the letters, for every later output.
+    pub fn set_symbols(symbols: bool) -> Result<()> {
+        SYMBOLS.set(symbols).map_err(|_| "suite symbols already chosen".into())
+    }
+
     fn to_i8(self) -> i8 {
         self as i8
     }
@@ -83,7 +122,7 @@ impl Suite {
 
     pub fn to_index(self) -> i8 {
         let index = self.to_i8() * 16;
-        debug_assert!(index >= 0 && index < 64);
+        debug_assert!((0..64).contains(&index));
         index
     }
 
@@ -0,0 +1,115 @@
+use std::fmt::Write;
+
+use crate::card::Card;
+use crate::cards::Cards;
+use crate::suite::Suite;
+
+const CARD_WIDTH: u32 = 50;
+const CARD_HEIGHT: u32 = 70;
+const GAP: u32 = 6;
+const LABEL_WIDTH: u32 = 90;
+const ROW_HEIGHT: u32 = CARD_HEIGHT + GAP;
+
+fn suite_symbol(suite: Suite) -> (&'static str, &'static str) {
+    match suite {
+        Suite::Diamonds => ("\u{2666}", "#c0392b"),
+        Suite::Spades => ("\u{2660}", "#111111"),
+        Suite::Hearts => ("\u{2665}", "#c0392b"),
+        Suite::Clubs => ("\u{2663}", "#111111"),
+    }
+}
+
+fn write_card(svg: &mut String, card: Card, x: u32, y: u32) {
+    let (symbol, color) = suite_symbol(card.suite());
+    write!(
+        svg,
+        concat!(
+            "<g transform=\"translate({},{})\">",
+            "<rect width=\"{}\" height=\"{}\" rx=\"5\" fill=\"#ffffff\" stroke=\"#333333\"/>",
+            "<text x=\"6\" y=\"22\" font-family=\"sans-serif\" font-size=\"18\" font-weight=\"bold\" fill=\"{}\">{}</text>",
+            "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"26\" text-anchor=\"middle\" fill=\"{}\">{}</text>",
+            "</g>",
+        ),
+        x,
+        y,
+        CARD_WIDTH,
+        CARD_HEIGHT,
+        color,
+        card.rank(),
+        CARD_WIDTH / 2,
+        CARD_HEIGHT - 14,
+        color,
+        symbol,
+    ).unwrap();
+}
+
+// Cards ordered by rank, highest first.
+fn sorted_cards(cards: Cards) -> Vec<Card> {
+    let mut sorted: Vec<_> = cards.iter().collect();
+    sorted.sort_by(|a, b| b.cmp_by_rank(*a));
+    sorted
+}
+
+fn svg_document(width: u32, height: u32, content: &str) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">{}</svg>\n",
+        width,
+        height,
+        width,
+        height,
+        content,
+    )
+}
+
+fn row_width(card_count: usize) -> u32 {
+    let card_count = card_count as u32;
+    card_count * CARD_WIDTH + card_count.saturating_sub(1) * GAP
+}
+
+// The cards in a row, e.g. a board or hole cards.
+// Self-contained: only text and shapes, no fonts or images to load.
+pub fn cards_svg(cards: Cards) -> String {
+    let mut content = String::new();
+    for (index, card) in sorted_cards(cards).into_iter().enumerate() {
+        write_card(&mut content, card, index as u32 * (CARD_WIDTH + GAP), 0);
+    }
+    svg_document(row_width(cards.count().into()).max(1), CARD_HEIGHT, &content)
+}
+
+// The board in the first row, then one labeled row of hole cards per player.
+pub fn spot_svg(community_cards: Cards, players: &[(String, Cards)]) -> String {
+    let mut content = String::new();
+    let mut rows = Vec::with_capacity(players.len() + 1);
+    rows.push(("board", community_cards));
+    rows.extend(players.iter().map(|(label, cards)| (label.as_str(), *cards)));
+    let mut max_cards = 0;
+    for (row, (label, cards)) in rows.into_iter().enumerate() {
+        let y = row as u32 * ROW_HEIGHT;
+        write!(
+            content,
+            "<text x=\"0\" y=\"{}\" font-family=\"sans-serif\" font-size=\"16\" fill=\"#333333\">{}</text>",
+            y + CARD_HEIGHT / 2 + 6,
+            escape_xml(label),
+        ).unwrap();
+        for (index, card) in sorted_cards(cards).into_iter().enumerate() {
+            write_card(&mut content, card, LABEL_WIDTH + index as u32 * (CARD_WIDTH + GAP), y);
+        }
+        max_cards = max_cards.max(usize::from(cards.count()));
+    }
+    let height = (players.len() as u32 + 1) * ROW_HEIGHT - GAP;
+    svg_document(LABEL_WIDTH + row_width(max_cards), height, &content)
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
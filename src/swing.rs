@@ -0,0 +1,120 @@
+use core::fmt;
+
+use crate::cards::Cards;
+use crate::equity::{boards_of_size, enumeration_error};
+use crate::equity_session::EquitySession;
+use crate::hand::Hand;
+use crate::range::HandRange;
+use crate::result::Result;
+
+// Upper bounds of the equity change buckets in percentage points,
+// e.g. the first bucket counts changes of at most -50.
+const BUCKETS: [f64; 7] = [-50.0, -20.0, -5.0, 5.0, 20.0, 50.0, 100.0];
+
+// Distribution of the equity changes from one street to the next,
+// every runout weighted by its probability.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StreetSwing {
+    pub transitions: u64,
+    pub mean_change: f64,
+    pub mean_absolute_change: f64,
+    pub std_dev: f64,
+    // Runouts where equity moved from below to above 50% or the other way around.
+    pub crossings: f64,
+    pub buckets: [f64; BUCKETS.len()],
+}
+
+impl fmt::Display for StreetSwing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "mean={:+.2} mean-abs={:.2} std-dev={:.2} crossings-50={:2.2}",
+            self.mean_change,
+            self.mean_absolute_change,
+            self.std_dev,
+            self.crossings * 100.0,
+        )?;
+        let mut lower = "-100".to_string();
+        for (upper, share) in BUCKETS.iter().zip(self.buckets.iter()) {
+            write!(f, " ({},{:+}]={:2.2}", lower, upper, share * 100.0)?;
+            lower = format!("{:+}", upper);
+        }
+        Ok(())
+    }
+}
+
+impl StreetSwing {
+    // Changes are from and to equities as fractions, weighted by probability.
+    fn new(changes: &[(f64, f64, f64)]) -> Self {
+        let mut swing = StreetSwing { transitions: changes.len() as u64, ..Self::default() };
+        let total_weight: f64 = changes.iter().map(|(_, _, weight)| weight).sum();
+        if total_weight <= 0.0 {
+            return swing;
+        }
+        for (from, to, weight) in changes.iter().copied() {
+            let weight = weight / total_weight;
+            let change = (to - from) * 100.0;
+            swing.mean_change += weight * change;
+            swing.mean_absolute_change += weight * change.abs();
+            if (from < 0.5) != (to < 0.5) {
+                swing.crossings += weight;
+            }
+            let bucket = BUCKETS.iter().position(|upper| change <= *upper).unwrap_or(BUCKETS.len() - 1);
+            swing.buckets[bucket] += weight;
+        }
+        let variance: f64 = changes.iter()
+            .map(|(from, to, weight)| {
+                weight / total_weight * ((to - from) * 100.0 - swing.mean_change).powi(2)
+            })
+            .sum();
+        swing.std_dev = variance.sqrt();
+        swing
+    }
+}
+
+pub struct EquitySwing {
+    pub start_equity: f64,
+    // Flop to turn (only if starting on the flop) and turn to river.
+    pub streets: Vec<StreetSwing>,
+}
+
+// Enumerates every runout as a tree, the equity of every node
+// is the exact equity with the cards dealt so far.
+pub fn equity_swing(
+    community_cards: Cards,
+    hero_hand: Hand,
+    villain_ranges: &[impl HandRange],
+) -> Result<EquitySwing> {
+    let count = community_cards.count();
+    if !(3..=4).contains(&count) {
+        return Err("equity swing failed: expected 3 or 4 community cards".into());
+    }
+    let session = EquitySession::new(villain_ranges);
+    let equity = |board: Cards| session.enumerate(board, hero_hand)
+        .map(|result| result.equities[0].equity_percent());
+    let Some(start_equity) = equity(community_cards) else {
+        return Err(enumeration_error("equity swing", community_cards, hero_hand, villain_ranges));
+    };
+    let hero_cards = hero_hand.to_cards();
+
+    let mut streets = Vec::new();
+    let mut previous = vec![(community_cards, start_equity, 1.0)];
+    for size in count+1..=5 {
+        let mut changes = Vec::new();
+        let mut next = Vec::new();
+        for (board, from, weight) in previous {
+            let boards = boards_of_size(board, hero_cards, size);
+            let weight = weight / boards.len() as f64;
+            for board in boards {
+                let Some(to) = equity(board) else {
+                    continue;
+                };
+                changes.push((from, to, weight));
+                next.push((board, to, weight));
+            }
+        }
+        streets.push(StreetSwing::new(&changes));
+        previous = next;
+    }
+    Ok(EquitySwing { start_equity, streets })
+}
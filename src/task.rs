@@ -0,0 +1,269 @@
+use std::any::Any;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+use std::time::Instant;
+
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+use crate::cards::Cards;
+use crate::equity::{enumeration_error, Enumeration, Equity, EquityResult};
+use crate::error::Error;
+use crate::hand::Hand;
+use crate::range::HandRange;
+use crate::result::Result;
+
+// Boards enumerated between two checks for cancellation.
+const ENUMERATION_CHUNK: usize = 16;
+
+// A calculation running on its own thread, awaitable from any executor
+// (the thread wakes the task when done, like spawn_blocking).
+// Dropping the future does not stop the calculation, use the handle to cancel it.
+pub struct EquityTask {
+    shared: Arc<Shared>,
+}
+
+// Cancels the task and reports its progress, can be cloned and sent
+// to other threads while the task is awaited.
+#[derive(Clone)]
+pub struct TaskHandle {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Progress {
+    pub done: u64,
+    pub total: u64,
+}
+
+impl Progress {
+    pub fn fraction(self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.done as f64 / self.total as f64
+        }
+    }
+}
+
+struct Shared {
+    cancelled: AtomicBool,
+    finished: AtomicBool,
+    done: AtomicU64,
+    total: AtomicU64,
+    state: Mutex<State>,
+}
+
+struct State {
+    result: Option<Result<EquityResult>>,
+    waker: Option<Waker>,
+}
+
+impl Shared {
+    fn finish(&self, result: Result<EquityResult>) {
+        let mut state = self.state.lock().unwrap();
+        state.result = Some(result);
+        self.finished.store(true, Ordering::Relaxed);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl EquityTask {
+    fn spawn(total: u64, run: impl FnOnce(&Shared) -> Result<EquityResult> + Send + 'static) -> Self {
+        let shared = Arc::new(Shared {
+            cancelled: AtomicBool::new(false),
+            finished: AtomicBool::new(false),
+            done: AtomicU64::new(0),
+            total: AtomicU64::new(total),
+            state: Mutex::new(State { result: None, waker: None }),
+        });
+        let thread_shared = Arc::clone(&shared);
+        thread::spawn(move || {
+            // A panic finishes the task with an error, otherwise it would never be ready.
+            let result = panic::catch_unwind(AssertUnwindSafe(|| run(&thread_shared)))
+                .unwrap_or_else(|payload| Err(format!("task panicked: {}", panic_message(&*payload)).into()));
+            thread_shared.finish(result);
+        });
+        Self { shared }
+    }
+
+    // Boards are enumerated in chunks, progress is counted in boards.
+    pub fn enumerate<RT: HandRange + Send + 'static>(
+        community_cards: Cards,
+        hero_hand: Hand,
+        villain_ranges: Vec<RT>,
+    ) -> Self {
+        Self::spawn(0, move |shared| {
+            let start = Instant::now();
+            let Some(mut enumeration) = Enumeration::new(community_cards, hero_hand, &villain_ranges) else {
+                return Err(enumeration_error("enumerate", community_cards, hero_hand, &villain_ranges));
+            };
+            shared.total.store(enumeration.board_count() as u64, Ordering::Relaxed);
+            while !enumeration.is_done() {
+                if shared.cancelled.load(Ordering::Relaxed) {
+                    return Err(Error::Cancelled);
+                }
+                enumeration.run_until(enumeration.cursor() + ENUMERATION_CHUNK);
+                shared.done.store(enumeration.cursor() as u64, Ordering::Relaxed);
+            }
+            let Some(mut result) = enumeration.result() else {
+                return Err("enumerate failed: no showdowns".into());
+            };
+            result.work.elapsed = start.elapsed();
+            Ok(result)
+        })
+    }
+
+    // Progress is counted in rounds.
+    pub fn simulate(
+        community_cards: Cards,
+        hero_hand: Hand,
+        villain_count: usize,
+        rounds: u64,
+    ) -> Self {
+        Self::spawn(rounds, move |shared| {
+            let mut rng = SmallRng::from_entropy();
+            let result = Equity::simulate_with_progress(
+                community_cards,
+                hero_hand,
+                villain_count,
+                rounds,
+                &mut rng,
+                |done| {
+                    shared.done.store(done, Ordering::Relaxed);
+                    !shared.cancelled.load(Ordering::Relaxed)
+                },
+            );
+            if shared.cancelled.load(Ordering::Relaxed) {
+                return Err(Error::Cancelled);
+            }
+            shared.done.store(rounds, Ordering::Relaxed);
+            result.ok_or_else(|| Error::usage("simulate failed: invalid input"))
+        })
+    }
+
+    pub fn handle(&self) -> TaskHandle {
+        TaskHandle { shared: Arc::clone(&self.shared) }
+    }
+}
+
+impl TaskHandle {
+    // The task finishes with Error::Cancelled soon after.
+    pub fn cancel(&self) {
+        self.shared.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn progress(&self) -> Progress {
+        Progress {
+            done: self.shared.done.load(Ordering::Relaxed),
+            total: self.shared.total.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.shared.finished.load(Ordering::Relaxed)
+    }
+}
+
+impl Future for EquityTask {
+    type Output = Result<EquityResult>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.shared.state.lock().unwrap();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            },
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown payload"
+    }
+}
+
+struct ThreadWaker(thread::Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+// Runs a future to completion on the current thread, for callers without an executor.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::range::RangeTable;
+
+    use super::*;
+
+    fn spot() -> (Cards, Hand, Vec<RangeTable>) {
+        let ranges = vec![RangeTable::parse("QQ+,AKs").unwrap(), RangeTable::parse("TT+,AQs+").unwrap()];
+        ("AsTd3h".parse().unwrap(), "AhTh".parse().unwrap(), ranges)
+    }
+
+    #[test]
+    fn enumerate_matches_equity() {
+        let (community_cards, hero_hand, villain_ranges) = spot();
+        let expected = Equity::enumerate(community_cards, hero_hand, &villain_ranges).unwrap();
+        let task = EquityTask::enumerate(community_cards, hero_hand, villain_ranges);
+        let result = block_on(task).unwrap();
+        assert_eq!(result.equities, expected.equities);
+    }
+
+    #[test]
+    fn progress_reaches_board_count() {
+        let (community_cards, hero_hand, villain_ranges) = spot();
+        let boards = Enumeration::new(community_cards, hero_hand, &villain_ranges).unwrap().board_count();
+        let task = EquityTask::enumerate(community_cards, hero_hand, villain_ranges);
+        let handle = task.handle();
+        block_on(task).unwrap();
+        assert!(handle.is_finished());
+        let progress = handle.progress();
+        assert_eq!((progress.done, progress.total), (boards as u64, boards as u64));
+    }
+
+    #[test]
+    fn cancel_stops_the_task() {
+        // About 1.7 million boards, far more than run before the cancellation is seen.
+        let task = EquityTask::enumerate(Cards::EMPTY, "AhKh".parse().unwrap(), vec![RangeTable::full()]);
+        task.handle().cancel();
+        assert!(matches!(block_on(task), Err(Error::Cancelled)));
+    }
+
+    #[test]
+    fn panic_finishes_with_an_error() {
+        let task = EquityTask::spawn(0, |_| panic!("boom"));
+        match block_on(task) {
+            Err(Error::Other(message)) => assert_eq!(message, "task panicked: boom"),
+            result => panic!("expected the panic as an error, got {:?}", result.map(|result| result.equities)),
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use core::fmt;
+
+use crate::cards::{Cards, HandRanking, Score};
+use crate::equity::boards_of_size;
+use crate::equivalence_class::{EquivalenceClass, CATEGORY_CLASS_COUNTS};
+use crate::evaluator::Evaluator;
+
+// Number of 5 card hands of every category out of all 2598960,
+// indexed by HandRanking::category (high card to royal flush).
+pub const FIVE_CARD_CATEGORY_COUNTS: [u64; HandRanking::CATEGORY_COUNT] = [
+    1302540,
+    1098240,
+    123552,
+    54912,
+    10200,
+    5108,
+    3744,
+    624,
+    36,
+    4,
+];
+
+// An evaluator's category of every 5 card hand compared against the known counts.
+pub struct CategoryCensus {
+    pub counts: [u64; HandRanking::CATEGORY_COUNT],
+}
+
+impl fmt::Display for CategoryCensus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", if self.passed() { "ok" } else { "FAILED" })?;
+        let mismatches = HandRanking::CATEGORY_NAMES.iter()
+            .zip(self.counts.iter().zip(FIVE_CARD_CATEGORY_COUNTS))
+            .filter(|(_, (count, expected))| **count != *expected);
+        for (name, (count, expected)) in mismatches {
+            write!(f, " {}={} expected={}", name, count, expected)?;
+        }
+        Ok(())
+    }
+}
+
+impl CategoryCensus {
+    pub fn five_cards<E: Evaluator + ?Sized>(evaluator: &E) -> Self {
+        Self::of_scores(|cards| evaluator.score(cards))
+    }
+
+    // Scored directly by Cards::score_five, which only takes exactly five cards.
+    pub fn five_cards_direct() -> Self {
+        Self::of_scores(Cards::score_five)
+    }
+
+    fn of_scores(score: impl Fn(Cards) -> Score) -> Self {
+        let mut counts = [0; HandRanking::CATEGORY_COUNT];
+        for cards in boards_of_size(Cards::EMPTY, Cards::EMPTY, 5) {
+            counts[score(cards).to_hand_ranking().category()] += 1;
+        }
+        Self { counts }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.counts == FIVE_CARD_CATEGORY_COUNTS
+    }
+}
+
+// The equivalence classes of every category against the known counts,
+// every class has to round trip through its score and representative
+// and every 5 card hand has to be in a class, with the same score when scored directly.
+pub struct ClassCensus {
+    pub counts: [usize; HandRanking::CATEGORY_COUNT],
+    pub mismatches: usize,
+}
+
+impl fmt::Display for ClassCensus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", if self.passed() { "ok" } else { "FAILED" })?;
+        let mismatches = HandRanking::CATEGORY_NAMES.iter()
+            .zip(self.counts.iter().zip(CATEGORY_CLASS_COUNTS))
+            .filter(|(_, (count, expected))| **count != *expected);
+        for (name, (count, expected)) in mismatches {
+            write!(f, " {}={} expected={}", name, count, expected)?;
+        }
+        if self.mismatches != 0 {
+            write!(f, " mismatches={}", self.mismatches)?;
+        }
+        Ok(())
+    }
+}
+
+impl ClassCensus {
+    pub fn equivalence_classes() -> Self {
+        let mut counts = [0; HandRanking::CATEGORY_COUNT];
+        let mut mismatches = 0;
+        for class in EquivalenceClass::all() {
+            counts[class.score().to_hand_ranking().category()] += 1;
+            let round_trip = EquivalenceClass::of_score(class.score()) == Some(class)
+                && EquivalenceClass::of_cards(class.representative()) == class;
+            if !round_trip {
+                mismatches += 1;
+            }
+        }
+        for cards in boards_of_size(Cards::EMPTY, Cards::EMPTY, 5) {
+            if EquivalenceClass::of_score(cards.score()).is_none() || cards.score_five() != cards.score() {
+                mismatches += 1;
+            }
+        }
+        Self { counts, mismatches }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.counts == CATEGORY_CLASS_COUNTS && self.mismatches == 0
+    }
+}
@@ -0,0 +1,231 @@
+use core::fmt;
+
+use rand::Rng;
+
+use crate::card::Card;
+use crate::cards::Cards;
+use crate::equity::Equity;
+use crate::error::Error;
+use crate::hand::Hand;
+use crate::parse_error::ParseError;
+use crate::range::{HandRange, RangeTable};
+use crate::result::Result;
+
+#[derive(Clone)]
+pub struct WeightedRange {
+    hands: Vec<(Hand, f64)>,
+}
+
+impl fmt::Display for WeightedRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut iter = self.hands.iter().peekable();
+        while let Some((hand, weight)) = iter.next() {
+            write!(f, "{}:{}", hand, weight)?;
+            if iter.peek().is_some() {
+                write!(f, ",")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for WeightedRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self, f)
+    }
+}
+
+impl WeightedRange {
+    pub fn empty() -> Self {
+        Self { hands: Vec::new() }
+    }
+
+    pub fn full() -> Self {
+        Self::from_range(&RangeTable::full())
+    }
+
+    pub fn from_range(range: &RangeTable) -> Self {
+        let mut weighted = Self::empty();
+        range.for_each_hand(|hand| weighted.hands.push((hand, 1.0)));
+        weighted
+    }
+
+    // Comma separated range entries with an optional weight suffix,
+    // e.g. "TT+,AKs:0.5,AQo:0.25". Later entries override earlier ones,
+    // a weight of 0 removes the hands.
+    pub fn parse(range_str: &str) -> Result<Self> {
+        let range_str = range_str.trim();
+        let mut range = Self::empty();
+        let mut offset = 0;
+        for def in range_str.split(',') {
+            let (raw_range, weight) = match def.split_once(':') {
+                Some((raw_range, raw_weight)) => {
+                    let weight = match raw_weight.parse::<f64>() {
+                        Ok(weight) if weight.is_finite() && weight >= 0.0 => weight,
+                        _ => return Err(ParseError::new(
+                            "weighted range",
+                            range_str,
+                            offset + raw_range.len() + 1,
+                            raw_weight,
+                            "invalid weight",
+                        ).into()),
+                    };
+                    (raw_range, weight)
+                },
+                None => (def, 1.0),
+            };
+            let table = RangeTable::parse(raw_range).map_err(|err| match err {
+                Error::Parse(err) => err.within("weighted range", range_str, offset).into(),
+                err => err,
+            })?;
+            table.for_each_hand(|hand| range.set(hand, weight));
+            offset += def.len() + 1;
+        }
+        Ok(range)
+    }
+
+    // The hands containing the card, e.g. for a villain with an exposed card.
+    pub fn containing(&self, card: Card) -> Self {
+        let hands = self.hands.iter()
+            .filter(|(hand, _)| hand.to_cards().has(card))
+            .copied()
+            .collect();
+        Self { hands }
+    }
+
+    pub fn set(&mut self, hand: Hand, weight: f64) {
+        assert!(weight.is_finite() && weight >= 0.0);
+        let position = self.hands.iter().position(|(h, _)| *h == hand);
+        match (position, weight == 0.0) {
+            (Some(index), true) => {
+                self.hands.remove(index);
+            },
+            (Some(index), false) => self.hands[index].1 = weight,
+            (None, true) => (),
+            (None, false) => self.hands.push((hand, weight)),
+        }
+    }
+
+    pub fn weight(&self, hand: Hand) -> f64 {
+        self.hands.iter()
+            .find(|(h, _)| *h == hand)
+            .map(|(_, weight)| *weight)
+            .unwrap_or(0.0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hands.is_empty()
+    }
+
+    pub fn count(&self) -> usize {
+        self.hands.len()
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.hands.iter().fold(0.0, |total, (_, weight)| total + weight)
+    }
+
+    pub fn hands(&self) -> impl Iterator<Item = (Hand, f64)> + '_ {
+        self.hands.iter().copied()
+    }
+
+    pub fn live_weight(&self, dead_cards: Cards) -> f64 {
+        self.hands.iter()
+            .filter(|(hand, _)| (hand.to_cards() & dead_cards) == Cards::EMPTY)
+            .fold(0.0, |total, (_, weight)| total + weight)
+    }
+
+    // The hands not blocked by the dead cards (e.g. the board and hero's hand)
+    // with their weights renormalized to sum up to 1, in range order.
+    // None if every hand is blocked.
+    pub fn live(&self, dead_cards: Cards) -> Option<Self> {
+        let live_weight = self.live_weight(dead_cards);
+        if live_weight <= 0.0 {
+            return None;
+        }
+        let hands = self.hands.iter()
+            .filter(|(hand, _)| (hand.to_cards() & dead_cards) == Cards::EMPTY)
+            .map(|(hand, weight)| (*hand, weight / live_weight))
+            .collect();
+        Some(Self { hands })
+    }
+
+    // Keeps the live hands (and their weights) whose equity against
+    // the opponent on the given community cards is at least min_equity.
+    pub fn filter_by_equity(
+        &self,
+        community_cards: Cards,
+        opponent: &impl HandRange,
+        min_equity: f64,
+    ) -> Self {
+        let mut filtered = Self::empty();
+        for (hand, weight) in self.hands() {
+            if (hand.to_cards() & community_cards) != Cards::EMPTY {
+                continue;
+            }
+            let Some(result) = Equity::enumerate(community_cards, hand, &[opponent]) else {
+                continue;
+            };
+            if result.equities[0].equity_percent() >= min_equity {
+                filtered.hands.push((hand, weight));
+            }
+        }
+        filtered
+    }
+
+    pub fn sampler(&self) -> WeightedRangeSampler {
+        let mut total = 0.0;
+        let mut cumulative = Vec::with_capacity(self.hands.len());
+        for (_, weight) in self.hands.iter() {
+            total += weight;
+            cumulative.push(total);
+        }
+        WeightedRangeSampler {
+            hands: self.hands.clone(),
+            cumulative,
+        }
+    }
+}
+
+pub struct WeightedRangeSampler {
+    hands: Vec<(Hand, f64)>,
+    cumulative: Vec<f64>,
+}
+
+impl WeightedRangeSampler {
+    pub fn sample(&self, rng: &mut impl Rng) -> Option<Hand> {
+        let total = *self.cumulative.last()?;
+        let target = rng.gen_range(0.0..total);
+        let index = self.cumulative.partition_point(|weight| *weight <= target);
+        Some(self.hands[index.min(self.hands.len() - 1)].0)
+    }
+
+    pub fn live_weight(&self, dead_cards: Cards) -> f64 {
+        self.hands.iter()
+            .filter(|(hand, _)| (hand.to_cards() & dead_cards) == Cards::EMPTY)
+            .fold(0.0, |total, (_, weight)| total + weight)
+    }
+
+    // Samples only from the hands not blocked by the dead cards,
+    // returns the hand together with the live weight.
+    pub fn sample_live(&self, rng: &mut impl Rng, dead_cards: Cards) -> Option<(Hand, f64)> {
+        let live_weight = self.live_weight(dead_cards);
+        if live_weight <= 0.0 {
+            return None;
+        }
+        let target = rng.gen_range(0.0..live_weight);
+        let mut current = 0.0;
+        let mut last_live = None;
+        for (hand, weight) in self.hands.iter().copied() {
+            if (hand.to_cards() & dead_cards) != Cards::EMPTY {
+                continue;
+            }
+            current += weight;
+            last_live = Some(hand);
+            if target < current {
+                return Some((hand, live_weight));
+            }
+        }
+        last_live.map(|hand| (hand, live_weight))
+    }
+}
@@ -0,0 +1,46 @@
+use crate::cards::Cards;
+use crate::equity::live_combos;
+use crate::hand::Hand;
+use crate::range::{Opponent, RangeTable};
+
+// A villain range backfilled with the fallback range,
+// because the known cards left fewer live combos than the minimum.
+#[derive(Debug, Clone, Copy)]
+pub struct Widening {
+    pub villain: usize,
+    pub live_before: usize,
+    pub live_after: usize,
+}
+
+// Widens every villain range (also the range of a villain with an exposed card)
+// below min_combos live combos to its union with the fallback range.
+// Known hands are never widened.
+pub fn widen_ranges(
+    community_cards: Cards,
+    hero_hand: Hand,
+    villains: &mut [Opponent],
+    min_combos: usize,
+    fallback: &RangeTable,
+) -> Vec<Widening> {
+    let known_cards = community_cards | hero_hand.to_cards();
+    let mut widenings = Vec::new();
+    for (villain, opponent) in villains.iter_mut().enumerate() {
+        if matches!(opponent, Opponent::Hand(_) | Opponent::Partial(_, None)) {
+            continue;
+        }
+        let live_before = live_combos(known_cards, opponent);
+        if live_before >= min_combos {
+            continue;
+        }
+        match opponent {
+            Opponent::Range(range) | Opponent::Partial(_, Some(range)) => *range = range.union(fallback),
+            Opponent::Hand(_) | Opponent::Partial(_, None) => unreachable!(),
+        }
+        widenings.push(Widening {
+            villain,
+            live_before,
+            live_after: live_combos(known_cards, opponent),
+        });
+    }
+    widenings
+}
@@ -0,0 +1,20 @@
+use std::process::Command;
+
+// Every calibration reference (data/references.txt) and the 5 card category census
+// through the verify command, see the Verify section of the README.
+#[test]
+fn verify_references() {
+    let output = Command::new(env!("CARGO_BIN_EXE_poker-equity"))
+        .arg("verify")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr),
+    );
+    assert!(stdout.lines().all(|line| line.contains(": ok")), "{}", stdout);
+    assert!(stdout.contains("five-card-categories: ok"), "{}", stdout);
+}
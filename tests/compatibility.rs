@@ -0,0 +1,62 @@
+use std::fs;
+use std::process::{Command, Output};
+
+// Results written before the schema version (tests/fixtures/*-v0.*) still parse,
+// results of this version parse next to them and newer versions are rejected,
+// see the Results section of the README.
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_poker-equity"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr),
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn old_journal() {
+    let stdout = stdout(&run(&["history", "tests/fixtures/journal-v0.jsonl"]));
+    assert!(stdout.contains("enumerate AsTd3h AhTh QQ+ AKs (enumerate): hero=81.45 villain 1=7.45 villain 2=11.10"), "{}", stdout);
+    assert!(stdout.contains("simulate none 7h2c 1 20000 (simulate): hero=34.33 villain 1=65.67"), "{}", stdout);
+    assert!(stdout.contains("entries: 2/2"), "{}", stdout);
+}
+
+#[test]
+fn old_results() {
+    let stdout = stdout(&run(&["significance", "tests/fixtures/simulate-v0.json", "tests/fixtures/daemon-v0.json"]));
+    assert!(stdout.contains("samples a=100000 b=1081"), "{}", stdout);
+    assert!(stdout.contains("equity a=93.55 b=83.06"), "{}", stdout);
+}
+
+#[test]
+fn current_results() {
+    let output = run(&["--json", "simulate", "AsTd3h", "AhTh", "1", "20000", "--seed", "1"]);
+    let result = stdout(&output);
+    assert!(result.starts_with("{\"result\":{\"schema_version\":1,"), "{}", result);
+    let path = format!("{}/simulate-v1.json", env!("CARGO_TARGET_TMPDIR"));
+    fs::write(&path, &result).unwrap();
+    let stdout = stdout(&run(&["significance", &path, "tests/fixtures/simulate-v0.json"]));
+    assert!(stdout.contains("samples a=20000 b=100000"), "{}", stdout);
+}
+
+#[test]
+fn newer_results() {
+    let path = format!("{}/simulate-v2.json", env!("CARGO_TARGET_TMPDIR"));
+    let result = fs::read_to_string("tests/fixtures/simulate-v0.json").unwrap()
+        .replace("{\"result\":{", "{\"result\":{\"schema_version\":2,");
+    fs::write(&path, result).unwrap();
+    let output = run(&["significance", &path, "tests/fixtures/daemon-v0.json"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("unsupported schema version 2"), "{}", stderr);
+}